@@ -1,14 +1,59 @@
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, HashSet};
+
 use engine_ecs::prelude::*;
+use tracing::{error, info};
 
+use crate::main_schedule::*;
 use crate::plugin::*;
-
-/// 应用程序退出类型
-#[derive(Debug, Clone, PartialEq, Eq)]
+use crate::sub_app::SubApp;
+
+/// 应用程序退出类型。任何系统都可以写入该事件请求退出：
+///
+/// ```ignore
+/// manager.write_event(AppExit::Error(2));
+/// ```
+///
+/// 同一帧写入了多个退出事件时，错误优先于 [`AppExit::Success`]。
+/// `main` 可以直接返回 `AppExit`，`Error(code)` 会成为进程的退出码。
+#[derive(Debug, Clone, PartialEq, Eq, BufferedEvent)]
 pub enum AppExit {
     Success,
     Error(u8),
 }
 
+impl AppExit {
+    /// 退出码为 1 的错误
+    pub const fn error() -> Self {
+        Self::Error(1)
+    }
+
+    /// 退出码为 0 时为 `Success`
+    pub const fn from_code(code: u8) -> Self {
+        match code {
+            0 => Self::Success,
+            code => Self::Error(code),
+        }
+    }
+
+    pub const fn is_success(&self) -> bool {
+        matches!(self, Self::Success)
+    }
+
+    pub const fn is_error(&self) -> bool {
+        matches!(self, Self::Error(_))
+    }
+}
+
+impl std::process::Termination for AppExit {
+    fn report(self) -> std::process::ExitCode {
+        match self {
+            AppExit::Success => std::process::ExitCode::SUCCESS,
+            AppExit::Error(code) => std::process::ExitCode::from(code),
+        }
+    }
+}
+
 impl Default for AppExit {
     fn default() -> Self {
         Self::Success
@@ -18,14 +63,29 @@ impl Default for AppExit {
 /// 简化的应用程序结构
 pub struct App {
     world: World,
+    // 已添加但还没有构建的插件
     plugins: Vec<Box<dyn Plugin>>,
+    // 已构建的插件，按构建顺序排列
+    built_plugins: Vec<Box<dyn Plugin>>,
+    plugin_names: HashSet<String>,
+    plugin_types: HashSet<TypeId>,
+    plugins_built: bool,
     runner: Option<Box<dyn FnOnce(App) -> AppExit>>,
     plugins_state: PluginsState,
+    main_schedule_order: MainScheduleOrder,
+    sub_apps: HashMap<ScheduleKey, SubApp>,
+    // 必须是最后一个字段：动态库在 World 和插件释放之后才能卸载
+    libraries: Vec<Box<dyn Any>>,
 }
 
 impl std::fmt::Debug for App {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "App {{ plugins: {}, state: {:?} }}", self.plugins.len(), self.plugins_state)
+        write!(
+            f,
+            "App {{ plugins: {}, state: {:?} }}",
+            self.plugins.len() + self.built_plugins.len(),
+            self.plugins_state()
+        )
     }
 }
 
@@ -38,18 +98,35 @@ impl Default for App {
 impl App {
     /// 创建新的应用程序
     pub fn new() -> App {
-        App {
+        let mut app = App {
             world: World::new(),
             plugins: Vec::new(),
+            built_plugins: Vec::new(),
+            plugin_names: HashSet::new(),
+            plugin_types: HashSet::new(),
+            plugins_built: false,
             runner: None,
             plugins_state: PluginsState::Adding,
+            main_schedule_order: MainScheduleOrder::default(),
+            sub_apps: HashMap::new(),
+            libraries: Vec::new(),
+        };
+        app.add_event::<AppExit>();
+        app
+    }
+
+    /// 注册事件类型：添加 `Events<E>` 资源，并在每帧的 [`First`] 中交换缓冲区
+    pub fn add_event<E: BufferedEvent>(&mut self) -> &mut Self {
+        if self.world.get_resource::<Events<E>>().is_none() {
+            self.world.add_resource(Events::<E>::default());
+            self.add_systems(First, event_update_system::<E>);
         }
+        self
     }
 
     /// 添加插件
     pub fn add_plugin<P: Plugin + 'static>(&mut self, plugin: P) -> &mut Self {
-        self.plugins.push(Box::new(plugin));
-        self
+        self.add_boxed_plugin(Box::new(plugin))
     }
 
     pub fn add_plugins<M>(&mut self, plugins: impl Plugins<M>) -> &mut Self {
@@ -80,57 +157,205 @@ impl App {
         }
 
         // 检查插件唯一性
-        if plugin.is_unique() {
-            let plugin_name = plugin.name();
-            for existing_plugin in &self.plugins {
-                if existing_plugin.name() == plugin_name {
-                    panic!("Plugin '{}' was added multiple times but is unique", plugin_name);
-                }
-            }
+        if plugin.is_unique() && !self.plugin_names.insert(plugin.name().to_string()) {
+            panic!("Plugin '{}' was added multiple times but is unique", plugin.name());
         }
+        self.plugin_types.insert(plugin_type_id(plugin.as_ref()));
 
         // 添加插件到列表
         self.plugins.push(plugin);
         self
     }
 
+    /// 是否添加过插件 `P`
+    pub fn is_plugin_added<P: Plugin>(&self) -> bool {
+        self.plugin_types.contains(&TypeId::of::<P>())
+    }
+
+    /// 按依赖顺序构建所有已添加的插件。
+    ///
+    /// 构建过程中添加的插件会在之后的批次中构建；依赖必须在所在批次构建之前添加。
+    pub fn build_plugins(&mut self) -> Result<(), PluginError> {
+        while !self.plugins.is_empty() {
+            let order = self.plugin_build_order()?;
+            let mut batch: Vec<Option<Box<dyn Plugin>>> =
+                std::mem::take(&mut self.plugins).into_iter().map(Some).collect();
+            for index in order {
+                let plugin = batch[index].take().unwrap();
+                plugin.build(self);
+                self.built_plugins.push(plugin);
+            }
+        }
+        self.plugins_built = true;
+        Ok(())
+    }
+
+    // 待构建插件的拓扑顺序，没有依赖关系的插件保持添加顺序
+    fn plugin_build_order(&self) -> Result<Vec<usize>, PluginError> {
+        for plugin in &self.plugins {
+            for dependency in plugin.dependencies() {
+                if !self.plugin_types.contains(&dependency.type_id()) {
+                    return Err(PluginError::MissingDependency {
+                        plugin: plugin.name().to_string(),
+                        dependency: dependency.name(),
+                    });
+                }
+            }
+        }
+
+        let mut remaining: Vec<usize> = (0..self.plugins.len()).collect();
+        let mut order = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            let pending: HashSet<TypeId> = remaining
+                .iter()
+                .map(|index| plugin_type_id(self.plugins[*index].as_ref()))
+                .collect();
+            let Some(position) = remaining.iter().position(|index| {
+                self.plugins[*index]
+                    .dependencies()
+                    .iter()
+                    .all(|dependency| !pending.contains(&dependency.type_id()))
+            }) else {
+                return Err(PluginError::DependencyCycle(
+                    remaining
+                        .iter()
+                        .map(|index| self.plugins[*index].name().to_string())
+                        .collect(),
+                ));
+            };
+            order.push(remaining.remove(position));
+        }
+        Ok(order)
+    }
+
     /// 运行应用程序
     pub fn run(&mut self) -> AppExit {
         // 构建所有插件
-        let plugins = std::mem::take(&mut self.plugins);
-        for plugin in plugins {
-            plugin.build(self);
+        if let Err(err) = self.build_plugins() {
+            error!("{}", err);
+            return AppExit::error();
         }
 
-        self.plugins_state = PluginsState::Ready;
-
         // 运行主循环
         if let Some(runner) = self.runner.take() {
             let app = std::mem::replace(self, App::new());
             runner(app)
         } else {
+            if self.plugins_state() == PluginsState::Ready {
+                self.finish();
+                self.cleanup();
+            }
             self.run_once()
         }
     }
 
-    /// 设置一个持续运行的 runner
+    /// 设置一个持续运行的 runner，收到 [`AppExit`] 事件后停止
     pub fn set_loop_runner(&mut self) -> &mut Self {
         self.set_runner(|mut app| {
             loop {
+                if app.plugins_state() == PluginsState::Ready {
+                    app.finish();
+                    app.cleanup();
+                }
                 let exit = app.run_once();
                 if exit != AppExit::Success {
                     return exit;
                 }
+                if let Some(exit) = app.should_exit() {
+                    return exit;
+                }
                 // 可以在这里添加帧率控制或事件处理
             }
         })
     }
 
+    /// 不经过 runner 直接运行一帧：先构建尚未构建的插件，插件准备好后调用 `finish` 和 `cleanup`，
+    /// 再运行一次更新。适合测试或由外部驱动的循环
+    pub fn update(&mut self) -> AppExit {
+        if let Err(err) = self.build_plugins() {
+            error!("{}", err);
+            return AppExit::error();
+        }
+        if self.plugins_state() == PluginsState::Ready {
+            self.finish();
+            self.cleanup();
+        }
+        self.run_once()
+    }
+
     /// 运行一次更新
     pub fn run_once(&mut self) -> AppExit {
-        // 更新 ECS 世界（运行所有系统）
-        self.world.update();
-        AppExit::Success
+        #[cfg(feature = "trace")]
+        let _span = tracing::info_span!("frame").entered();
+        // 按 MainScheduleOrder 的顺序运行每帧的调度
+        let update = ScheduleKey::new(Update);
+        for label in self.main_schedule_order.labels() {
+            self.world.run_schedule(label.clone());
+            if *label == update {
+                // 通过 World::add_system 添加的系统
+                self.world.update();
+            }
+        }
+        for (_label, sub_app) in self.sub_apps.iter_mut() {
+            #[cfg(feature = "trace")]
+            let _span = tracing::info_span!("sub_app", name = ?_label.label()).entered();
+            sub_app.extract(&mut self.world);
+            sub_app.update();
+        }
+        // 只有错误会中断自定义的 runner，成功退出需要通过 should_exit 检查
+        match self.should_exit() {
+            Some(exit @ AppExit::Error(_)) => exit,
+            _ => AppExit::Success,
+        }
+    }
+
+    /// 插入子应用，已存在同名标签时替换并返回旧的子应用。
+    /// 子应用标签和调度标签一样，可以是任何 `Debug + Clone + Eq + Hash` 的类型
+    pub fn insert_sub_app(&mut self, label: impl ScheduleLabel, sub_app: SubApp) -> Option<SubApp> {
+        self.sub_apps.insert(ScheduleKey::new(label), sub_app)
+    }
+
+    pub fn remove_sub_app(&mut self, label: impl ScheduleLabel) -> Option<SubApp> {
+        self.sub_apps.remove(&ScheduleKey::new(label))
+    }
+
+    pub fn get_sub_app(&self, label: impl ScheduleLabel) -> Option<&SubApp> {
+        self.sub_apps.get(&ScheduleKey::new(label))
+    }
+
+    pub fn get_sub_app_mut(&mut self, label: impl ScheduleLabel) -> Option<&mut SubApp> {
+        self.sub_apps.get_mut(&ScheduleKey::new(label))
+    }
+
+    /// 获取子应用，不存在时 panic
+    pub fn sub_app(&self, label: impl ScheduleLabel) -> &SubApp {
+        let key = ScheduleKey::new(label);
+        self.sub_apps
+            .get(&key)
+            .unwrap_or_else(|| panic!("Sub app {:?} does not exist", key.label()))
+    }
+
+    /// 获取子应用的可变引用，不存在时 panic
+    pub fn sub_app_mut(&mut self, label: impl ScheduleLabel) -> &mut SubApp {
+        let key = ScheduleKey::new(label);
+        match self.sub_apps.get_mut(&key) {
+            Some(sub_app) => sub_app,
+            None => panic!("Sub app {:?} does not exist", key.label()),
+        }
+    }
+
+    /// 把系统添加到调度中，例如 `app.add_systems(Update, system)`
+    pub fn add_systems(&mut self, label: impl ScheduleLabel, system: impl IntoSystemConfig) -> &mut Self {
+        self.world.add_system_to(label, system);
+        self
+    }
+
+    pub fn main_schedule_order(&self) -> &MainScheduleOrder {
+        &self.main_schedule_order
+    }
+
+    pub fn main_schedule_order_mut(&mut self) -> &mut MainScheduleOrder {
+        &mut self.main_schedule_order
     }
 
     /// 设置自定义运行器
@@ -142,42 +367,70 @@ impl App {
         self
     }
 
+    /// 保存动态库句柄。动态库中的代码可能被插件、系统和资源引用，
+    /// 所以它们在 App 的其他数据全部释放之后才会被释放
+    pub fn hold_library(&mut self, library: impl Any) -> &mut Self {
+        self.libraries.push(Box::new(library));
+        self
+    }
+
+    /// 获取世界的引用
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
     /// 获取世界的可变引用
     pub fn world_mut(&mut self) -> &mut World {
         &mut self.world
     }
 
-    /// 获取插件状态
+    /// 获取插件状态。所有插件都构建完成并且 `ready` 之后为 `Ready`
     pub fn plugins_state(&self) -> PluginsState {
-        self.plugins_state.clone()
+        match self.plugins_state {
+            PluginsState::Adding
+                if self.plugins_built
+                    && self.plugins.is_empty()
+                    && self.built_plugins.iter().all(|plugin| plugin.ready(self)) =>
+            {
+                PluginsState::Ready
+            }
+            ref state => state.clone(),
+        }
     }
 
-    /// 清理应用程序
+    /// 按构建顺序调用每个插件的 `cleanup`
     pub fn cleanup(&mut self) {
         self.plugins_state = PluginsState::Cleaned;
+        let plugins = std::mem::take(&mut self.built_plugins);
+        for plugin in &plugins {
+            plugin.cleanup(self);
+        }
+        self.built_plugins = plugins;
     }
 
-    /// 完成应用程序
+    /// 按构建顺序调用每个插件的 `finish`
     pub fn finish(&mut self) {
         self.plugins_state = PluginsState::Finished;
+        let plugins = std::mem::take(&mut self.built_plugins);
+        for plugin in &plugins {
+            plugin.finish(self);
+        }
+        self.built_plugins = plugins;
     }
 
+    /// 是否有系统请求退出。存在多个 [`AppExit`] 事件时返回第一个错误
     pub fn should_exit(&self) -> Option<AppExit> {
-        // let mut reader = EventCursor::default();
-
-        // let events = self.world().get_resource::<Events<AppExit>>()?;
-        // let mut events = reader.read(events);
-
-        // if events.len() != 0 {
-        //     return Some(
-        //         events
-        //             .find(|exit| exit.is_error())
-        //             .cloned()
-        //             .unwrap_or(AppExit::Success),
-        //     );
-        // }
-
-        None
+        let events = self.world.get_resource::<Events<AppExit>>()?;
+        if events.is_empty() {
+            return None;
+        }
+        Some(
+            events
+                .iter()
+                .find(|exit| exit.is_error())
+                .cloned()
+                .unwrap_or(AppExit::Success),
+        )
     }
 }
 
@@ -187,7 +440,7 @@ pub struct HelloWorldSystem;
 
 impl System for HelloWorldSystem {
     fn update(&mut self, _manager: &mut EntityManager, _accessor: &mut EntityIdAccessor) {
-        println!("Hello, World!");
+        info!("Hello, World!");
     }
 }
 
@@ -321,20 +574,35 @@ mod tests {
             called: Rc<RefCell<bool>>,
         }
         impl System for DummySystem {
-            fn update(&mut self, _manager: &mut EntityManager, _accessor: &mut EntityIdAccessor) {
+            fn update(&mut self, manager: &mut EntityManager, _accessor: &mut EntityIdAccessor) {
                 self.count += 1;
                 *self.called.borrow_mut() = true;
                 println!("Dummy system called {} times", self.count);
+                if self.count >= 3 {
+                    manager.write_event(AppExit::Success);
+                }
             }
         }
         app.world_mut().add_system(DummySystem {count: 0, called: Rc::clone(&called) });
-        // 设置循环运行器，但run_once总是返回Success，会无限循环，这里人为终止
+        // 循环运行器在系统写入 AppExit 后停止
         app.set_loop_runner();
         let result = app.run();
         assert_eq!(result, AppExit::Success);
         assert!(*called.borrow());
     }
 
+    #[test]
+    fn test_app_exit_error_precedence() {
+        let mut app = App::new();
+        app.add_systems(Update, |manager: &mut EntityManager| {
+            manager.write_event(AppExit::Success);
+            manager.write_event(AppExit::Error(3));
+            manager.write_event(AppExit::Error(4));
+        });
+        app.set_loop_runner();
+        assert_eq!(app.run(), AppExit::Error(3));
+    }
+
     #[test]
     fn test_plugins_state_management() {
         let mut app = App::new();
@@ -362,9 +630,139 @@ mod tests {
 
     #[test]
     fn test_add_plugins_with_vec() {
+        struct CounterPlugin;
+        impl Plugin for CounterPlugin {
+            fn build(&self, _app: &mut App) {}
+
+            fn is_unique(&self) -> bool {
+                false
+            }
+        }
+
         let mut app = App::new();
-        let plugins = vec![HelloWorldPlugin, HelloWorldPlugin];
+        let plugins = vec![CounterPlugin, CounterPlugin];
         app.add_plugins(plugins);
         assert_eq!(app.plugins.len(), 2);
     }
+
+    #[test]
+    #[should_panic(expected = "was added multiple times but is unique")]
+    fn test_add_plugin_checks_uniqueness() {
+        let mut app = App::new();
+        app.add_plugin(HelloWorldPlugin).add_plugin(HelloWorldPlugin);
+    }
+
+    mod lifecycle {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        use crate::prelude::*;
+
+        type Log = Rc<RefCell<Vec<String>>>;
+
+        struct Recorder {
+            log: Log,
+        }
+
+        macro_rules! recording_plugin {
+            ($name:ident $(, $dependency:ident)*) => {
+                struct $name(Log);
+                impl Plugin for $name {
+                    fn build(&self, _app: &mut App) {
+                        self.0.borrow_mut().push(format!("build {}", stringify!($name)));
+                    }
+
+                    fn finish(&self, _app: &mut App) {
+                        self.0.borrow_mut().push(format!("finish {}", stringify!($name)));
+                    }
+
+                    fn cleanup(&self, _app: &mut App) {
+                        self.0.borrow_mut().push(format!("cleanup {}", stringify!($name)));
+                    }
+
+                    fn dependencies(&self) -> Vec<PluginDependency> {
+                        vec![$(PluginDependency::of::<$dependency>()),*]
+                    }
+                }
+            };
+        }
+
+        recording_plugin!(WindowPlugin);
+        recording_plugin!(RenderPlugin, WindowPlugin);
+        recording_plugin!(UiPlugin, RenderPlugin, WindowPlugin);
+
+        impl Recorder {
+            fn new() -> Self {
+                Recorder { log: Rc::new(RefCell::new(Vec::new())) }
+            }
+
+            fn take(&self) -> Vec<String> {
+                std::mem::take(&mut *self.log.borrow_mut())
+            }
+        }
+
+        #[test]
+        fn test_plugins_build_in_dependency_order() {
+            let recorder = Recorder::new();
+            let mut app = App::new();
+            app.add_plugin(UiPlugin(recorder.log.clone()))
+                .add_plugin(RenderPlugin(recorder.log.clone()))
+                .add_plugin(WindowPlugin(recorder.log.clone()));
+            assert_eq!(app.run(), AppExit::Success);
+            assert_eq!(app.plugins_state(), PluginsState::Cleaned);
+            assert_eq!(
+                recorder.take(),
+                [
+                    "build WindowPlugin",
+                    "build RenderPlugin",
+                    "build UiPlugin",
+                    "finish WindowPlugin",
+                    "finish RenderPlugin",
+                    "finish UiPlugin",
+                    "cleanup WindowPlugin",
+                    "cleanup RenderPlugin",
+                    "cleanup UiPlugin",
+                ]
+            );
+        }
+
+        #[test]
+        fn test_missing_plugin_dependency() {
+            let recorder = Recorder::new();
+            let mut app = App::new();
+            app.add_plugin(RenderPlugin(recorder.log.clone()));
+            assert_eq!(
+                app.build_plugins(),
+                Err(PluginError::MissingDependency {
+                    plugin: std::any::type_name::<RenderPlugin>().to_string(),
+                    dependency: std::any::type_name::<WindowPlugin>(),
+                })
+            );
+            assert!(recorder.take().is_empty());
+            assert_eq!(app.run(), AppExit::error());
+        }
+
+        #[test]
+        fn test_finish_waits_until_plugins_are_ready() {
+            struct Loading;
+
+            struct AssetPlugin;
+            impl Plugin for AssetPlugin {
+                fn build(&self, app: &mut App) {
+                    app.world_mut().add_resource(Loading);
+                }
+
+                fn ready(&self, app: &App) -> bool {
+                    app.world().get_resource::<Loading>().is_none()
+                }
+            }
+
+            let mut app = App::new();
+            app.add_plugin(AssetPlugin);
+            app.build_plugins().unwrap();
+            assert_eq!(app.plugins_state(), PluginsState::Adding);
+            app.world_mut().remove_resource::<Loading>();
+            assert_eq!(app.plugins_state(), PluginsState::Ready);
+        }
+    }
 }
@@ -1,9 +1,17 @@
 mod app;
+mod main_schedule;
 mod plugin_group;
 mod plugin;
+mod schedule_runner;
+mod state;
+mod sub_app;
 
 pub mod prelude {
     pub use super::app::*;
+    pub use super::main_schedule::*;
     pub use super::plugin_group::*;
     pub use super::plugin::*;
+    pub use super::schedule_runner::*;
+    pub use super::state::*;
+    pub use super::sub_app::*;
 }
\ No newline at end of file
@@ -0,0 +1,88 @@
+use engine_ecs::prelude::*;
+
+/// 每帧最先运行的调度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct First;
+
+/// 在 [`Update`] 之前运行，例如处理输入
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PreUpdate;
+
+/// 应用状态切换（[`NextState`](crate::prelude::NextState)），运行 OnExit/OnTransition/OnEnter 调度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StateTransition;
+
+/// 运行固定步长的调度（[`FixedPreUpdate`]、[`FixedUpdate`]、[`FixedPostUpdate`]），
+/// 每帧可能运行零次或多次，由 `TimePlugin` 驱动
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RunFixedMainLoop;
+
+/// 固定步长中在 [`FixedUpdate`] 之前运行
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedPreUpdate;
+
+/// 固定步长的游戏逻辑，例如移动和物理，运行速度与帧率无关
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedUpdate;
+
+/// 固定步长中在 [`FixedUpdate`] 之后运行
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedPostUpdate;
+
+/// 游戏逻辑，通过 `World::add_system` 添加的系统也在这里运行
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Update;
+
+/// 在 [`Update`] 之后运行，例如同步窗口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PostUpdate;
+
+/// 每帧最后运行的调度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Last;
+
+/// 每帧按顺序运行的调度
+#[derive(Debug, Clone)]
+pub struct MainScheduleOrder {
+    labels: Vec<ScheduleKey>,
+}
+
+impl Default for MainScheduleOrder {
+    fn default() -> Self {
+        MainScheduleOrder {
+            labels: vec![
+                ScheduleKey::new(First),
+                ScheduleKey::new(PreUpdate),
+                ScheduleKey::new(StateTransition),
+                ScheduleKey::new(RunFixedMainLoop),
+                ScheduleKey::new(Update),
+                ScheduleKey::new(PostUpdate),
+                ScheduleKey::new(Last),
+            ],
+        }
+    }
+}
+
+impl MainScheduleOrder {
+    pub fn labels(&self) -> &[ScheduleKey] {
+        &self.labels
+    }
+
+    /// 在 `after` 之后插入调度，`after` 不存在时添加到末尾
+    pub fn insert_after(&mut self, after: impl ScheduleLabel, label: impl ScheduleLabel) {
+        let after = ScheduleKey::new(after);
+        let index = self
+            .labels
+            .iter()
+            .position(|key| *key == after)
+            .map_or(self.labels.len(), |index| index + 1);
+        self.labels.insert(index, ScheduleKey::new(label));
+    }
+
+    /// 在 `before` 之前插入调度，`before` 不存在时添加到开头
+    pub fn insert_before(&mut self, before: impl ScheduleLabel, label: impl ScheduleLabel) {
+        let before = ScheduleKey::new(before);
+        let index = self.labels.iter().position(|key| *key == before).unwrap_or(0);
+        self.labels.insert(index, ScheduleKey::new(label));
+    }
+}
@@ -1,17 +1,90 @@
+use std::any::{Any, TypeId};
+use std::fmt;
+
 use crate::{app::App, prelude::PluginGroup};
 
-pub trait Plugin {
+/// 插件。App 运行时按依赖顺序调用 `build`，所有插件 `ready` 之后依次调用 `finish` 和 `cleanup`。
+pub trait Plugin: Any {
     fn name(&self) -> &str {
         std::any::type_name::<Self>()
     }
     
     fn build(&self, app: &mut App);
+
+    /// 插件是否已经准备好（例如异步初始化完成），所有插件都准备好后才会调用 `finish`
+    fn ready(&self, _app: &App) -> bool {
+        true
+    }
+
+    /// 所有插件都构建并准备好之后调用，可以读取其他插件添加的资源
+    fn finish(&self, _app: &mut App) {}
+
+    /// 在 `finish` 之后调用，用于清理构建时使用的临时资源
+    fn cleanup(&self, _app: &mut App) {}
+
+    /// 依赖的插件，依赖会先于当前插件构建
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        Vec::new()
+    }
     
     fn is_unique(&self) -> bool {
         true
     }
 }
 
+/// 插件的类型 ID（不是 `Box<dyn Plugin>` 的类型 ID）
+pub(crate) fn plugin_type_id(plugin: &dyn Plugin) -> TypeId {
+    (plugin as &dyn Any).type_id()
+}
+
+/// 插件声明的依赖
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PluginDependency {
+    type_id: TypeId,
+    name: &'static str,
+}
+
+impl PluginDependency {
+    pub fn of<P: Plugin>() -> Self {
+        PluginDependency {
+            type_id: TypeId::of::<P>(),
+            name: std::any::type_name::<P>(),
+        }
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// 构建插件时的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginError {
+    /// 插件依赖的插件没有被添加
+    MissingDependency { plugin: String, dependency: &'static str },
+    /// 插件之间存在循环依赖
+    DependencyCycle(Vec<String>),
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PluginError::MissingDependency { plugin, dependency } => {
+                write!(f, "Plugin '{}' depends on '{}', which was not added", plugin, dependency)
+            }
+            PluginError::DependencyCycle(plugins) => {
+                write!(f, "Plugins have a dependency cycle: {}", plugins.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for PluginError {}
+
 
 /// 插件状态
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -2,17 +2,27 @@ use std::any::TypeId;
 use std::collections::HashMap;
 
 use crate::app::App;
-use crate::plugin::{Plugin, Plugins};
+use crate::plugin::{plugin_type_id, Plugin, Plugins};
 
 
-/// 简化的插件组宏
+/// 简化的插件组宏。用 `#[plugin_group]` 标记的条目是嵌套的插件组：
+///
+/// ```ignore
+/// plugin_group! {
+///     pub struct DefaultPlugins {
+///         #[plugin_group]
+///         MinimalPlugins,
+///         WindowPlugin,
+///     }
+/// }
+/// ```
 #[macro_export]
 macro_rules! plugin_group {
     {
         $(#[$meta:meta])*
         $vis:vis struct $group:ident {
             $(
-                $plugin:ty
+                $(#[$entry_meta:ident])? $entry:path
             ),* $(,)?
         }
     } => {
@@ -24,13 +34,19 @@ macro_rules! plugin_group {
                 let mut group = $crate::prelude::PluginGroupBuilder::start::<Self>();
                 
                 $(
-                    group = group.add(<$plugin>::default());
+                    group = $crate::plugin_group!(@add group, $(#[$entry_meta])? $entry);
                 )*
                 
                 group
             }
         }
     };
+    (@add $builder:ident, #[plugin_group] $group:path) => {
+        $builder.add_group($group)
+    };
+    (@add $builder:ident, $plugin:path) => {
+        $builder.add(<$plugin>::default())
+    };
 }
 
 
@@ -49,6 +65,27 @@ pub trait PluginGroup: Sized {
     fn name() -> String {
         std::any::type_name::<Self>().to_string()
     }
+
+    /// 替换组中插件的配置，例如 `MinimalPlugins.set(winit_plugin)`
+    fn set<T: Plugin>(self, plugin: T) -> PluginGroupBuilder {
+        self.build().set(plugin)
+    }
+
+    fn add<T: Plugin>(self, plugin: T) -> PluginGroupBuilder {
+        self.build().add(plugin)
+    }
+
+    fn add_before<Target: Plugin>(self, plugin: impl Plugin) -> PluginGroupBuilder {
+        self.build().add_before::<Target>(plugin)
+    }
+
+    fn add_after<Target: Plugin>(self, plugin: impl Plugin) -> PluginGroupBuilder {
+        self.build().add_after::<Target>(plugin)
+    }
+
+    fn disable<T: Plugin>(self) -> PluginGroupBuilder {
+        self.build().disable::<T>()
+    }
 }
 
 /// 插件组构建器
@@ -81,45 +118,88 @@ impl PluginGroupBuilder {
             .unwrap_or(false)
     }
 
+    // 插件在组中的位置，不存在时 panic
+    fn index_of<Target: Plugin>(&self) -> usize {
+        let type_id = TypeId::of::<Target>();
+        self.order
+            .iter()
+            .position(|id| *id == type_id)
+            .unwrap_or_else(|| {
+                panic!(
+                    "Plugin '{}' does not exist in group '{}'",
+                    std::any::type_name::<Target>(),
+                    self.group_name
+                )
+            })
+    }
+
+    // 在 `index` 处插入插件；插件已存在时替换并移动到新位置
+    fn upsert(&mut self, index: usize, entry: PluginEntry) {
+        let type_id = plugin_type_id(entry.plugin.as_ref());
+        let mut index = index;
+        if let Some(old_index) = self.order.iter().position(|id| *id == type_id) {
+            self.order.remove(old_index);
+            if old_index < index {
+                index -= 1;
+            }
+        }
+        self.order.insert(index, type_id);
+        self.plugins.insert(type_id, entry);
+    }
+
     /// 添加插件到组末尾
     pub fn add<T: Plugin + 'static>(mut self, plugin: T) -> Self {
-        let type_id = TypeId::of::<T>();
-        
-        // 如果插件已存在，先移除旧的顺序
-        if self.plugins.contains_key(&type_id) {
-            self.order.retain(|&id| id != type_id);
-        }
-        
-        // 添加新插件
-        self.order.push(type_id);
-        self.plugins.insert(type_id, PluginEntry {
+        let index = self.order.len();
+        self.upsert(index, PluginEntry {
             plugin: Box::new(plugin),
             enabled: true,
         });
-        
         self
     }
 
-    /// 添加插件组
+    /// 在 `Target` 之前添加插件
+    pub fn add_before<Target: Plugin>(mut self, plugin: impl Plugin) -> Self {
+        let index = self.index_of::<Target>();
+        self.upsert(index, PluginEntry {
+            plugin: Box::new(plugin),
+            enabled: true,
+        });
+        self
+    }
+
+    /// 在 `Target` 之后添加插件
+    pub fn add_after<Target: Plugin>(mut self, plugin: impl Plugin) -> Self {
+        let index = self.index_of::<Target>() + 1;
+        self.upsert(index, PluginEntry {
+            plugin: Box::new(plugin),
+            enabled: true,
+        });
+        self
+    }
+
+    /// 替换组中已有插件的配置，位置和启用状态不变
+    pub fn set<T: Plugin>(mut self, plugin: T) -> Self {
+        let group_name = &self.group_name;
+        let entry = self.plugins.get_mut(&TypeId::of::<T>()).unwrap_or_else(|| {
+            panic!(
+                "Plugin '{}' does not exist in group '{}'",
+                std::any::type_name::<T>(),
+                group_name
+            )
+        });
+        entry.plugin = Box::new(plugin);
+        self
+    }
+
+    /// 把另一个插件组的插件按顺序添加到末尾，已存在的插件会被替换
     pub fn add_group(mut self, group: impl PluginGroup) -> Self {
-        let other_builder = group.build();
-        
-        // 合并插件
-        for type_id in other_builder.order {
-            if let Some(entry) = other_builder.plugins.get(&type_id) {
-                // 如果插件已存在，先移除旧的顺序
-                if self.plugins.contains_key(&type_id) {
-                    self.order.retain(|&id| id != type_id);
-                }
-                
-                self.order.push(type_id);
-                // 注意：这里我们不能直接移动 Box<dyn Plugin>，所以跳过已存在的插件
-                if !self.plugins.contains_key(&type_id) {
-                    println!("Warning: Cannot move plugin from another group, skipping: {:?}", type_id);
-                }
+        let mut other = group.build();
+        for type_id in other.order {
+            if let Some(entry) = other.plugins.remove(&type_id) {
+                let index = self.order.len();
+                self.upsert(index, entry);
             }
         }
-        
         self
     }
 
@@ -153,14 +233,22 @@ impl PluginGroupBuilder {
     pub fn group_name(&self) -> &str {
         &self.group_name
     }
+
+    /// 按顺序列出插件名称
+    pub fn plugin_names(&self) -> Vec<&str> {
+        self.order
+            .iter()
+            .map(|type_id| self.plugins[type_id].plugin.name())
+            .collect()
+    }
     
-    /// 完成构建，将所有启用的插件添加到应用
-    pub fn finish(self, app: &mut App) {
+    /// 完成构建，将所有启用的插件按顺序添加到应用
+    pub fn finish(mut self, app: &mut App) {
         for type_id in self.order {
-            if let Some(entry) = self.plugins.get(&type_id) {
-                if entry.enabled {
-                    entry.plugin.build(app);
-                }
+            if let Some(entry) = self.plugins.remove(&type_id)
+                && entry.enabled
+            {
+                app.add_boxed_plugin(entry.plugin);
             }
         }
     }
@@ -210,6 +298,34 @@ mod tests {
         }
     }
 
+    #[derive(Default)]
+    struct ConfigPlugin {
+        value: u32,
+    }
+
+    impl Plugin for ConfigPlugin {
+        fn build(&self, app: &mut App) {
+            app.world_mut().add_resource(self.value);
+        }
+    }
+
+    plugin_group! {
+        /// 包含嵌套插件组的测试插件组
+        pub struct NestedPluginGroup {
+            ConfigPlugin,
+            #[plugin_group]
+            TestPluginGroup,
+        }
+    }
+
+    fn short_names(builder: &PluginGroupBuilder) -> Vec<&str> {
+        builder
+            .plugin_names()
+            .into_iter()
+            .map(|name| name.rsplit("::").next().unwrap())
+            .collect()
+    }
+
     #[test]
     fn test_plugin_group_creation() {
         let builder = TestPluginGroup.build();
@@ -236,4 +352,42 @@ mod tests {
         // 这应该会打印插件构建信息
         builder.finish(&mut app);
     }
+
+    #[test]
+    fn test_nested_group_and_ordering() {
+        let builder = NestedPluginGroup.build();
+        assert_eq!(short_names(&builder), ["ConfigPlugin", "TestPlugin1", "TestPlugin2"]);
+
+        let builder = TestPluginGroup
+            .add_before::<TestPlugin1>(ConfigPlugin::default());
+        assert_eq!(short_names(&builder), ["ConfigPlugin", "TestPlugin1", "TestPlugin2"]);
+
+        // 已存在的插件会移动到新位置
+        let builder = builder.add_after::<TestPlugin2>(ConfigPlugin::default());
+        assert_eq!(short_names(&builder), ["TestPlugin1", "TestPlugin2", "ConfigPlugin"]);
+
+        let builder = PluginGroupBuilder::start::<TestPluginGroup>()
+            .add(TestPlugin2)
+            .add_group(NestedPluginGroup);
+        assert_eq!(short_names(&builder), ["ConfigPlugin", "TestPlugin1", "TestPlugin2"]);
+    }
+
+    #[test]
+    fn test_set_replaces_plugin_config() {
+        let mut app = App::new();
+        NestedPluginGroup
+            .disable::<TestPlugin1>()
+            .set(ConfigPlugin { value: 42 })
+            .finish(&mut app);
+        app.build_plugins().unwrap();
+        assert_eq!(app.world().get_resource::<u32>(), Some(&42));
+        assert!(!app.is_plugin_added::<TestPlugin1>());
+        assert!(app.is_plugin_added::<TestPlugin2>());
+    }
+
+    #[test]
+    #[should_panic(expected = "does not exist")]
+    fn test_set_missing_plugin_panics() {
+        let _ = TestPluginGroup.set(ConfigPlugin::default());
+    }
 }
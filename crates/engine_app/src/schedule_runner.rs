@@ -0,0 +1,156 @@
+use std::time::{Duration, Instant};
+
+use crate::app::{App, AppExit};
+use crate::plugin::{Plugin, PluginsState};
+
+/// [`ScheduleRunnerPlugin`] 的运行方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    /// 只运行一帧
+    RunOnce,
+    /// 持续运行直到收到 [`AppExit`]，每帧至少间隔 `wait`（`Duration::ZERO` 表示不等待）
+    Loop { wait: Duration },
+    /// 运行指定的帧数，期间收到 [`AppExit`] 会提前停止
+    RunFrames(u32),
+}
+
+impl Default for RunMode {
+    fn default() -> Self {
+        RunMode::Loop { wait: Duration::ZERO }
+    }
+}
+
+/// 不需要窗口的运行器，适用于服务器、命令行工具和集成测试。
+///
+/// ```ignore
+/// // 以每秒 60 帧运行
+/// app.add_plugins(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(1.0 / 60.0)));
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ScheduleRunnerPlugin {
+    pub run_mode: RunMode,
+}
+
+impl ScheduleRunnerPlugin {
+    pub fn run_once() -> Self {
+        ScheduleRunnerPlugin { run_mode: RunMode::RunOnce }
+    }
+
+    pub fn run_loop(wait: Duration) -> Self {
+        ScheduleRunnerPlugin { run_mode: RunMode::Loop { wait } }
+    }
+
+    pub fn run_frames(frames: u32) -> Self {
+        ScheduleRunnerPlugin { run_mode: RunMode::RunFrames(frames) }
+    }
+}
+
+impl Plugin for ScheduleRunnerPlugin {
+    fn build(&self, app: &mut App) {
+        let run_mode = self.run_mode;
+        app.set_runner(move |mut app: App| {
+            match run_mode {
+                RunMode::RunOnce => tick(&mut app).unwrap_or(AppExit::Success),
+                RunMode::Loop { wait } => loop {
+                    let frame_start = Instant::now();
+                    if let Some(exit) = tick(&mut app) {
+                        return exit;
+                    }
+                    if !wait.is_zero() {
+                        sleep_until(frame_start + wait);
+                    }
+                },
+                RunMode::RunFrames(frames) => {
+                    for _ in 0..frames {
+                        if let Some(exit) = tick(&mut app) {
+                            return exit;
+                        }
+                    }
+                    AppExit::Success
+                }
+            }
+        });
+    }
+}
+
+// 运行一帧，返回本帧请求的退出
+fn tick(app: &mut App) -> Option<AppExit> {
+    // 插件可能需要几帧才能准备好
+    if app.plugins_state() == PluginsState::Ready {
+        app.finish();
+        app.cleanup();
+    }
+    match app.run_once() {
+        exit @ AppExit::Error(_) => Some(exit),
+        AppExit::Success => app.should_exit(),
+    }
+}
+
+// 系统的 sleep 精度通常只有 1 毫秒左右，最后一小段时间用自旋等待
+const SPIN_THRESHOLD: Duration = Duration::from_millis(1);
+
+fn sleep_until(deadline: Instant) {
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            return;
+        }
+        let remaining = deadline - now;
+        if remaining > SPIN_THRESHOLD {
+            std::thread::sleep(remaining - SPIN_THRESHOLD);
+        } else {
+            std::hint::spin_loop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::time::{Duration, Instant};
+
+    use engine_ecs::prelude::*;
+
+    use crate::prelude::*;
+
+    fn counting_app(plugin: ScheduleRunnerPlugin, exit_after: Option<u32>) -> (App, Rc<Cell<u32>>) {
+        let frames = Rc::new(Cell::new(0));
+        let counter = frames.clone();
+        let mut app = App::new();
+        app.add_plugin(plugin);
+        app.add_systems(Update, move |manager: &mut EntityManager| {
+            counter.set(counter.get() + 1);
+            if exit_after == Some(counter.get()) {
+                manager.write_event(AppExit::Error(7));
+            }
+        });
+        (app, frames)
+    }
+
+    #[test]
+    fn test_run_once_and_run_frames() {
+        let (mut app, frames) = counting_app(ScheduleRunnerPlugin::run_once(), None);
+        assert_eq!(app.run(), AppExit::Success);
+        assert_eq!(frames.get(), 1);
+
+        let (mut app, frames) = counting_app(ScheduleRunnerPlugin::run_frames(5), None);
+        assert_eq!(app.run(), AppExit::Success);
+        assert_eq!(frames.get(), 5);
+
+        let (mut app, frames) = counting_app(ScheduleRunnerPlugin::run_frames(5), Some(2));
+        assert_eq!(app.run(), AppExit::Error(7));
+        assert_eq!(frames.get(), 2);
+    }
+
+    #[test]
+    fn test_loop_waits_for_frame_time() {
+        let wait = Duration::from_millis(10);
+        let (mut app, frames) = counting_app(ScheduleRunnerPlugin::run_loop(wait), Some(4));
+        let start = Instant::now();
+        assert_eq!(app.run(), AppExit::Error(7));
+        assert_eq!(frames.get(), 4);
+        // 退出前的 3 帧都等待了完整的帧时间
+        assert!(start.elapsed() >= wait * 3);
+    }
+}
@@ -0,0 +1,400 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use tracing::warn;
+use engine_ecs::prelude::*;
+
+use crate::app::App;
+use crate::main_schedule::StateTransition;
+
+/// 应用状态，例如主菜单、游戏中、暂停。
+///
+/// ```ignore
+/// #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+/// enum GameState {
+///     #[default]
+///     Menu,
+///     Playing,
+/// }
+/// impl States for GameState {}
+///
+/// app.init_state::<GameState>()
+///     .add_systems(OnEnter(GameState::Playing), spawn_level)
+///     .add_systems(Update, move_player.run_if(in_state(GameState::Playing)));
+/// ```
+pub trait States: Debug + Clone + PartialEq + Eq + Hash + Send + Sync + 'static {}
+
+/// 只在父状态为特定值时才存在的子状态，例如只在 `Playing` 中存在的 `Paused`。
+///
+/// 父状态切换后，子状态在同一次 [`StateTransition`] 中创建或移除：
+/// 子状态的 [`OnExit`] 在父状态离开之前运行，[`OnEnter`] 在父状态进入之后运行。
+pub trait SubStates: States {
+    type Source: States;
+
+    /// 父状态为 `source` 时子状态是否存在，存在时返回进入时的初始值
+    fn should_exist(source: &Self::Source) -> Option<Self>;
+}
+
+/// 当前状态，通过 [`NextState`] 修改
+#[derive(Debug)]
+pub struct State<S: States>(S);
+
+impl<S: States> State<S> {
+    pub fn get(&self) -> &S {
+        &self.0
+    }
+}
+
+/// 下一帧的 [`StateTransition`] 中要切换到的状态
+#[derive(Debug, Default)]
+pub enum NextState<S: States> {
+    #[default]
+    Unchanged,
+    Pending(S),
+}
+
+impl<S: States> NextState<S> {
+    pub fn set(&mut self, state: S) {
+        *self = NextState::Pending(state);
+    }
+
+    pub fn reset(&mut self) {
+        *self = NextState::Unchanged;
+    }
+}
+
+/// 进入状态时运行的调度
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OnEnter<S: States>(pub S);
+
+/// 离开状态时运行的调度
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OnExit<S: States>(pub S);
+
+/// 从 `exited` 切换到 `entered` 时运行的调度，在 [`OnExit`] 之后、[`OnEnter`] 之前
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OnTransition<S: States> {
+    pub exited: S,
+    pub entered: S,
+}
+
+// 父状态切换前调用，移除新的父状态下不再存在的子状态
+struct SubStateExits<S: States>(Vec<fn(&mut World, Option<&S>)>);
+
+/// 离开该状态时自动销毁实体
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateScoped<S: States>(pub S);
+
+impl<S: States> Component for StateScoped<S> {}
+
+/// 运行条件：当前状态等于 `state`
+pub fn in_state<S: States>(state: S) -> impl FnMut(&EntityManager) -> bool + 'static {
+    move |manager: &EntityManager| {
+        manager
+            .get_resource::<State<S>>()
+            .is_some_and(|current| *current.get() == state)
+    }
+}
+
+impl App {
+    /// 以默认值初始化状态
+    pub fn init_state<S: States + Default>(&mut self) -> &mut Self {
+        self.insert_state(S::default())
+    }
+
+    /// 以指定值初始化状态，初始状态的 [`OnEnter`] 在第一帧的 [`StateTransition`] 中运行
+    pub fn insert_state<S: States>(&mut self, state: S) -> &mut Self {
+        if self.world().get_resource::<NextState<S>>().is_some() {
+            warn!("State {} is already initialized", std::any::type_name::<S>());
+            return self;
+        }
+        self.init_state_resources::<S>();
+        self.world_mut().add_resource(State(state));
+
+        let mut started = false;
+        self.add_systems(
+            StateTransition,
+            SystemConfig::exclusive(move |world: &mut World| {
+                if !started {
+                    started = true;
+                    let initial = world.get_resource::<State<S>>().unwrap().get().clone();
+                    run_transition(world, None, Some(initial));
+                }
+                apply_state_transition::<S>(world);
+            }),
+        )
+    }
+
+    /// 添加子状态，父状态必须先通过 [`App::init_state`] 初始化
+    pub fn add_sub_state<S: SubStates>(&mut self) -> &mut Self {
+        if self.world().get_resource::<NextState<S::Source>>().is_none() {
+            panic!(
+                "Sub state {} requires state {} to be initialized first",
+                std::any::type_name::<S>(),
+                std::any::type_name::<S::Source>()
+            );
+        }
+        if self.world().get_resource::<NextState<S>>().is_some() {
+            warn!("State {} is already initialized", std::any::type_name::<S>());
+            return self;
+        }
+        self.init_state_resources::<S>();
+        let exits = self.world_mut().get_resource_mut::<SubStateExits<S::Source>>().unwrap();
+        exits.0.push(exit_sub_state::<S>);
+        self.add_systems(
+            StateTransition,
+            SystemConfig::exclusive(apply_sub_state_transition::<S>),
+        )
+    }
+
+    fn init_state_resources<S: States>(&mut self) {
+        let world = self.world_mut();
+        world.register_component::<StateScoped<S>>();
+        world.add_resource(NextState::<S>::Unchanged);
+        world.add_resource(SubStateExits::<S>(Vec::new()));
+    }
+}
+
+// 取出 NextState 中等待切换的状态
+fn take_next_state<S: States>(world: &mut World) -> Option<S> {
+    match std::mem::take(world.get_resource_mut::<NextState<S>>()?) {
+        NextState::Pending(state) => Some(state),
+        NextState::Unchanged => None,
+    }
+}
+
+fn apply_state_transition<S: States>(world: &mut World) {
+    let Some(entered) = take_next_state::<S>(world) else {
+        return;
+    };
+    let Some(exited) = world.get_resource::<State<S>>().map(|state| state.get().clone()) else {
+        return;
+    };
+    // 切换到当前状态不会触发任何调度
+    if exited == entered {
+        return;
+    }
+    // 子状态离开时父状态还没有改变
+    exit_sub_states(world, Some(&entered));
+    world.get_resource_mut::<State<S>>().unwrap().0 = entered.clone();
+    run_transition(world, Some(exited), Some(entered));
+}
+
+// 父状态将切换到 `source`（`None` 表示父状态本身被移除）
+fn exit_sub_states<S: States>(world: &mut World, source: Option<&S>) {
+    let Some(exits) = world.get_resource::<SubStateExits<S>>().map(|exits| exits.0.clone()) else {
+        return;
+    };
+    for exit in exits {
+        exit(world, source);
+    }
+}
+
+fn exit_sub_state<S: SubStates>(world: &mut World, source: Option<&S::Source>) {
+    if source.and_then(S::should_exist).is_some() {
+        return;
+    }
+    let Some(current) = world.get_resource::<State<S>>().map(|state| state.get().clone()) else {
+        return;
+    };
+    take_next_state::<S>(world);
+    exit_sub_states::<S>(world, None);
+    run_transition(world, Some(current), None);
+    world.remove_resource::<State<S>>();
+}
+
+fn apply_sub_state_transition<S: SubStates>(world: &mut World) {
+    let should_exist = world
+        .get_resource::<State<S::Source>>()
+        .and_then(|source| S::should_exist(source.get()));
+    let current = world.get_resource::<State<S>>().map(|state| state.get().clone());
+
+    match (current, should_exist) {
+        (Some(_), Some(_)) => apply_state_transition::<S>(world),
+        // 通常已经在父状态切换前移除
+        (Some(_), None) => exit_sub_state::<S>(world, None),
+        (None, Some(initial)) => {
+            take_next_state::<S>(world);
+            world.add_resource(State(initial.clone()));
+            run_transition(world, None, Some(initial));
+        }
+        // 子状态不存在时忽略对它的修改
+        (None, None) => {
+            take_next_state::<S>(world);
+        }
+    }
+}
+
+fn run_transition<S: States>(world: &mut World, exited: Option<S>, entered: Option<S>) {
+    if let Some(exited) = &exited {
+        world.run_schedule(OnExit(exited.clone()));
+        despawn_state_scoped(world, exited);
+    }
+    if let (Some(exited), Some(entered)) = (&exited, &entered) {
+        world.run_schedule(OnTransition {
+            exited: exited.clone(),
+            entered: entered.clone(),
+        });
+    }
+    if let Some(entered) = entered {
+        world.run_schedule(OnEnter(entered));
+    }
+}
+
+fn despawn_state_scoped<S: States>(world: &mut World, exited: &S) {
+    let entities: Vec<usize> = world
+        .query_with_entities::<StateScoped<S>>()
+        .into_iter()
+        .filter(|(_, scoped)| scoped.0 == *exited)
+        .map(|(entity_id, _)| entity_id)
+        .collect();
+    for entity_id in entities {
+        world.remove_entity(entity_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::prelude::*;
+    use engine_ecs::prelude::*;
+
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+    enum GameState {
+        #[default]
+        Menu,
+        Playing,
+    }
+    impl States for GameState {}
+
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+    enum Paused {
+        #[default]
+        Running,
+        Paused,
+    }
+    impl States for Paused {}
+    impl SubStates for Paused {
+        type Source = GameState;
+
+        fn should_exist(source: &GameState) -> Option<Self> {
+            (*source == GameState::Playing).then_some(Paused::Running)
+        }
+    }
+
+    struct Player;
+    impl Component for Player {}
+
+    fn set_state<S: States>(app: &mut App, state: S) {
+        app.world_mut().get_resource_mut::<NextState<S>>().unwrap().set(state);
+    }
+
+    #[test]
+    fn test_state_transitions() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut app = App::new();
+        app.init_state::<GameState>();
+
+        for (label, name) in [
+            (ScheduleKey::new(OnEnter(GameState::Menu)), "enter menu"),
+            (ScheduleKey::new(OnExit(GameState::Menu)), "exit menu"),
+            (
+                ScheduleKey::new(OnTransition {
+                    exited: GameState::Menu,
+                    entered: GameState::Playing,
+                }),
+                "menu -> playing",
+            ),
+            (ScheduleKey::new(OnEnter(GameState::Playing)), "enter playing"),
+        ] {
+            let log = log.clone();
+            app.add_systems(label, move |_: &mut EntityManager| log.borrow_mut().push(name));
+        }
+        let frames = Rc::new(RefCell::new(0));
+        let counter = frames.clone();
+        app.add_systems(
+            Update,
+            (move |_: &mut EntityManager| *counter.borrow_mut() += 1)
+                .run_if(in_state(GameState::Playing)),
+        );
+
+        app.run_once();
+        assert_eq!(*log.borrow(), vec!["enter menu"]);
+        assert_eq!(*frames.borrow(), 0);
+
+        set_state(&mut app, GameState::Playing);
+        app.run_once();
+        assert_eq!(
+            *log.borrow(),
+            vec!["enter menu", "exit menu", "menu -> playing", "enter playing"]
+        );
+        // 状态切换在 Update 之前完成，同一帧内条件就已满足
+        assert_eq!(*frames.borrow(), 1);
+        assert_eq!(app.world().get_resource::<State<GameState>>().unwrap().get(), &GameState::Playing);
+    }
+
+    #[test]
+    fn test_state_scoped_and_sub_states() {
+        let mut app = App::new();
+        app.init_state::<GameState>().add_sub_state::<Paused>();
+        app.add_systems(OnEnter(GameState::Playing), |manager: &mut EntityManager| {
+            manager.spawn((Player, StateScoped(GameState::Playing)));
+        });
+
+        app.run_once();
+        assert!(app.world().get_resource::<State<Paused>>().is_none());
+
+        set_state(&mut app, GameState::Playing);
+        app.run_once();
+        assert_eq!(app.world_mut().query::<Player>().len(), 1);
+        assert_eq!(app.world().get_resource::<State<Paused>>().unwrap().get(), &Paused::Running);
+
+        set_state(&mut app, Paused::Paused);
+        app.run_once();
+        assert_eq!(app.world().get_resource::<State<Paused>>().unwrap().get(), &Paused::Paused);
+
+        set_state(&mut app, GameState::Menu);
+        app.run_once();
+        assert!(app.world_mut().query::<Player>().is_empty());
+        assert!(app.world().get_resource::<State<Paused>>().is_none());
+    }
+
+    #[test]
+    fn test_sub_state_transition_order() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut app = App::new();
+        app.init_state::<GameState>().add_sub_state::<Paused>();
+
+        let schedules: [(ScheduleKey, &'static str); 4] = [
+            (ScheduleKey::new(OnEnter(GameState::Playing)), "enter playing"),
+            (ScheduleKey::new(OnExit(GameState::Playing)), "exit playing"),
+            (ScheduleKey::new(OnEnter(Paused::Running)), "enter running"),
+            (ScheduleKey::new(OnExit(Paused::Running)), "exit running"),
+        ];
+        for (label, name) in schedules {
+            let log = log.clone();
+            app.add_systems(label, move |manager: &mut EntityManager| {
+                let parent = manager.get_resource::<State<GameState>>().unwrap().get().clone();
+                log.borrow_mut().push((name, parent));
+            });
+        }
+
+        app.run_once();
+        set_state(&mut app, GameState::Playing);
+        app.run_once();
+        set_state(&mut app, GameState::Menu);
+        app.run_once();
+        assert_eq!(
+            *log.borrow(),
+            vec![
+                ("enter playing", GameState::Playing),
+                ("enter running", GameState::Playing),
+                // 子状态离开时父状态仍然是 Playing
+                ("exit running", GameState::Playing),
+                ("exit playing", GameState::Menu),
+            ]
+        );
+    }
+}
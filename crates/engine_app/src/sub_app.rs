@@ -0,0 +1,147 @@
+use engine_ecs::prelude::*;
+
+type ExtractFn = Box<dyn FnMut(&mut World, &mut World)>;
+
+/// 拥有独立 [`World`] 和调度的子应用，例如渲染世界。
+///
+/// 每帧主应用的调度运行完成后，先调用 `extract` 从主世界复制需要的数据，
+/// 再运行子应用的更新调度。子应用的系统不会借用主世界的数据。
+///
+/// ```ignore
+/// let mut render_app = SubApp::new();
+/// render_app
+///     .set_extract(|main_world, render_world| { /* 复制 Sprite、Transform */ })
+///     .set_update_schedule(Render)
+///     .add_systems(Render, draw_system);
+/// app.insert_sub_app(RenderApp, render_app);
+/// ```
+pub struct SubApp {
+    world: World,
+    update_schedule: Option<ScheduleKey>,
+    extract: Option<ExtractFn>,
+}
+
+impl std::fmt::Debug for SubApp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubApp")
+            .field("update_schedule", &self.update_schedule)
+            .field("has_extract", &self.extract.is_some())
+            .finish()
+    }
+}
+
+impl Default for SubApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SubApp {
+    pub fn new() -> Self {
+        SubApp {
+            world: World::new(),
+            update_schedule: None,
+            extract: None,
+        }
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    /// 把系统添加到子应用的调度中
+    pub fn add_systems(&mut self, label: impl ScheduleLabel, system: impl IntoSystemConfig) -> &mut Self {
+        self.world.add_system_to(label, system);
+        self
+    }
+
+    /// 设置每帧运行的调度，未设置时 [`SubApp::update`] 什么也不做
+    pub fn set_update_schedule(&mut self, label: impl ScheduleLabel) -> &mut Self {
+        self.update_schedule = Some(ScheduleKey::new(label));
+        self
+    }
+
+    pub fn update_schedule(&self) -> Option<&ScheduleKey> {
+        self.update_schedule.as_ref()
+    }
+
+    /// 设置提取函数，参数依次是主世界和子应用的世界
+    pub fn set_extract(&mut self, extract: impl FnMut(&mut World, &mut World) + 'static) -> &mut Self {
+        self.extract = Some(Box::new(extract));
+        self
+    }
+
+    /// 从主世界提取数据
+    pub fn extract(&mut self, main_world: &mut World) {
+        if let Some(extract) = self.extract.as_mut() {
+            #[cfg(feature = "trace")]
+            let _span = tracing::info_span!("extract").entered();
+            extract(main_world, &mut self.world);
+        }
+    }
+
+    /// 运行一次更新调度
+    pub fn update(&mut self) {
+        if let Some(label) = self.update_schedule.clone() {
+            self.world.try_run_schedule(label);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use engine_ecs::prelude::*;
+
+    use crate::prelude::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct RenderApp;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Render;
+
+    #[derive(Debug, Default)]
+    struct ExtractedPositions(Vec<i32>);
+
+    #[derive(Debug, Default)]
+    struct DrawnFrames(Vec<i32>);
+
+    #[test]
+    fn test_sub_app_extracts_and_updates_each_frame() {
+        let mut app = App::new();
+        app.world_mut().add_resource(0i32);
+        app.add_systems(Update, |manager: &mut EntityManager| {
+            *manager.get_resource_mut::<i32>().unwrap() += 1;
+        });
+
+        let mut render_app = SubApp::new();
+        render_app.world_mut().add_resource(ExtractedPositions::default());
+        render_app.world_mut().add_resource(DrawnFrames::default());
+        render_app
+            .set_extract(|main_world, render_world| {
+                let value = *main_world.get_resource::<i32>().unwrap();
+                render_world.get_resource_mut::<ExtractedPositions>().unwrap().0 = vec![value];
+            })
+            .set_update_schedule(Render)
+            .add_systems(Render, |manager: &mut EntityManager| {
+                let extracted = manager.get_resource::<ExtractedPositions>().unwrap().0.clone();
+                manager.get_resource_mut::<DrawnFrames>().unwrap().0.extend(extracted);
+            });
+        assert!(app.insert_sub_app(RenderApp, render_app).is_none());
+
+        app.run_once();
+        app.run_once();
+
+        let render_world = app.sub_app(RenderApp).world();
+        assert_eq!(render_world.get_resource::<DrawnFrames>().unwrap().0, [1, 2]);
+        // 子应用的资源不会出现在主世界
+        assert!(app.world().get_resource::<DrawnFrames>().is_none());
+
+        assert!(app.remove_sub_app(RenderApp).is_some());
+        assert!(app.get_sub_app(RenderApp).is_none());
+    }
+}
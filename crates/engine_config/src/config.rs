@@ -0,0 +1,298 @@
+use std::any::TypeId;
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::path::{Path, PathBuf};
+
+use engine_ecs::prelude::*;
+use toml_edit::{Item, TableLike};
+use tracing::warn;
+
+use super::settings::Settings;
+use super::value::ConfigValue;
+
+/// 环境变量的默认前缀，`ENGINE__WINDOW__WIDTH=1024` 对应 `window.width = 1024`
+pub const CONFIG_ENV_PREFIX: &str = "ENGINE__";
+
+/// 配置值的来源，优先级从低到高
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// 代码中的默认值
+    Default,
+    File(PathBuf),
+    /// 环境变量名
+    Env(String),
+    /// 命令行的 `--set`
+    Cli,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Default => f.write_str("default"),
+            ConfigSource::File(path) => write!(f, "{}", path.display()),
+            ConfigSource::Env(name) => write!(f, "env {}", name),
+            ConfigSource::Cli => f.write_str("--set"),
+        }
+    }
+}
+
+/// 一个配置项的值和来源
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigEntry {
+    pub value: ConfigValue,
+    pub source: ConfigSource,
+}
+
+/// 加载或应用配置时的错误
+#[derive(Debug)]
+pub enum ConfigError {
+    /// 无法读取配置文件
+    Io { path: PathBuf, source: std::io::Error },
+    /// 配置文件不是有效的 TOML，或使用了不支持的结构（例如表数组）
+    Parse { source: ConfigSource, message: String },
+    /// 命令行参数格式错误
+    InvalidArgument(String),
+    /// 没有任何配置类型使用的键，通常是拼写错误
+    UnknownKey { key: String, source: ConfigSource },
+    /// 值的类型与配置项不匹配
+    InvalidValue {
+        key: String,
+        expected: &'static str,
+        found: ConfigValue,
+        source: ConfigSource,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(f, "Failed to read config file '{}': {}", path.display(), source)
+            }
+            ConfigError::Parse { source, message } => write!(f, "Failed to parse config from {}: {}", source, message),
+            ConfigError::InvalidArgument(argument) => {
+                write!(f, "Invalid config argument '{}', expected `--set key=value`", argument)
+            }
+            ConfigError::UnknownKey { key, source } => write!(f, "Unknown config key '{}' (from {})", key, source),
+            ConfigError::InvalidValue {
+                key,
+                expected,
+                found,
+                source,
+            } => write!(
+                f,
+                "Config key '{}' expects {}, found {} {} (from {})",
+                key,
+                expected,
+                found.type_name(),
+                found,
+                source
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+// 已注册的配置类型
+struct RegisteredSection {
+    keys: &'static [&'static str],
+    // 应用配置之后的值，用于输出
+    values: Vec<(&'static str, ConfigValue)>,
+}
+
+type Applier = Box<dyn Fn(&mut World)>;
+
+/// 合并后的配置，键使用 `.` 分隔，例如 `window.width`。
+///
+/// 后加载的值覆盖先加载的值，[`ConfigPlugin`](crate::ConfigPlugin) 依次加载配置文件、环境变量和命令行参数。
+#[derive(Default)]
+pub struct Config {
+    entries: BTreeMap<String, ConfigEntry>,
+    sections: BTreeMap<&'static str, RegisteredSection>,
+    errors: Vec<ConfigError>,
+    // 重新加载配置后，用来重新生成已注册的配置资源
+    pub(crate) appliers: Vec<(TypeId, Applier)>,
+}
+
+impl Config {
+    pub fn set(&mut self, key: impl Into<String>, value: ConfigValue, source: ConfigSource) {
+        self.entries.insert(key.into(), ConfigEntry { value, source });
+    }
+
+    pub fn get(&self, key: &str) -> Option<&ConfigEntry> {
+        self.entries.get(key)
+    }
+
+    /// 所有加载的配置项，按键排序
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &ConfigEntry)> {
+        self.entries.iter().map(|(key, entry)| (key.as_str(), entry))
+    }
+
+    /// 加载 TOML 配置文件，文件不存在时返回 `Ok(false)`
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> Result<bool, ConfigError> {
+        let path = path.as_ref();
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(source) => {
+                return Err(ConfigError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                });
+            }
+        };
+        self.load_toml(&text, ConfigSource::File(path.to_path_buf()))?;
+        Ok(true)
+    }
+
+    /// 加载 TOML 文本，嵌套的表展开为 `.` 分隔的键
+    pub fn load_toml(&mut self, text: &str, source: ConfigSource) -> Result<(), ConfigError> {
+        let document = text.parse::<toml_edit::DocumentMut>().map_err(|err| ConfigError::Parse {
+            source: source.clone(),
+            message: err.to_string(),
+        })?;
+        self.load_table("", document.as_table(), &source)
+    }
+
+    fn load_table(&mut self, prefix: &str, table: &dyn TableLike, source: &ConfigSource) -> Result<(), ConfigError> {
+        for (key, item) in table.iter() {
+            let key = if prefix.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match item {
+                Item::None => {}
+                Item::Table(table) => self.load_table(&key, table, source)?,
+                Item::Value(toml_edit::Value::InlineTable(table)) => self.load_table(&key, table, source)?,
+                Item::Value(value) => match ConfigValue::from_toml(value) {
+                    Some(value) => self.set(key, value, source.clone()),
+                    None => return Err(unsupported(&key, source)),
+                },
+                Item::ArrayOfTables(_) => return Err(unsupported(&key, source)),
+            }
+        }
+        Ok(())
+    }
+
+    /// 加载以 `prefix` 开头的环境变量，`__` 对应键中的 `.`，键会转为小写
+    pub fn load_env_vars(&mut self, prefix: &str, vars: impl IntoIterator<Item = (String, String)>) {
+        for (name, value) in vars {
+            let Some(key) = name.strip_prefix(prefix) else {
+                continue;
+            };
+            let key = key.to_lowercase().replace("__", ".");
+            self.set(key, ConfigValue::parse(&value), ConfigSource::Env(name));
+        }
+    }
+
+    /// 加载命令行中的 `--set key=value` 和 `--set=key=value`，其他参数会被忽略
+    pub fn load_args<S: AsRef<str>>(&mut self, args: &[S]) -> Result<(), ConfigError> {
+        let mut args = args.iter().map(AsRef::as_ref);
+        while let Some(arg) = args.next() {
+            let assignment = match arg.strip_prefix("--set") {
+                Some("") => args.next().ok_or_else(|| ConfigError::InvalidArgument(arg.to_string()))?,
+                Some(rest) => match rest.strip_prefix('=') {
+                    Some(assignment) => assignment,
+                    None => continue,
+                },
+                None => continue,
+            };
+            let Some((key, value)) = assignment.split_once('=') else {
+                return Err(ConfigError::InvalidArgument(assignment.to_string()));
+            };
+            self.set(key.trim(), ConfigValue::parse(value), ConfigSource::Cli);
+        }
+        Ok(())
+    }
+
+    /// 把配置中 `S::SECTION` 表的值写入 `settings`，类型不匹配的值会被忽略并记录错误
+    pub fn apply<S: Settings>(&mut self, settings: &mut S) {
+        for &key in S::keys() {
+            let full_key = format!("{}.{}", S::SECTION, key);
+            let Some(entry) = self.entries.get(&full_key) else {
+                continue;
+            };
+            if let Err(expected) = settings.set(key, &entry.value) {
+                let error = ConfigError::InvalidValue {
+                    key: full_key,
+                    expected,
+                    found: entry.value.clone(),
+                    source: entry.source.clone(),
+                };
+                warn!("{}", error);
+                self.errors.push(error);
+            }
+        }
+        self.sections.insert(
+            S::SECTION,
+            RegisteredSection {
+                keys: S::keys(),
+                values: settings.values(),
+            },
+        );
+    }
+
+    /// 找出没有被任何已注册配置类型使用的键
+    pub fn validate(&self) -> Vec<ConfigError> {
+        self.entries
+            .iter()
+            .filter(|(key, _)| !self.is_known_key(key))
+            .map(|(key, entry)| ConfigError::UnknownKey {
+                key: key.clone(),
+                source: entry.source.clone(),
+            })
+            .collect()
+    }
+
+    fn is_known_key(&self, key: &str) -> bool {
+        key.rsplit_once('.').is_some_and(|(section, key)| {
+            self.sections
+                .get(section)
+                .is_some_and(|registered| registered.keys.contains(&key))
+        })
+    }
+
+    /// 加载和应用配置时记录的错误
+    pub fn errors(&self) -> &[ConfigError] {
+        &self.errors
+    }
+
+    pub(crate) fn push_error(&mut self, error: ConfigError) {
+        self.errors.push(error);
+    }
+
+    /// 以 TOML 格式输出所有已注册配置类型的最终值，非默认值会注明来源
+    pub fn dump(&self) -> String {
+        let mut output = String::new();
+        for (section, registered) in &self.sections {
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            let _ = writeln!(output, "[{}]", section);
+            for (key, value) in &registered.values {
+                let _ = write!(output, "{} = {}", key, value);
+                if let Some(entry) = self.entries.get(&format!("{}.{}", section, key)) {
+                    let _ = write!(output, " # {}", entry.source);
+                }
+                output.push('\n');
+            }
+        }
+        output
+    }
+}
+
+fn unsupported(key: &str, source: &ConfigSource) -> ConfigError {
+    ConfigError::Parse {
+        source: source.clone(),
+        message: format!("'{}' uses an unsupported value (arrays of tables are not supported)", key),
+    }
+}
@@ -0,0 +1,241 @@
+mod config;
+mod settings;
+mod value;
+
+use std::any::TypeId;
+use std::path::PathBuf;
+
+use engine_app::prelude::*;
+use engine_ecs::prelude::*;
+use tracing::{error, warn};
+
+pub use config::{Config, ConfigEntry, ConfigError, ConfigSource, CONFIG_ENV_PREFIX};
+pub use settings::Settings;
+pub use value::{ConfigValue, SettingValue};
+
+pub mod prelude {
+    pub use super::{settings, Config, ConfigAppExt, ConfigPlugin, ConfigValue, Settings};
+}
+
+/// 默认的配置文件
+pub const DEFAULT_CONFIG_FILE: &str = "engine.toml";
+
+/// 命令行中使用这个参数时，在所有插件构建完成后输出最终的配置
+pub const DUMP_CONFIG_ARG: &str = "--dump-config";
+
+/// 依次加载配置文件、环境变量和命令行的 `--set key=value`，后加载的覆盖先加载的，
+/// 合并结果保存在 [`Config`] 资源中。
+///
+/// 其他插件通过 [`ConfigAppExt::register_settings`] 注册类型化的配置资源。
+/// 所有插件构建完成后会检查未知的键，运行时加上 `--dump-config` 可以查看最终的配置。
+#[derive(Debug, Clone)]
+pub struct ConfigPlugin {
+    /// 按顺序加载的配置文件，不存在的文件会被跳过
+    pub files: Vec<PathBuf>,
+    /// 环境变量前缀，为 `None` 时不读取环境变量
+    pub env_prefix: Option<String>,
+    /// 命令行参数，为 `None` 时使用进程的参数
+    pub args: Option<Vec<String>>,
+    /// 出现错误（例如未知的键）时 panic，默认只输出日志
+    pub strict: bool,
+}
+
+impl Default for ConfigPlugin {
+    fn default() -> Self {
+        ConfigPlugin {
+            files: vec![PathBuf::from(DEFAULT_CONFIG_FILE)],
+            env_prefix: Some(CONFIG_ENV_PREFIX.to_string()),
+            args: None,
+            strict: false,
+        }
+    }
+}
+
+impl ConfigPlugin {
+    fn args(&self) -> Vec<String> {
+        self.args.clone().unwrap_or_else(|| std::env::args().skip(1).collect())
+    }
+
+    /// 按插件的设置加载配置，错误记录在返回的 [`Config`] 中
+    pub fn load(&self) -> Config {
+        let mut config = Config::default();
+        for file in &self.files {
+            if let Err(err) = config.load_file(file) {
+                error!("{}", err);
+                config.push_error(err);
+            }
+        }
+        if let Some(prefix) = &self.env_prefix {
+            config.load_env_vars(prefix, std::env::vars());
+        }
+        if let Err(err) = config.load_args(&self.args()) {
+            error!("{}", err);
+            config.push_error(err);
+        }
+        config
+    }
+}
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        let config = self.load();
+        let world = app.world_mut();
+        // 在这之前注册的配置类型使用的是空配置，需要重新生成
+        let appliers = match world.remove_resource::<Config>() {
+            Some(previous) => previous.appliers,
+            None => Vec::new(),
+        };
+        world.add_resource(config);
+        for (_, apply) in &appliers {
+            apply(world);
+        }
+        world.get_resource_mut::<Config>().unwrap().appliers = appliers;
+    }
+
+    fn finish(&self, app: &mut App) {
+        let config = app.world_mut().get_resource_mut::<Config>().unwrap();
+        for err in config.validate() {
+            warn!("{}", err);
+            config.push_error(err);
+        }
+        if self.strict && !config.errors().is_empty() {
+            let errors: Vec<String> = config.errors().iter().map(ToString::to_string).collect();
+            panic!("Invalid engine configuration:\n{}", errors.join("\n"));
+        }
+        if self.args().iter().any(|arg| arg == DUMP_CONFIG_ARG) {
+            print!("{}", config.dump());
+        }
+    }
+}
+
+/// 在 [`App`] 中注册类型化的配置
+pub trait ConfigAppExt {
+    /// 用配置覆盖 `defaults` 中的值，然后作为资源添加到 World。
+    ///
+    /// [`ConfigPlugin`] 之后添加的配置会重新生成，所以插件的顺序不影响结果。
+    fn register_settings<S: Settings>(&mut self, defaults: S) -> &mut Self;
+}
+
+impl ConfigAppExt for App {
+    fn register_settings<S: Settings>(&mut self, defaults: S) -> &mut Self {
+        let world = self.world_mut();
+        if world.get_resource::<Config>().is_none() {
+            world.add_resource(Config::default());
+        }
+        let apply = move |world: &mut World| {
+            let mut settings = defaults.clone();
+            world.get_resource_mut::<Config>().unwrap().apply(&mut settings);
+            world.add_resource(settings);
+        };
+        apply(world);
+        let appliers = &mut world.get_resource_mut::<Config>().unwrap().appliers;
+        appliers.retain(|(type_id, _)| *type_id != TypeId::of::<S>());
+        appliers.push((TypeId::of::<S>(), Box::new(apply)));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    settings! {
+        /// 测试用的音频配置
+        pub struct AudioSettings("audio") {
+            pub master_volume: f32 = 1.0,
+            pub muted: bool = false,
+            pub device: String = "default".to_string(),
+        }
+    }
+
+    fn config_plugin(args: &[&str]) -> ConfigPlugin {
+        ConfigPlugin {
+            files: Vec::new(),
+            env_prefix: None,
+            args: Some(args.iter().map(ToString::to_string).collect()),
+            strict: false,
+        }
+    }
+
+    #[test]
+    fn test_layers_override_in_order() {
+        let mut config = Config::default();
+        let file = ConfigSource::File("engine.toml".into());
+        config
+            .load_toml("[audio]\nmaster_volume = 0.5\nmuted = true\ndevice = \"hdmi\"", file.clone())
+            .unwrap();
+        config.load_env_vars(
+            CONFIG_ENV_PREFIX,
+            [
+                ("ENGINE__AUDIO__MASTER_VOLUME".to_string(), "0.25".to_string()),
+                ("OTHER".to_string(), "1".to_string()),
+            ],
+        );
+        config.load_args(&["game", "--set", "audio.muted=false"]).unwrap();
+
+        let mut audio = AudioSettings::default();
+        config.apply(&mut audio);
+        assert_eq!(audio.master_volume, 0.25);
+        assert!(!audio.muted);
+        assert_eq!(audio.device, "hdmi");
+        assert_eq!(config.get("audio.device").unwrap().source, file);
+        assert_eq!(config.get("audio.muted").unwrap().source, ConfigSource::Cli);
+
+        assert!(matches!(config.load_args(&["--set", "oops"]), Err(ConfigError::InvalidArgument(_))));
+        assert!(config.load_toml("[audio", file).is_err());
+    }
+
+    #[test]
+    fn test_unknown_keys_and_invalid_values() {
+        let mut app = App::new();
+        app.add_plugin(config_plugin(&[
+            "--set=audio.volume=2",
+            "--set=audio.muted=loud",
+            "--set=video.vsync=true",
+        ]));
+        app.register_settings(AudioSettings::default());
+        app.build_plugins().unwrap();
+        app.finish();
+
+        let config = app.world().get_resource::<Config>().unwrap();
+        let errors: Vec<String> = config.errors().iter().map(ToString::to_string).collect();
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(errors[0].contains("'audio.muted' expects a boolean"));
+        assert!(errors[1].starts_with("Unknown config key 'audio.volume'"));
+        assert!(errors[2].starts_with("Unknown config key 'video.vsync'"));
+        assert!(!app.world().get_resource::<AudioSettings>().unwrap().muted);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown config key 'audio.volume'")]
+    fn test_strict_panics_on_unknown_keys() {
+        let mut app = App::new();
+        app.add_plugin(ConfigPlugin {
+            strict: true,
+            ..config_plugin(&["--set", "audio.volume=2"])
+        });
+        app.register_settings(AudioSettings::default());
+        app.build_plugins().unwrap();
+        app.finish();
+    }
+
+    #[test]
+    fn test_registered_before_plugin_and_dump() {
+        let mut app = App::new();
+        // 插件在 build_plugins 时才构建，所以这里先于配置注册
+        app.register_settings(AudioSettings {
+            device: "speakers".to_string(),
+            ..Default::default()
+        });
+        app.add_plugin(config_plugin(&["--set", "audio.master_volume=0.5"]));
+        app.build_plugins().unwrap();
+
+        let audio = app.world().get_resource::<AudioSettings>().unwrap();
+        assert_eq!(audio.master_volume, 0.5);
+        assert_eq!(audio.device, "speakers");
+        assert_eq!(
+            app.world().get_resource::<Config>().unwrap().dump(),
+            "[audio]\nmaster_volume = 0.5 # --set\nmuted = false\ndevice = \"speakers\"\n"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use super::value::ConfigValue;
+
+/// 一组类型化的配置，对应配置文件中的一个表（例如 `[window]`），注册后作为资源保存在 World 中。
+///
+/// 通常使用 [`settings!`](crate::settings) 定义。
+pub trait Settings: Clone + 'static {
+    /// 配置文件中的表名，也是键的前缀，例如 `window` 对应 `window.width`
+    const SECTION: &'static str;
+
+    /// 所有配置项的名称
+    fn keys() -> &'static [&'static str];
+
+    /// 设置一个配置项，类型不匹配时返回期望的类型
+    fn set(&mut self, key: &str, value: &ConfigValue) -> Result<(), &'static str>;
+
+    /// 所有配置项当前的值，顺序与 [`Settings::keys`] 相同
+    fn values(&self) -> Vec<(&'static str, ConfigValue)>;
+}
+
+/// 定义配置类型，每个字段都要给出默认值：
+///
+/// ```ignore
+/// settings! {
+///     /// 对应配置文件中的 `[audio]`
+///     pub struct AudioSettings("audio") {
+///         /// 主音量
+///         pub master_volume: f32 = 1.0,
+///         pub muted: bool = false,
+///     }
+/// }
+/// ```
+///
+/// 字段类型需要实现 [`SettingValue`](crate::SettingValue)。
+#[macro_export]
+macro_rules! settings {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident($section:literal) {
+            $(
+                $(#[$field_meta:meta])*
+                $field_vis:vis $field:ident: $ty:ty = $default:expr
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq)]
+        $vis struct $name {
+            $(
+                $(#[$field_meta])*
+                $field_vis $field: $ty,
+            )*
+        }
+
+        impl ::std::default::Default for $name {
+            fn default() -> Self {
+                $name {
+                    $($field: $default,)*
+                }
+            }
+        }
+
+        impl $crate::Settings for $name {
+            const SECTION: &'static str = $section;
+
+            fn keys() -> &'static [&'static str] {
+                &[$(stringify!($field)),*]
+            }
+
+            fn set(&mut self, key: &str, value: &$crate::ConfigValue) -> ::std::result::Result<(), &'static str> {
+                match key {
+                    $(
+                        stringify!($field) => {
+                            self.$field = <$ty as $crate::SettingValue>::from_config_value(value)
+                                .ok_or(<$ty as $crate::SettingValue>::EXPECTED)?;
+                            Ok(())
+                        }
+                    )*
+                    _ => Err("a known key"),
+                }
+            }
+
+            fn values(&self) -> ::std::vec::Vec<(&'static str, $crate::ConfigValue)> {
+                ::std::vec![$((stringify!($field), $crate::SettingValue::to_config_value(&self.$field))),*]
+            }
+        }
+    };
+}
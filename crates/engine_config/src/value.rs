@@ -0,0 +1,217 @@
+use std::fmt;
+
+/// 配置中的一个值，对应 TOML 的标量和数组
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigValue {
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<ConfigValue>),
+}
+
+impl ConfigValue {
+    /// 解析环境变量或命令行中的值：能解析为 TOML 值时按 TOML 解析，否则作为字符串，
+    /// 所以 `--set window.title=My Game` 不需要加引号
+    pub fn parse(text: &str) -> ConfigValue {
+        match text.trim().parse::<toml_edit::Value>() {
+            Ok(value) => ConfigValue::from_toml(&value).unwrap_or_else(|| ConfigValue::String(text.to_string())),
+            Err(_) => ConfigValue::String(text.to_string()),
+        }
+    }
+
+    /// 内联表没有对应的值，返回 `None`
+    pub(crate) fn from_toml(value: &toml_edit::Value) -> Option<ConfigValue> {
+        Some(match value {
+            toml_edit::Value::String(value) => ConfigValue::String(value.value().clone()),
+            toml_edit::Value::Integer(value) => ConfigValue::Integer(*value.value()),
+            toml_edit::Value::Float(value) => ConfigValue::Float(*value.value()),
+            toml_edit::Value::Boolean(value) => ConfigValue::Bool(*value.value()),
+            toml_edit::Value::Datetime(value) => ConfigValue::String(value.value().to_string()),
+            toml_edit::Value::Array(array) => {
+                ConfigValue::Array(array.iter().map(ConfigValue::from_toml).collect::<Option<_>>()?)
+            }
+            toml_edit::Value::InlineTable(_) => return None,
+        })
+    }
+
+    /// 值的类型名称，用于错误信息
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ConfigValue::Bool(_) => "boolean",
+            ConfigValue::Integer(_) => "integer",
+            ConfigValue::Float(_) => "float",
+            ConfigValue::String(_) => "string",
+            ConfigValue::Array(_) => "array",
+        }
+    }
+}
+
+/// 按 TOML 语法输出
+impl fmt::Display for ConfigValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigValue::Bool(value) => write!(f, "{}", value),
+            ConfigValue::Integer(value) => write!(f, "{}", value),
+            ConfigValue::Float(value) if value.is_nan() => f.write_str("nan"),
+            ConfigValue::Float(value) if value.is_infinite() => {
+                f.write_str(if *value > 0.0 { "inf" } else { "-inf" })
+            }
+            ConfigValue::Float(value) => write!(f, "{:?}", value),
+            ConfigValue::String(value) => {
+                f.write_str("\"")?;
+                for c in value.chars() {
+                    match c {
+                        '"' => f.write_str("\\\"")?,
+                        '\\' => f.write_str("\\\\")?,
+                        '\n' => f.write_str("\\n")?,
+                        '\r' => f.write_str("\\r")?,
+                        '\t' => f.write_str("\\t")?,
+                        c if c.is_control() => write!(f, "\\u{:04X}", c as u32)?,
+                        c => write!(f, "{}", c)?,
+                    }
+                }
+                f.write_str("\"")
+            }
+            ConfigValue::Array(values) => {
+                f.write_str("[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                f.write_str("]")
+            }
+        }
+    }
+}
+
+/// 可以作为配置项的类型
+pub trait SettingValue: Sized {
+    /// 期望的类型，用于错误信息
+    const EXPECTED: &'static str;
+
+    fn from_config_value(value: &ConfigValue) -> Option<Self>;
+
+    fn to_config_value(&self) -> ConfigValue;
+}
+
+impl SettingValue for bool {
+    const EXPECTED: &'static str = "a boolean";
+
+    fn from_config_value(value: &ConfigValue) -> Option<Self> {
+        match value {
+            ConfigValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn to_config_value(&self) -> ConfigValue {
+        ConfigValue::Bool(*self)
+    }
+}
+
+macro_rules! impl_integer_setting {
+    ($($ty:ty),*) => {
+        $(
+            impl SettingValue for $ty {
+                const EXPECTED: &'static str = concat!("an integer in the range of ", stringify!($ty));
+
+                fn from_config_value(value: &ConfigValue) -> Option<Self> {
+                    match value {
+                        ConfigValue::Integer(value) => <$ty>::try_from(*value).ok(),
+                        _ => None,
+                    }
+                }
+
+                fn to_config_value(&self) -> ConfigValue {
+                    // 超出 i64 范围的值只能以浮点数保存
+                    i64::try_from(*self).map_or(ConfigValue::Float(*self as f64), ConfigValue::Integer)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_setting!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! impl_float_setting {
+    ($($ty:ty),*) => {
+        $(
+            impl SettingValue for $ty {
+                const EXPECTED: &'static str = "a number";
+
+                fn from_config_value(value: &ConfigValue) -> Option<Self> {
+                    match value {
+                        ConfigValue::Float(value) => Some(*value as $ty),
+                        ConfigValue::Integer(value) => Some(*value as $ty),
+                        _ => None,
+                    }
+                }
+
+                fn to_config_value(&self) -> ConfigValue {
+                    ConfigValue::Float(*self as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_float_setting!(f32, f64);
+
+impl SettingValue for String {
+    const EXPECTED: &'static str = "a string";
+
+    /// 环境变量和命令行中的 `1` 或 `true` 也可以作为字符串
+    fn from_config_value(value: &ConfigValue) -> Option<Self> {
+        match value {
+            ConfigValue::String(value) => Some(value.clone()),
+            ConfigValue::Bool(value) => Some(value.to_string()),
+            ConfigValue::Integer(value) => Some(value.to_string()),
+            ConfigValue::Float(value) => Some(value.to_string()),
+            ConfigValue::Array(_) => None,
+        }
+    }
+
+    fn to_config_value(&self) -> ConfigValue {
+        ConfigValue::String(self.clone())
+    }
+}
+
+impl<T: SettingValue> SettingValue for Vec<T> {
+    const EXPECTED: &'static str = "an array";
+
+    fn from_config_value(value: &ConfigValue) -> Option<Self> {
+        match value {
+            ConfigValue::Array(values) => values.iter().map(T::from_config_value).collect(),
+            _ => None,
+        }
+    }
+
+    fn to_config_value(&self) -> ConfigValue {
+        ConfigValue::Array(self.iter().map(T::to_config_value).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(ConfigValue::parse("1024"), ConfigValue::Integer(1024));
+        assert_eq!(ConfigValue::parse("0.5"), ConfigValue::Float(0.5));
+        assert_eq!(ConfigValue::parse("false"), ConfigValue::Bool(false));
+        assert_eq!(ConfigValue::parse("My Game"), ConfigValue::String("My Game".to_string()));
+        assert_eq!(
+            ConfigValue::parse("[1, 2]"),
+            ConfigValue::Array(vec![ConfigValue::Integer(1), ConfigValue::Integer(2)])
+        );
+        assert_eq!(ConfigValue::String("a \"b\"\n".to_string()).to_string(), r#""a \"b\"\n""#);
+        assert_eq!(ConfigValue::Float(1.0).to_string(), "1.0");
+
+        assert_eq!(u8::from_config_value(&ConfigValue::Integer(300)), None);
+        assert_eq!(f32::from_config_value(&ConfigValue::Integer(2)), Some(2.0));
+    }
+}
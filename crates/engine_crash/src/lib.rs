@@ -0,0 +1,296 @@
+mod report;
+
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::panic::{self, PanicHookInfo};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, Once, OnceLock, Weak};
+use std::thread;
+
+use engine_app::prelude::*;
+use engine_ecs::prelude::*;
+use engine_log::{error, RecentLogs};
+
+use report::{Shared, WorldSummary};
+
+pub mod prelude {
+    pub use super::{CrashReportAppExt, CrashReportPlugin, CrashReporter};
+}
+
+/// 安装 panic hook，panic 时把崩溃报告写入 `directory`。
+///
+/// 报告包含 panic 信息和调用栈、正在运行的系统、帧号、实体和组件数量、资源列表、
+/// 最近的日志（需要 [`LogPlugin`](engine_log::LogPlugin)），以及通过
+/// [`CrashReportAppExt::record_crash_input`] 记录的最近几帧输入，用来复现问题。
+///
+/// hook 在进程中只安装一次，只为运行 App 的线程上发生的 panic 写入报告，App 释放后不再写入。
+#[derive(Debug, Clone)]
+pub struct CrashReportPlugin {
+    /// 报告所在的目录，不存在时自动创建
+    pub directory: PathBuf,
+    /// 记录最近多少帧的输入，为 0 时不记录
+    pub input_frames: usize,
+}
+
+impl Default for CrashReportPlugin {
+    fn default() -> Self {
+        CrashReportPlugin {
+            directory: PathBuf::from("crash_reports"),
+            input_frames: 0,
+        }
+    }
+}
+
+impl Plugin for CrashReportPlugin {
+    fn build(&self, app: &mut App) {
+        let shared = Arc::new(Shared {
+            directory: self.directory.clone(),
+            summary: Mutex::new(WorldSummary::default()),
+            input: Mutex::new(VecDeque::with_capacity(self.input_frames)),
+            input_frames: self.input_frames,
+            recent_logs: OnceLock::new(),
+            last_report: Mutex::new(None),
+            thread: Mutex::new(thread::current().id()),
+        });
+        register_reporter(&shared);
+        app.world_mut().add_resource(CrashReporter(shared));
+        app.add_systems(First, crash_summary_system);
+    }
+
+    // LogPlugin 可能在这个插件之后构建
+    fn finish(&self, app: &mut App) {
+        let world = app.world();
+        if let (Some(reporter), Some(recent_logs)) =
+            (world.get_resource::<CrashReporter>(), world.get_resource::<RecentLogs>())
+        {
+            let _ = reporter.0.recent_logs.set(recent_logs.clone());
+        }
+    }
+}
+
+/// 崩溃报告的状态，由 [`CrashReportPlugin`] 添加
+pub struct CrashReporter(Arc<Shared>);
+
+impl CrashReporter {
+    /// 当前帧号，从 1 开始
+    pub fn frame(&self) -> u64 {
+        self.0.frame()
+    }
+
+    /// 最近一次写入的报告
+    pub fn last_report(&self) -> Option<PathBuf> {
+        self.0.last_report.lock().unwrap_or_else(|err| err.into_inner()).clone()
+    }
+
+    /// 记录当前帧的一条输入
+    pub fn record_input(&self, line: impl Into<String>) {
+        if self.0.input_frames > 0 {
+            self.0.record_input(line.into());
+        }
+    }
+}
+
+// 所有存活的 App 的崩溃报告状态，panic hook 通过它找到要写入的报告
+static REPORTERS: Mutex<Vec<Weak<Shared>>> = Mutex::new(Vec::new());
+static INSTALL_HOOK: Once = Once::new();
+
+fn register_reporter(shared: &Arc<Shared>) {
+    INSTALL_HOOK.call_once(install_panic_hook);
+    let mut reporters = REPORTERS.lock().unwrap_or_else(|err| err.into_inner());
+    reporters.retain(|reporter| reporter.strong_count() > 0);
+    reporters.push(Arc::downgrade(shared));
+}
+
+fn install_panic_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let reporters: Vec<Arc<Shared>> = REPORTERS
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .iter()
+            .filter_map(Weak::upgrade)
+            .filter(|shared| shared.runs_on_current_thread())
+            .collect();
+        if !reporters.is_empty() {
+            let backtrace = Backtrace::force_capture();
+            let system = running_system();
+            let message = panic_message(info);
+            for shared in reporters {
+                let report = shared.render(&message, system.as_deref(), &backtrace);
+                match shared.write_report(&report) {
+                    Ok(path) => error!("Crash report written to {}", path.display()),
+                    Err(err) => error!("Failed to write crash report: {}", err),
+                }
+            }
+        }
+        previous(info);
+    }));
+}
+
+fn panic_message(info: &PanicHookInfo<'_>) -> String {
+    let payload = info.payload();
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("<non-string panic payload>");
+    match info.location() {
+        Some(location) => format!("{} at {}", message, location),
+        None => message.to_string(),
+    }
+}
+
+/// 每帧开始时记录 World 的摘要
+pub fn crash_summary_system(manager: &mut EntityManager) {
+    let Some(reporter) = manager.get_resource::<CrashReporter>() else {
+        return;
+    };
+    let shared = reporter.0.clone();
+    let summary = WorldSummary {
+        frame: shared.frame() + 1,
+        entity_count: manager.entity_ids().len(),
+        component_counts: manager
+            .components()
+            .iter()
+            .map(|info| (info.name().to_string(), manager.entity_ids_by_id(info.id()).len()))
+            .collect(),
+        resources: manager.resource_names(),
+    };
+    shared.begin_frame(summary);
+}
+
+// 下一个还没有记录的事件 ID
+struct InputCursor<E> {
+    next_id: usize,
+    marker: PhantomData<fn(E)>,
+}
+
+/// 把新写入的事件 `E` 记录到崩溃报告
+pub fn record_input_system<E: BufferedEvent + Debug>(manager: &mut EntityManager) {
+    let Some(next_id) = manager.get_resource::<InputCursor<E>>().map(|cursor| cursor.next_id) else {
+        return;
+    };
+    let (Some(events), Some(reporter)) = (manager.get_resource::<Events<E>>(), manager.get_resource::<CrashReporter>())
+    else {
+        return;
+    };
+    let mut last_id = None;
+    for (id, event) in events.iter_with_id().filter(|(id, _)| id.id >= next_id) {
+        reporter.record_input(format!("{:?}", event));
+        last_id = Some(id.id);
+    }
+    if let (Some(last_id), Some(cursor)) = (last_id, manager.get_resource_mut::<InputCursor<E>>()) {
+        cursor.next_id = last_id + 1;
+    }
+}
+
+/// 在 [`App`] 中选择要记录到崩溃报告的输入
+pub trait CrashReportAppExt {
+    /// 把每帧写入的事件 `E` 记录到崩溃报告，需要 [`CrashReportPlugin::input_frames`] 大于 0
+    fn record_crash_input<E: BufferedEvent + Debug>(&mut self) -> &mut Self;
+}
+
+impl CrashReportAppExt for App {
+    fn record_crash_input<E: BufferedEvent + Debug>(&mut self) -> &mut Self {
+        if self.world().get_resource::<InputCursor<E>>().is_none() {
+            self.world_mut().add_resource(InputCursor::<E> {
+                next_id: 0,
+                marker: PhantomData,
+            });
+            self.add_systems(Last, record_input_system::<E>);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::AssertUnwindSafe;
+
+    use super::*;
+
+    // 字段只通过 Debug 输出
+    #[allow(dead_code)]
+    #[derive(Debug)]
+    struct KeyPressed(char);
+    impl BufferedEvent for KeyPressed {}
+
+    struct Position;
+    impl Component for Position {}
+
+    fn exploding_system(manager: &mut EntityManager) {
+        let frame = manager.get_resource::<CrashReporter>().unwrap().frame();
+        if frame == 3 {
+            panic!("boom on frame {}", frame);
+        }
+    }
+
+    #[test]
+    fn test_crash_report() {
+        let directory = std::env::temp_dir().join(format!("engine_crash_{}", std::process::id()));
+        let mut app = App::new();
+        app.add_plugin(CrashReportPlugin {
+            directory: directory.clone(),
+            input_frames: 2,
+        });
+        app.add_event::<KeyPressed>();
+        app.record_crash_input::<KeyPressed>();
+        app.add_systems(Update, exploding_system);
+        app.build_plugins().unwrap();
+        let recent_logs = RecentLogs::new(10);
+        recent_logs.push("INFO game: level loaded".to_string());
+        app.world_mut().add_resource(recent_logs);
+        app.finish();
+        app.world_mut().register_component::<Position>();
+        app.world_mut().spawn(Position);
+
+        for key in ['a', 'b'] {
+            app.world_mut().get_resource_mut::<Events<KeyPressed>>().unwrap().write(KeyPressed(key));
+            app.run_once();
+        }
+        app.world_mut().get_resource_mut::<Events<KeyPressed>>().unwrap().write(KeyPressed('c'));
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| app.run_once())).is_err());
+
+        let path = app.world().get_resource::<CrashReporter>().unwrap().last_report().unwrap();
+        let report = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert!(report.contains("system: engine_crash::tests::exploding_system"));
+        assert!(report.contains("frame: 3"));
+        assert!(report.contains("boom on frame 3 at crates/engine_crash/src/lib.rs"));
+        assert!(report.contains("entities: 1"));
+        assert!(report.contains("engine_crash::tests::Position: 1"));
+        assert!(report.contains("engine_crash::CrashReporter"));
+        assert!(report.contains("INFO game: level loaded"));
+        // 只保留最近两帧，第 3 帧的输入在 panic 之前还没有被记录
+        assert!(!report.contains("KeyPressed('a')"));
+        assert!(report.contains("frame 2:\n    KeyPressed('b')\n  frame 3:\n"));
+    }
+
+    #[test]
+    fn test_panic_on_other_thread_is_not_reported() {
+        let directory = std::env::temp_dir().join(format!("engine_crash_thread_{}", std::process::id()));
+        let mut apps: Vec<App> = (0..2)
+            .map(|_| {
+                let mut app = App::new();
+                app.add_plugin(CrashReportPlugin {
+                    directory: directory.clone(),
+                    input_frames: 0,
+                });
+                app.build_plugins().unwrap();
+                app.finish();
+                app
+            })
+            .collect();
+        for app in &mut apps {
+            app.run_once();
+        }
+
+        assert!(std::thread::spawn(|| panic!("worker panic")).join().is_err());
+        for app in &apps {
+            assert_eq!(app.world().get_resource::<CrashReporter>().unwrap().last_report(), None);
+        }
+        assert!(!directory.exists());
+    }
+}
@@ -0,0 +1,142 @@
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, OnceLock, TryLockError};
+use std::thread::{self, ThreadId};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use engine_log::RecentLogs;
+
+// 每帧开始时记录的 World 摘要，panic 时 World 正在被借用，只能使用之前的快照
+#[derive(Debug, Default)]
+pub(crate) struct WorldSummary {
+    pub(crate) frame: u64,
+    pub(crate) entity_count: usize,
+    pub(crate) component_counts: Vec<(String, usize)>,
+    pub(crate) resources: Vec<&'static str>,
+}
+
+pub(crate) struct Shared {
+    pub(crate) directory: PathBuf,
+    pub(crate) summary: Mutex<WorldSummary>,
+    // 最近几帧的输入，每帧一项
+    pub(crate) input: Mutex<VecDeque<(u64, Vec<String>)>>,
+    pub(crate) input_frames: usize,
+    pub(crate) recent_logs: OnceLock<RecentLogs>,
+    pub(crate) last_report: Mutex<Option<PathBuf>>,
+    // 运行 World 的线程，每帧开始时更新，只有这个线程上的 panic 才写入报告
+    pub(crate) thread: Mutex<ThreadId>,
+}
+
+// panic 可能发生在持有锁的时候，hook 中不能阻塞等待
+fn try_lock<T>(mutex: &Mutex<T>) -> Option<MutexGuard<'_, T>> {
+    match mutex.try_lock() {
+        Ok(guard) => Some(guard),
+        Err(TryLockError::Poisoned(err)) => Some(err.into_inner()),
+        Err(TryLockError::WouldBlock) => None,
+    }
+}
+
+const UNAVAILABLE: &str = "  (unavailable, it was being updated when the panic happened)\n";
+
+impl Shared {
+    pub(crate) fn begin_frame(&self, summary: WorldSummary) {
+        let frame = summary.frame;
+        *self.summary.lock().unwrap_or_else(|err| err.into_inner()) = summary;
+        *self.thread.lock().unwrap_or_else(|err| err.into_inner()) = thread::current().id();
+        if self.input_frames > 0 {
+            let mut input = self.input.lock().unwrap_or_else(|err| err.into_inner());
+            if input.len() >= self.input_frames {
+                input.pop_front();
+            }
+            input.push_back((frame, Vec::new()));
+        }
+    }
+
+    pub(crate) fn runs_on_current_thread(&self) -> bool {
+        try_lock(&self.thread).is_some_and(|thread| *thread == thread::current().id())
+    }
+
+    pub(crate) fn frame(&self) -> u64 {
+        self.summary.lock().unwrap_or_else(|err| err.into_inner()).frame
+    }
+
+    pub(crate) fn record_input(&self, line: String) {
+        let mut input = self.input.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some((_, lines)) = input.back_mut() {
+            lines.push(line);
+        }
+    }
+
+    pub(crate) fn render(&self, panic: &str, system: Option<&str>, backtrace: &Backtrace) -> String {
+        let mut report = String::new();
+        let thread = std::thread::current();
+        let _ = writeln!(report, "Crash report");
+        let _ = writeln!(report, "time: {}", unix_time().as_secs());
+        let _ = writeln!(report, "thread: {}", thread.name().unwrap_or("<unnamed>"));
+        let _ = writeln!(report, "system: {}", system.unwrap_or("<none>"));
+
+        match try_lock(&self.summary) {
+            Some(summary) => {
+                let _ = writeln!(report, "frame: {}", summary.frame);
+                let _ = writeln!(report, "\nPanic:\n{}\n\nBacktrace:\n{}", panic, backtrace);
+                let _ = writeln!(report, "World:\n  entities: {}", summary.entity_count);
+                let _ = writeln!(report, "  components:");
+                for (name, count) in &summary.component_counts {
+                    let _ = writeln!(report, "    {}: {}", name, count);
+                }
+                let _ = writeln!(report, "  resources:");
+                for name in &summary.resources {
+                    let _ = writeln!(report, "    {}", name);
+                }
+            }
+            None => {
+                let _ = writeln!(report, "\nPanic:\n{}\n\nBacktrace:\n{}", panic, backtrace);
+                let _ = write!(report, "World:\n{}", UNAVAILABLE);
+            }
+        }
+
+        if let Some(recent_logs) = self.recent_logs.get() {
+            let _ = writeln!(report, "\nRecent logs:");
+            match recent_logs.lines() {
+                Some(lines) => lines.iter().for_each(|line| {
+                    let _ = writeln!(report, "  {}", line);
+                }),
+                None => report.push_str(UNAVAILABLE),
+            }
+        }
+
+        if self.input_frames > 0 {
+            let _ = writeln!(report, "\nInput (last {} frames):", self.input_frames);
+            match try_lock(&self.input) {
+                Some(input) => {
+                    for (frame, lines) in input.iter() {
+                        let _ = writeln!(report, "  frame {}:", frame);
+                        for line in lines {
+                            let _ = writeln!(report, "    {}", line);
+                        }
+                    }
+                }
+                None => report.push_str(UNAVAILABLE),
+            }
+        }
+        report
+    }
+
+    pub(crate) fn write_report(&self, report: &str) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(&self.directory)?;
+        let path = self
+            .directory
+            .join(format!("crash-{}-{}.txt", unix_time().as_micros(), std::process::id()));
+        std::fs::write(&path, report)?;
+        if let Some(mut last_report) = try_lock(&self.last_report) {
+            *last_report = Some(path.clone());
+        }
+        Ok(path)
+    }
+}
+
+fn unix_time() -> std::time::Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
+}
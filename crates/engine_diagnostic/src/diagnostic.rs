@@ -0,0 +1,256 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::time::Instant;
+
+use engine_app::prelude::*;
+use engine_ecs::prelude::*;
+
+/// 诊断的名称，用 `/` 分隔层级，例如 `frame_time` 或 `system_time/game::move_system`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DiagnosticPath(Cow<'static, str>);
+
+impl DiagnosticPath {
+    pub const fn const_new(path: &'static str) -> Self {
+        DiagnosticPath(Cow::Borrowed(path))
+    }
+
+    pub fn new(path: impl Into<Cow<'static, str>>) -> Self {
+        DiagnosticPath(path.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for DiagnosticPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// 一次测量
+#[derive(Debug, Clone, Copy)]
+pub struct DiagnosticMeasurement {
+    pub time: Instant,
+    pub value: f64,
+}
+
+/// 一项诊断，保存最近的测量值以及平滑后的值
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    path: DiagnosticPath,
+    /// 显示在数值后面的单位，例如 `ms`
+    pub suffix: Cow<'static, str>,
+    history: VecDeque<DiagnosticMeasurement>,
+    max_history_length: usize,
+    sum: f64,
+    ema: f64,
+    ema_smoothing_factor: f64,
+    pub is_enabled: bool,
+}
+
+impl Diagnostic {
+    /// 默认保存的测量数量
+    pub const DEFAULT_MAX_HISTORY_LENGTH: usize = 120;
+
+    pub fn new(path: DiagnosticPath) -> Self {
+        Diagnostic {
+            path,
+            suffix: Cow::Borrowed(""),
+            history: VecDeque::with_capacity(Self::DEFAULT_MAX_HISTORY_LENGTH),
+            max_history_length: Self::DEFAULT_MAX_HISTORY_LENGTH,
+            sum: 0.0,
+            ema: 0.0,
+            ema_smoothing_factor: 2.0 / 21.0,
+            is_enabled: true,
+        }
+    }
+
+    pub fn with_suffix(mut self, suffix: impl Into<Cow<'static, str>>) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+
+    pub fn with_max_history_length(mut self, max_history_length: usize) -> Self {
+        self.max_history_length = max_history_length.max(1);
+        while self.history.len() > self.max_history_length {
+            self.pop_oldest();
+        }
+        self
+    }
+
+    /// 指数移动平均的系数，取值 `(0, 1]`，越大越接近最新的测量值
+    pub fn with_smoothing_factor(mut self, factor: f64) -> Self {
+        self.ema_smoothing_factor = factor.clamp(f64::EPSILON, 1.0);
+        self
+    }
+
+    pub fn path(&self) -> &DiagnosticPath {
+        &self.path
+    }
+
+    pub fn add_measurement(&mut self, measurement: DiagnosticMeasurement) {
+        if measurement.value.is_nan() {
+            return;
+        }
+        if self.history.is_empty() {
+            self.ema = measurement.value;
+        } else {
+            self.ema += (measurement.value - self.ema) * self.ema_smoothing_factor;
+        }
+        if self.history.len() >= self.max_history_length {
+            self.pop_oldest();
+        }
+        self.sum += measurement.value;
+        self.history.push_back(measurement);
+    }
+
+    fn pop_oldest(&mut self) {
+        if let Some(oldest) = self.history.pop_front() {
+            self.sum -= oldest.value;
+        }
+    }
+
+    /// 最新的测量值
+    pub fn value(&self) -> Option<f64> {
+        self.history.back().map(|measurement| measurement.value)
+    }
+
+    /// 指数移动平均
+    pub fn smoothed(&self) -> Option<f64> {
+        (!self.history.is_empty()).then_some(self.ema)
+    }
+
+    /// 历史中所有测量值的平均值
+    pub fn average(&self) -> Option<f64> {
+        (!self.history.is_empty()).then(|| self.sum / self.history.len() as f64)
+    }
+
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn max_history_length(&self) -> usize {
+        self.max_history_length
+    }
+
+    /// 按时间顺序返回保存的测量
+    pub fn measurements(&self) -> impl Iterator<Item = &DiagnosticMeasurement> {
+        self.history.iter()
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.sum = 0.0;
+        self.ema = 0.0;
+    }
+}
+
+/// 所有诊断，按名称排序
+#[derive(Debug, Default)]
+pub struct DiagnosticsStore {
+    diagnostics: BTreeMap<DiagnosticPath, Diagnostic>,
+}
+
+impl DiagnosticsStore {
+    /// 添加诊断，同名的诊断会被替换
+    pub fn add(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.insert(diagnostic.path.clone(), diagnostic);
+    }
+
+    pub fn get(&self, path: &DiagnosticPath) -> Option<&Diagnostic> {
+        self.diagnostics.get(path)
+    }
+
+    pub fn get_mut(&mut self, path: &DiagnosticPath) -> Option<&mut Diagnostic> {
+        self.diagnostics.get_mut(path)
+    }
+
+    /// 获取诊断，不存在时用 `create` 创建，适合名称在运行时才确定的诊断
+    pub fn get_or_add(&mut self, path: &DiagnosticPath, create: impl FnOnce() -> Diagnostic) -> &mut Diagnostic {
+        self.diagnostics.entry(path.clone()).or_insert_with(create)
+    }
+
+    /// 最新的测量值
+    pub fn get_measurement(&self, path: &DiagnosticPath) -> Option<f64> {
+        self.get(path).filter(|diagnostic| diagnostic.is_enabled)?.value()
+    }
+
+    /// 给已添加并启用的诊断记录一次测量
+    pub fn add_measurement(&mut self, path: &DiagnosticPath, value: f64) {
+        if let Some(diagnostic) = self.diagnostics.get_mut(path).filter(|diagnostic| diagnostic.is_enabled) {
+            diagnostic.add_measurement(DiagnosticMeasurement {
+                time: Instant::now(),
+                value,
+            });
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
+
+/// 添加 [`DiagnosticsStore`] 资源，其他诊断插件会自动添加它
+#[derive(Default)]
+pub struct DiagnosticsPlugin;
+
+impl Plugin for DiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        init_diagnostics_store(app.world_mut());
+    }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
+
+/// 没有 [`DiagnosticsStore`] 时添加一个，返回它的可变引用
+pub fn init_diagnostics_store(world: &mut World) -> &mut DiagnosticsStore {
+    if world.get_resource::<DiagnosticsStore>().is_none() {
+        world.add_resource(DiagnosticsStore::default());
+    }
+    world.get_resource_mut::<DiagnosticsStore>().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEED: DiagnosticPath = DiagnosticPath::const_new("speed");
+
+    #[test]
+    fn test_history_and_smoothing() {
+        let mut store = DiagnosticsStore::default();
+        store.add(
+            Diagnostic::new(SPEED)
+                .with_max_history_length(3)
+                .with_smoothing_factor(0.5),
+        );
+        for value in [1.0, 2.0, 3.0, 7.0] {
+            store.add_measurement(&SPEED, value);
+        }
+
+        let diagnostic = store.get(&SPEED).unwrap();
+        assert_eq!(diagnostic.history_len(), 3);
+        assert_eq!(diagnostic.value(), Some(7.0));
+        assert_eq!(diagnostic.average(), Some(4.0));
+        // 1 -> 1.5 -> 2.25 -> 4.625
+        assert_eq!(diagnostic.smoothed(), Some(4.625));
+
+        store.get_mut(&SPEED).unwrap().is_enabled = false;
+        store.add_measurement(&SPEED, 100.0);
+        assert_eq!(store.get(&SPEED).unwrap().value(), Some(7.0));
+        assert_eq!(store.get_measurement(&SPEED), None);
+    }
+}
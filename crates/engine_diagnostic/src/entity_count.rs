@@ -0,0 +1,64 @@
+use engine_app::prelude::*;
+use engine_ecs::prelude::*;
+
+use crate::diagnostic::{init_diagnostics_store, Diagnostic, DiagnosticPath, DiagnosticsStore};
+
+/// 记录实体数量
+#[derive(Default)]
+pub struct EntityCountDiagnosticsPlugin;
+
+impl EntityCountDiagnosticsPlugin {
+    pub const ENTITY_COUNT: DiagnosticPath = DiagnosticPath::const_new("entity_count");
+}
+
+impl Plugin for EntityCountDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        init_diagnostics_store(app.world_mut()).add(Diagnostic::new(Self::ENTITY_COUNT));
+        app.add_systems(Last, entity_count_diagnostic_system);
+    }
+}
+
+pub fn entity_count_diagnostic_system(manager: &mut EntityManager) {
+    let count = manager.entity_ids().len();
+    if let Some(store) = manager.get_resource_mut::<DiagnosticsStore>() {
+        store.add_measurement(&EntityCountDiagnosticsPlugin::ENTITY_COUNT, count as f64);
+    }
+}
+
+/// 按组件类型记录拥有该组件的实体数量，诊断名称为 `component_count/<组件名>`
+#[derive(Default)]
+pub struct ComponentCountDiagnosticsPlugin;
+
+impl ComponentCountDiagnosticsPlugin {
+    pub const PREFIX: &'static str = "component_count";
+
+    pub fn path(component_name: &str) -> DiagnosticPath {
+        DiagnosticPath::new(format!("{}/{}", Self::PREFIX, component_name))
+    }
+}
+
+impl Plugin for ComponentCountDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        init_diagnostics_store(app.world_mut());
+        app.add_systems(Last, component_count_diagnostic_system);
+    }
+}
+
+pub fn component_count_diagnostic_system(manager: &mut EntityManager) {
+    let counts: Vec<(DiagnosticPath, usize)> = manager
+        .components()
+        .iter()
+        .map(|info| {
+            let count = manager.entity_ids_by_id(info.id()).len();
+            (ComponentCountDiagnosticsPlugin::path(info.name()), count)
+        })
+        .collect();
+    let Some(store) = manager.get_resource_mut::<DiagnosticsStore>() else {
+        return;
+    };
+    // 组件可以在运行时注册，新组件第一次出现时添加诊断
+    for (path, count) in counts {
+        store.get_or_add(&path, || Diagnostic::new(path.clone()));
+        store.add_measurement(&path, count as f64);
+    }
+}
@@ -0,0 +1,57 @@
+use engine_app::prelude::*;
+use engine_ecs::prelude::*;
+use engine_time::prelude::*;
+
+use crate::diagnostic::{init_diagnostics_store, Diagnostic, DiagnosticPath, DiagnosticsStore};
+
+/// 记录帧率、帧时间和帧数，帧时间来自 `Time<Real>`
+#[derive(Default)]
+pub struct FrameTimeDiagnosticsPlugin;
+
+impl FrameTimeDiagnosticsPlugin {
+    pub const FPS: DiagnosticPath = DiagnosticPath::const_new("fps");
+    pub const FRAME_TIME: DiagnosticPath = DiagnosticPath::const_new("frame_time");
+    pub const FRAME_COUNT: DiagnosticPath = DiagnosticPath::const_new("frame_count");
+}
+
+impl Plugin for FrameTimeDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        let store = init_diagnostics_store(app.world_mut());
+        store.add(Diagnostic::new(Self::FPS));
+        store.add(Diagnostic::new(Self::FRAME_TIME).with_suffix("ms"));
+        store.add(Diagnostic::new(Self::FRAME_COUNT).with_smoothing_factor(1.0));
+        app.world_mut().add_resource(FrameCount::default());
+        app.add_systems(Last, frame_time_diagnostic_system);
+    }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![PluginDependency::of::<TimePlugin>()]
+    }
+}
+
+/// 应用启动以来运行的帧数
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FrameCount(pub u64);
+
+pub fn frame_time_diagnostic_system(manager: &mut EntityManager) {
+    let Some(delta) = manager.get_resource::<Time<Real>>().map(|time| time.delta_secs_f64()) else {
+        return;
+    };
+    let frame_count = match manager.get_resource_mut::<FrameCount>() {
+        Some(count) => {
+            count.0 += 1;
+            count.0
+        }
+        None => return,
+    };
+    let Some(store) = manager.get_resource_mut::<DiagnosticsStore>() else {
+        return;
+    };
+    store.add_measurement(&FrameTimeDiagnosticsPlugin::FRAME_COUNT, frame_count as f64);
+    // 第一帧没有帧时间
+    if delta == 0.0 {
+        return;
+    }
+    store.add_measurement(&FrameTimeDiagnosticsPlugin::FRAME_TIME, delta * 1000.0);
+    store.add_measurement(&FrameTimeDiagnosticsPlugin::FPS, 1.0 / delta);
+}
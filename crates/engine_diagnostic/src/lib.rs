@@ -0,0 +1,64 @@
+mod diagnostic;
+mod entity_count;
+mod frame_time;
+mod log_diagnostics;
+mod system_time;
+
+pub mod prelude {
+    pub use super::diagnostic::*;
+    pub use super::entity_count::*;
+    pub use super::frame_time::*;
+    pub use super::log_diagnostics::*;
+    pub use super::system_time::*;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use engine_app::prelude::*;
+    use engine_ecs::prelude::*;
+    use engine_time::prelude::*;
+
+    use super::prelude::*;
+
+    struct Enemy;
+    impl Component for Enemy {}
+
+    fn spawn_enemy_system(manager: &mut EntityManager) {
+        manager.spawn(Enemy);
+    }
+
+    #[test]
+    fn test_builtin_diagnostics() {
+        let mut app = App::new();
+        app.world_mut()
+            .add_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+            .register_component::<Enemy>();
+        app.add_plugin(TimePlugin)
+            .add_plugin(FrameTimeDiagnosticsPlugin)
+            .add_plugin(EntityCountDiagnosticsPlugin)
+            .add_plugin(ComponentCountDiagnosticsPlugin)
+            .add_plugin(SystemTimeDiagnosticsPlugin)
+            .add_plugin(LogDiagnosticsPlugin::default())
+            .add_systems(Update, spawn_enemy_system);
+        for _ in 0..4 {
+            app.update();
+        }
+
+        let store = app.world().get_resource::<DiagnosticsStore>().unwrap();
+        let fps = store.get(&FrameTimeDiagnosticsPlugin::FPS).unwrap();
+        assert!((fps.smoothed().unwrap() - 10.0).abs() < 1e-6);
+        assert_eq!(store.get_measurement(&FrameTimeDiagnosticsPlugin::FRAME_COUNT), Some(4.0));
+        assert_eq!(store.get_measurement(&EntityCountDiagnosticsPlugin::ENTITY_COUNT), Some(4.0));
+
+        let enemy_count = ComponentCountDiagnosticsPlugin::path(std::any::type_name::<Enemy>());
+        assert_eq!(store.get_measurement(&enemy_count), Some(4.0));
+        let spawn_time = SystemTimeDiagnosticsPlugin::path(std::any::type_name_of_val(&spawn_enemy_system));
+        assert_eq!(store.get(&spawn_time).unwrap().history_len(), 4);
+
+        let lines = summary_lines(store, Some(&[FrameTimeDiagnosticsPlugin::FRAME_TIME]));
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("frame_time: "), "{}", lines[0]);
+    }
+}
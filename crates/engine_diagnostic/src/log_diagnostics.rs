@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use engine_app::prelude::*;
+use engine_ecs::prelude::*;
+use engine_time::prelude::*;
+use tracing::info;
+
+use crate::diagnostic::{init_diagnostics_store, Diagnostic, DiagnosticPath, DiagnosticsStore};
+
+/// 每隔 `wait_duration`（真实时间）把诊断的汇总输出到日志
+pub struct LogDiagnosticsPlugin {
+    pub wait_duration: Duration,
+    /// 只输出这些诊断，`None` 表示输出全部
+    pub filter: Option<Vec<DiagnosticPath>>,
+}
+
+impl Default for LogDiagnosticsPlugin {
+    fn default() -> Self {
+        LogDiagnosticsPlugin {
+            wait_duration: Duration::from_secs(1),
+            filter: None,
+        }
+    }
+}
+
+impl LogDiagnosticsPlugin {
+    pub fn filtered(filter: Vec<DiagnosticPath>) -> Self {
+        LogDiagnosticsPlugin {
+            filter: Some(filter),
+            ..Default::default()
+        }
+    }
+}
+
+/// [`LogDiagnosticsPlugin`] 的计时器和过滤条件
+pub struct LogDiagnosticsState {
+    timer: Timer,
+    filter: Option<Vec<DiagnosticPath>>,
+}
+
+impl Plugin for LogDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        init_diagnostics_store(app.world_mut());
+        app.world_mut().add_resource(LogDiagnosticsState {
+            timer: Timer::new(self.wait_duration, TimerMode::Repeating),
+            filter: self.filter.clone(),
+        });
+        app.add_systems(Last, log_diagnostics_system);
+    }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![PluginDependency::of::<TimePlugin>()]
+    }
+}
+
+pub fn log_diagnostics_system(manager: &mut EntityManager) {
+    let Some(delta) = manager.get_resource::<Time<Real>>().map(|time| time.delta()) else {
+        return;
+    };
+    let Some(state) = manager.get_resource_mut::<LogDiagnosticsState>() else {
+        return;
+    };
+    if !state.timer.tick(delta).just_finished() {
+        return;
+    }
+    let filter = state.filter.clone();
+    let Some(store) = manager.get_resource::<DiagnosticsStore>() else {
+        return;
+    };
+    for line in summary_lines(store, filter.as_deref()) {
+        info!(target: "engine_diagnostic", "{}", line);
+    }
+}
+
+/// 每项诊断一行：名称、最新值、平均值
+pub fn summary_lines(store: &DiagnosticsStore, filter: Option<&[DiagnosticPath]>) -> Vec<String> {
+    let diagnostics: Vec<&Diagnostic> = store
+        .iter()
+        .filter(|diagnostic| diagnostic.is_enabled && diagnostic.value().is_some())
+        .filter(|diagnostic| filter.is_none_or(|filter| filter.contains(diagnostic.path())))
+        .collect();
+    let width = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.path().as_str().len())
+        .max()
+        .unwrap_or(0);
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| {
+            let suffix = diagnostic.suffix.as_ref();
+            format!(
+                "{:<width$}: {:>12.6}{:<2} (avg {:.6}{})",
+                diagnostic.path().as_str(),
+                diagnostic.smoothed().unwrap_or_default(),
+                suffix,
+                diagnostic.average().unwrap_or_default(),
+                suffix,
+                width = width
+            )
+        })
+        .collect()
+}
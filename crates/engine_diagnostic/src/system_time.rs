@@ -0,0 +1,48 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use engine_app::prelude::*;
+use engine_ecs::prelude::*;
+
+use crate::diagnostic::{init_diagnostics_store, Diagnostic, DiagnosticPath, DiagnosticsStore};
+
+/// 记录每个系统每帧的运行时间（毫秒），诊断名称为 `system_time/<系统名>`。
+///
+/// 同一帧运行多次的系统（例如 `FixedUpdate` 中的系统）记录总时间。
+/// 计时本身也有开销，只在需要时添加这个插件。
+#[derive(Default)]
+pub struct SystemTimeDiagnosticsPlugin;
+
+impl SystemTimeDiagnosticsPlugin {
+    pub const PREFIX: &'static str = "system_time";
+
+    pub fn path(system_name: &str) -> DiagnosticPath {
+        DiagnosticPath::new(format!("{}/{}", Self::PREFIX, system_name))
+    }
+}
+
+impl Plugin for SystemTimeDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        init_diagnostics_store(app.world_mut());
+        app.world_mut().add_resource(SystemTimings::default());
+        app.add_systems(Last, system_time_diagnostic_system);
+    }
+}
+
+pub fn system_time_diagnostic_system(manager: &mut EntityManager) {
+    let Some(timings) = manager.get_resource_mut::<SystemTimings>() else {
+        return;
+    };
+    let mut totals: BTreeMap<String, Duration> = BTreeMap::new();
+    for (name, duration) in timings.drain() {
+        *totals.entry(name.into_owned()).or_default() += duration;
+    }
+    let Some(store) = manager.get_resource_mut::<DiagnosticsStore>() else {
+        return;
+    };
+    for (name, duration) in totals {
+        let path = SystemTimeDiagnosticsPlugin::path(&name);
+        store.get_or_add(&path, || Diagnostic::new(path.clone()).with_suffix("ms"));
+        store.add_measurement(&path, duration.as_secs_f64() * 1000.0);
+    }
+}
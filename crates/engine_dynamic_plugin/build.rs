@@ -0,0 +1,62 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// 插件和宿主程序共享 engine_app / engine_ecs 中的类型，
+// 编译器版本或这些 crate 的源码不同时内存布局可能不一致
+const ENGINE_CRATES: [&str; 2] = ["../engine_app", "../engine_ecs"];
+
+fn main() {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let output = Command::new(rustc).arg("-vV").output().expect("failed to run rustc -vV");
+    let version = String::from_utf8_lossy(&output.stdout);
+    let version = version.lines().next().unwrap_or_default().trim();
+    println!("cargo:rustc-env=ENGINE_RUSTC_VERSION={}", version);
+
+    let mut files = Vec::new();
+    for engine_crate in ENGINE_CRATES {
+        let engine_crate = Path::new(engine_crate);
+        println!("cargo:rerun-if-changed={}", engine_crate.display());
+        files.push(engine_crate.join("Cargo.toml"));
+        collect_files(&engine_crate.join("src"), &mut files);
+    }
+    files.sort();
+    let mut hash = Fnv1a::default();
+    for file in files {
+        hash.write(file.to_string_lossy().as_bytes());
+        hash.write(&fs::read(&file).unwrap_or_default());
+    }
+    println!("cargo:rustc-env=ENGINE_SOURCE_HASH={}", hash.0);
+}
+
+fn collect_files(directory: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+// 结果不随编译器变化的 64 位 FNV-1a 哈希
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf29ce484222325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! 没有使用 `export_plugin!` 的动态库，测试加载时缺少插件符号的错误
+
+#[unsafe(no_mangle)]
+pub extern "C" fn engine_empty_library_version() -> u32 {
+    1
+}
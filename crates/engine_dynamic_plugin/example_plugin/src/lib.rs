@@ -0,0 +1,25 @@
+//! 动态插件示例，编译为 cdylib 后由 `DynamicPluginLoader` 加载
+
+use engine_app::prelude::*;
+use engine_dynamic_plugin::prelude::*;
+use engine_ecs::prelude::*;
+
+/// 记录动态插件运行帧数的资源
+#[derive(Debug, Default)]
+pub struct ExampleCounter(pub u32);
+
+#[derive(Default)]
+pub struct ExamplePlugin;
+
+impl Plugin for ExamplePlugin {
+    fn build(&self, app: &mut App) {
+        app.world_mut().add_resource(ExampleCounter::default());
+        app.add_systems(Update, |manager: &mut EntityManager| {
+            if let Some(counter) = manager.get_resource_mut::<ExampleCounter>() {
+                counter.0 += 1;
+            }
+        });
+    }
+}
+
+export_plugin!(ExamplePlugin);
@@ -0,0 +1,25 @@
+//! 从目录加载动态插件（Linux）：
+//!
+//! ```sh
+//! cargo build -p engine_example_plugin
+//! mkdir -p plugins && cp target/debug/libengine_example_plugin.so plugins/
+//! cargo run -p engine_dynamic_plugin --example load_plugins -- plugins
+//! ```
+
+use engine_app::prelude::*;
+use engine_dynamic_plugin::prelude::*;
+
+fn main() -> AppExit {
+    let directory = std::env::args().nth(1).unwrap_or_else(|| "plugins".to_string());
+
+    let mut app = App::new();
+    match unsafe { app.load_plugins_from_dir(&directory) } {
+        Ok(count) => println!("Loaded {} plugin(s) from '{}'", count, directory),
+        Err(err) => {
+            eprintln!("{}", err);
+            return AppExit::error();
+        }
+    }
+    app.add_plugin(ScheduleRunnerPlugin::run_frames(3));
+    app.run()
+}
@@ -0,0 +1,13 @@
+mod loader;
+
+pub mod prelude {
+    pub use super::loader::*;
+    pub use super::export_plugin;
+}
+
+pub use libloading;
+
+#[doc(hidden)]
+pub mod __macro_exports {
+    pub use engine_app::prelude::Plugin;
+}
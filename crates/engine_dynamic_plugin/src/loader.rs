@@ -0,0 +1,308 @@
+use std::ffi::{c_char, CStr};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use engine_app::prelude::*;
+use libloading::{Library, Symbol};
+
+/// 动态插件接口的版本，[`PluginAbi`] 或构造函数的签名变化时递增
+pub const PLUGIN_ABI_VERSION: u32 = 2;
+
+/// 动态插件编译时使用的引擎版本
+pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 编译引擎使用的 rustc 版本（`rustc -vV` 的第一行），Rust 没有稳定的 ABI，插件必须使用同一个编译器
+pub const RUSTC_VERSION: &str = env!("ENGINE_RUSTC_VERSION");
+
+/// engine_app 和 engine_ecs 源码的哈希，同一版本号下源码不同时类型布局也可能不同
+pub const ENGINE_SOURCE_HASH: &str = env!("ENGINE_SOURCE_HASH");
+
+/// 导出 ABI 信息的符号名
+pub const PLUGIN_ABI_SYMBOL: &str = "_engine_plugin_abi";
+
+/// 导出插件构造函数的符号名
+pub const PLUGIN_CREATE_SYMBOL: &str = "_engine_plugin_create";
+
+/// 动态库导出的 ABI 信息，由 [`export_plugin!`](crate::export_plugin) 生成
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PluginAbi {
+    pub abi_version: u32,
+    /// 以 `\0` 结尾的引擎版本字符串
+    pub engine_version: *const c_char,
+    /// 以 `\0` 结尾的 rustc 版本字符串
+    pub rustc_version: *const c_char,
+    /// 以 `\0` 结尾的引擎源码哈希
+    pub source_hash: *const c_char,
+}
+
+impl PluginAbi {
+    pub const fn current() -> Self {
+        PluginAbi {
+            abi_version: PLUGIN_ABI_VERSION,
+            engine_version: concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char,
+            rustc_version: concat!(env!("ENGINE_RUSTC_VERSION"), "\0").as_ptr() as *const c_char,
+            source_hash: concat!(env!("ENGINE_SOURCE_HASH"), "\0").as_ptr() as *const c_char,
+        }
+    }
+}
+
+/// 加载的插件和它所在的动态库。
+///
+/// 字段按声明顺序释放：插件（以及它的虚表）在动态库卸载之前释放。
+pub struct LoadedPlugin {
+    pub plugin: Box<dyn Plugin>,
+    pub library: Library,
+}
+
+type AbiFn = unsafe extern "C" fn() -> PluginAbi;
+#[allow(improper_ctypes_definitions)]
+type CreatePluginFn = unsafe extern "C" fn() -> *mut dyn Plugin;
+
+/// 在 cdylib 中导出插件，插件类型必须实现 `Default`：
+///
+/// ```ignore
+/// #[derive(Default)]
+/// pub struct GameplayPlugin;
+///
+/// impl Plugin for GameplayPlugin { ... }
+///
+/// export_plugin!(GameplayPlugin);
+/// ```
+#[macro_export]
+macro_rules! export_plugin {
+    ($plugin:ty) => {
+        #[unsafe(no_mangle)]
+        pub extern "C" fn _engine_plugin_abi() -> $crate::prelude::PluginAbi {
+            $crate::prelude::PluginAbi::current()
+        }
+
+        #[unsafe(no_mangle)]
+        #[allow(improper_ctypes_definitions)]
+        pub extern "C" fn _engine_plugin_create() -> *mut dyn $crate::__macro_exports::Plugin {
+            let plugin: ::std::boxed::Box<dyn $crate::__macro_exports::Plugin> =
+                ::std::boxed::Box::new(<$plugin as ::std::default::Default>::default());
+            ::std::boxed::Box::into_raw(plugin)
+        }
+    };
+}
+
+/// 加载动态插件时的错误
+#[derive(Debug)]
+pub enum DynamicPluginError {
+    /// 无法打开动态库
+    Open { path: PathBuf, source: libloading::Error },
+    /// 动态库没有导出需要的符号，通常是因为没有使用 `export_plugin!`
+    MissingSymbol { path: PathBuf, symbol: &'static str, source: libloading::Error },
+    /// 插件接口版本不一致
+    AbiMismatch { path: PathBuf, expected: u32, found: u32 },
+    /// 插件使用其他版本的引擎编译
+    EngineVersionMismatch { path: PathBuf, expected: &'static str, found: String },
+    /// 插件使用其他版本的 rustc 编译
+    RustcVersionMismatch { path: PathBuf, expected: &'static str, found: String },
+    /// 插件使用的 engine_app / engine_ecs 源码与宿主程序不同
+    EngineSourceMismatch { path: PathBuf, expected: &'static str, found: String },
+    /// 无法读取插件目录
+    ReadDir { path: PathBuf, source: std::io::Error },
+}
+
+impl fmt::Display for DynamicPluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynamicPluginError::Open { path, source } => {
+                write!(f, "Failed to open plugin library '{}': {}", path.display(), source)
+            }
+            DynamicPluginError::MissingSymbol { path, symbol, source } => write!(
+                f,
+                "Plugin library '{}' does not export '{}' (was it built with export_plugin!?): {}",
+                path.display(),
+                symbol,
+                source
+            ),
+            DynamicPluginError::AbiMismatch { path, expected, found } => write!(
+                f,
+                "Plugin library '{}' uses plugin ABI version {}, but the engine expects {}",
+                path.display(),
+                found,
+                expected
+            ),
+            DynamicPluginError::EngineVersionMismatch { path, expected, found } => write!(
+                f,
+                "Plugin library '{}' was built against engine {}, but the engine is {}",
+                path.display(),
+                found,
+                expected
+            ),
+            DynamicPluginError::RustcVersionMismatch { path, expected, found } => write!(
+                f,
+                "Plugin library '{}' was built with {}, but the engine was built with {}",
+                path.display(),
+                found,
+                expected
+            ),
+            DynamicPluginError::EngineSourceMismatch { path, expected, found } => write!(
+                f,
+                "Plugin library '{}' was built against different engine sources (hash {}, expected {})",
+                path.display(),
+                found,
+                expected
+            ),
+            DynamicPluginError::ReadDir { path, source } => {
+                write!(f, "Failed to read plugin directory '{}': {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DynamicPluginError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DynamicPluginError::Open { source, .. } => Some(source),
+            DynamicPluginError::MissingSymbol { source, .. } => Some(source),
+            DynamicPluginError::ReadDir { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// 从动态库（Linux 上的 `.so`）加载插件。
+///
+/// 插件和宿主程序必须使用同一个编译器和同一版本的引擎编译，
+/// 加载时会检查 [`PLUGIN_ABI_VERSION`]、[`ENGINE_VERSION`]、[`RUSTC_VERSION`] 和 [`ENGINE_SOURCE_HASH`]。
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DynamicPluginLoader;
+
+impl DynamicPluginLoader {
+    /// 打开动态库，检查 ABI 信息并创建插件。
+    ///
+    /// 动态库必须比插件以及插件添加的系统、资源活得更久，直接释放 [`LoadedPlugin`] 时会先释放插件。
+    ///
+    /// # Safety
+    ///
+    /// 动态库的初始化代码和插件构造函数会被执行，调用者需要确认动态库可信，
+    /// 并且使用 [`export_plugin!`](crate::export_plugin) 导出插件。
+    pub unsafe fn load(path: impl AsRef<Path>) -> Result<LoadedPlugin, DynamicPluginError> {
+        let path = path.as_ref();
+        let library = unsafe { Library::new(path) }.map_err(|source| DynamicPluginError::Open {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let abi = unsafe {
+            let abi: Symbol<AbiFn> = library
+                .get(PLUGIN_ABI_SYMBOL.as_bytes())
+                .map_err(|source| missing_symbol(path, PLUGIN_ABI_SYMBOL, source))?;
+            abi()
+        };
+        if abi.abi_version != PLUGIN_ABI_VERSION {
+            return Err(DynamicPluginError::AbiMismatch {
+                path: path.to_path_buf(),
+                expected: PLUGIN_ABI_VERSION,
+                found: abi.abi_version,
+            });
+        }
+        let engine_version = unsafe { read_c_str(abi.engine_version) };
+        if engine_version != ENGINE_VERSION {
+            return Err(DynamicPluginError::EngineVersionMismatch {
+                path: path.to_path_buf(),
+                expected: ENGINE_VERSION,
+                found: engine_version,
+            });
+        }
+        let rustc_version = unsafe { read_c_str(abi.rustc_version) };
+        if rustc_version != RUSTC_VERSION {
+            return Err(DynamicPluginError::RustcVersionMismatch {
+                path: path.to_path_buf(),
+                expected: RUSTC_VERSION,
+                found: rustc_version,
+            });
+        }
+        let source_hash = unsafe { read_c_str(abi.source_hash) };
+        if source_hash != ENGINE_SOURCE_HASH {
+            return Err(DynamicPluginError::EngineSourceMismatch {
+                path: path.to_path_buf(),
+                expected: ENGINE_SOURCE_HASH,
+                found: source_hash,
+            });
+        }
+
+        let plugin = unsafe {
+            let create: Symbol<CreatePluginFn> = library
+                .get(PLUGIN_CREATE_SYMBOL.as_bytes())
+                .map_err(|source| missing_symbol(path, PLUGIN_CREATE_SYMBOL, source))?;
+            Box::from_raw(create())
+        };
+        Ok(LoadedPlugin { plugin, library })
+    }
+
+    /// 目录中所有动态库的路径，按文件名排序
+    pub fn plugin_paths(directory: impl AsRef<Path>) -> Result<Vec<PathBuf>, DynamicPluginError> {
+        let directory = directory.as_ref();
+        let read_dir_error = |source| DynamicPluginError::ReadDir {
+            path: directory.to_path_buf(),
+            source,
+        };
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(directory).map_err(read_dir_error)? {
+            let path = entry.map_err(read_dir_error)?.path();
+            if path.is_file()
+                && path.extension().is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION)
+            {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+}
+
+// 空指针视为空字符串
+unsafe fn read_c_str(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned()
+    }
+}
+
+fn missing_symbol(path: &Path, symbol: &'static str, source: libloading::Error) -> DynamicPluginError {
+    DynamicPluginError::MissingSymbol {
+        path: path.to_path_buf(),
+        symbol,
+        source,
+    }
+}
+
+/// 在 [`App`] 中加载动态插件，动态库会一直保存到 App 释放
+pub trait DynamicPluginExt {
+    /// 加载一个动态插件并添加到 App
+    ///
+    /// # Safety
+    ///
+    /// 同 [`DynamicPluginLoader::load`]
+    unsafe fn load_plugin(&mut self, path: impl AsRef<Path>) -> Result<&mut Self, DynamicPluginError>;
+
+    /// 加载目录（例如 `plugins/`）中的所有动态插件，返回加载的数量
+    ///
+    /// # Safety
+    ///
+    /// 同 [`DynamicPluginLoader::load`]
+    unsafe fn load_plugins_from_dir(&mut self, directory: impl AsRef<Path>) -> Result<usize, DynamicPluginError>;
+}
+
+impl DynamicPluginExt for App {
+    unsafe fn load_plugin(&mut self, path: impl AsRef<Path>) -> Result<&mut Self, DynamicPluginError> {
+        let LoadedPlugin { plugin, library } = unsafe { DynamicPluginLoader::load(path)? };
+        self.hold_library(library);
+        self.add_boxed_plugin(plugin);
+        Ok(self)
+    }
+
+    unsafe fn load_plugins_from_dir(&mut self, directory: impl AsRef<Path>) -> Result<usize, DynamicPluginError> {
+        let paths = DynamicPluginLoader::plugin_paths(directory)?;
+        for path in &paths {
+            unsafe { self.load_plugin(path)? };
+        }
+        Ok(paths.len())
+    }
+}
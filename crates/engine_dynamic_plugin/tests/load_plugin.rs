@@ -0,0 +1,63 @@
+#![cfg(target_os = "linux")]
+
+use std::path::PathBuf;
+
+use engine_app::prelude::*;
+use engine_dynamic_plugin::prelude::*;
+use engine_example_plugin::ExampleCounter;
+
+// 作为 dev-dependency 编译的 cdylib 和测试程序在同一个 deps 目录
+fn library_path(name: &str) -> PathBuf {
+    let deps = std::env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    deps.join(format!(
+        "{}{}.{}",
+        std::env::consts::DLL_PREFIX,
+        name,
+        std::env::consts::DLL_EXTENSION
+    ))
+}
+
+#[test]
+fn test_load_plugin_from_cdylib() {
+    let path = library_path("engine_example_plugin");
+    assert!(path.exists(), "{} was not built", path.display());
+
+    let mut app = App::new();
+    unsafe { app.load_plugin(&path) }.unwrap();
+    app.update();
+    app.update();
+
+    assert_eq!(app.world().get_resource::<ExampleCounter>().unwrap().0, 2);
+}
+
+#[test]
+fn test_load_errors() {
+    let missing = PathBuf::from("plugins/does_not_exist.so");
+    let err = unsafe { DynamicPluginLoader::load(&missing) }.err().unwrap();
+    assert!(matches!(err, DynamicPluginError::Open { .. }));
+    assert!(err.to_string().contains("does_not_exist.so"));
+
+    // 普通的动态库没有导出插件符号
+    let path = library_path("engine_empty_library");
+    assert!(path.exists(), "{} was not built", path.display());
+    let err = unsafe { DynamicPluginLoader::load(&path) }.err().unwrap();
+    assert!(
+        matches!(err, DynamicPluginError::MissingSymbol { symbol: PLUGIN_ABI_SYMBOL, .. }),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_plugin_paths_only_lists_libraries() {
+    let directory = std::env::temp_dir().join(format!("engine_plugins_{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(directory.join("b.so"), b"").unwrap();
+    std::fs::write(directory.join("a.so"), b"").unwrap();
+    std::fs::write(directory.join("readme.txt"), b"").unwrap();
+
+    let paths = DynamicPluginLoader::plugin_paths(&directory).unwrap();
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(paths, [directory.join("a.so"), directory.join("b.so")]);
+}
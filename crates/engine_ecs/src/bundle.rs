@@ -0,0 +1,96 @@
+use std::any::TypeId;
+
+use super::component::Component;
+use super::entity_manager::EntityManager;
+
+/// 一组可以一次性插入实体的组件。
+///
+/// 单个组件以及最多 8 个组件组成的元组都实现了 `Bundle`。
+pub trait Bundle: 'static {
+    /// 注册 bundle 中的所有组件类型
+    fn register_components(manager: &mut EntityManager);
+
+    /// 为接下来插入的 `additional` 个 bundle 预留组件存储
+    fn reserve_components(manager: &mut EntityManager, additional: usize);
+
+    /// 将组件写入实体，不触发变更记录
+    fn insert_components(self, manager: &mut EntityManager, entity_id: usize);
+
+    /// bundle 中各组件的 TypeId
+    fn component_type_ids() -> Vec<TypeId>;
+}
+
+impl<C: 'static + Component> Bundle for C {
+    fn register_components(manager: &mut EntityManager) {
+        manager.register::<C>();
+    }
+
+    fn reserve_components(manager: &mut EntityManager, additional: usize) {
+        manager.reserve_components::<C>(additional);
+    }
+
+    fn insert_components(self, manager: &mut EntityManager, entity_id: usize) {
+        manager.insert_component_untracked(entity_id, self);
+    }
+
+    fn component_type_ids() -> Vec<TypeId> {
+        vec![TypeId::of::<C>()]
+    }
+}
+
+macro_rules! impl_bundle_for_tuple {
+    ($($name:ident),*) => {
+        impl<$($name: 'static + Component),*> Bundle for ($($name,)*) {
+            fn register_components(manager: &mut EntityManager) {
+                $(manager.register::<$name>();)*
+            }
+
+            fn reserve_components(manager: &mut EntityManager, additional: usize) {
+                $(manager.reserve_components::<$name>(additional);)*
+            }
+
+            #[allow(non_snake_case)]
+            fn insert_components(self, manager: &mut EntityManager, entity_id: usize) {
+                let ($($name,)*) = self;
+                $(manager.insert_component_untracked(entity_id, $name);)*
+            }
+
+            fn component_type_ids() -> Vec<TypeId> {
+                vec![$(TypeId::of::<$name>()),*]
+            }
+        }
+    };
+}
+
+impl_bundle_for_tuple!(A);
+impl_bundle_for_tuple!(A, B);
+impl_bundle_for_tuple!(A, B, C);
+impl_bundle_for_tuple!(A, B, C, D);
+impl_bundle_for_tuple!(A, B, C, D, E);
+impl_bundle_for_tuple!(A, B, C, D, E, F);
+impl_bundle_for_tuple!(A, B, C, D, E, F, G);
+impl_bundle_for_tuple!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32);
+    impl Component for Position {}
+
+    #[derive(Debug, PartialEq)]
+    struct Enemy;
+    impl Component for Enemy {}
+
+    #[test]
+    fn test_spawn_batch() {
+        let mut world = World::new();
+        let ids = world.spawn_batch((0..10).map(|i| (Position(i), Enemy)));
+
+        assert_eq!(ids.len(), 10);
+        assert_eq!(world.query::<Enemy>().len(), 10);
+        assert_eq!(world.get_component::<Position>(ids[3]), Some(&Position(3)));
+        assert!(world.entity(ids[9]).contains::<Enemy>());
+    }
+}
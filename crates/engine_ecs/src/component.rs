@@ -1,2 +1,199 @@
+use std::alloc::Layout;
+use std::any::TypeId;
+use std::borrow::Cow;
+
+use super::entity_cloner::EntityMap;
+use super::entity_manager::EntityManager;
+
 pub trait Component: Sized {
+    /// 注册组件的生命周期钩子，默认不注册任何钩子
+    fn register_hooks(_hooks: &mut ComponentHooks) {}
+}
+
+/// 组件钩子，参数为组件所在的实体
+pub type ComponentHook = fn(&mut EntityManager, usize);
+
+/// 组件添加到实体或从实体移除时触发的钩子
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ComponentHooks {
+    pub(crate) on_add: Option<ComponentHook>,
+    pub(crate) on_remove: Option<ComponentHook>,
+}
+
+impl ComponentHooks {
+    /// 组件被添加到实体之后调用（替换已有组件不会触发）
+    pub fn on_add(&mut self, hook: ComponentHook) -> &mut Self {
+        self.on_add = Some(hook);
+        self
+    }
+
+    /// 组件从实体移除之前调用，包括实体被销毁的情况
+    pub fn on_remove(&mut self, hook: ComponentHook) -> &mut Self {
+        self.on_remove = Some(hook);
+        self
+    }
+}
+
+/// 组件 ID，在组件注册时按顺序分配
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct ComponentId(usize);
+
+impl ComponentId {
+    pub fn new(index: usize) -> Self {
+        ComponentId(index)
+    }
+
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// 描述组件在内存中的表示方式，用于注册编译期不存在的组件类型（脚本、模组、控制台等）
+#[derive(Clone, Debug)]
+pub struct ComponentDescriptor {
+    name: Cow<'static, str>,
+    layout: Layout,
+    drop: Option<unsafe fn(*mut u8)>,
+    clone: Option<unsafe fn(*const u8, *mut u8)>,
+    serialize: Option<unsafe fn(*const u8) -> String>,
+}
+
+impl ComponentDescriptor {
+    /// 根据 Rust 类型创建描述
+    pub fn new<T: 'static>() -> Self {
+        unsafe fn drop_ptr<T>(ptr: *mut u8) {
+            unsafe { std::ptr::drop_in_place(ptr as *mut T) }
+        }
+
+        ComponentDescriptor {
+            name: Cow::Borrowed(std::any::type_name::<T>()),
+            layout: Layout::new::<T>(),
+            drop: std::mem::needs_drop::<T>().then_some(drop_ptr::<T> as unsafe fn(*mut u8)),
+            clone: None,
+            serialize: None,
+        }
+    }
+
+    /// 根据内存布局创建描述
+    ///
+    /// # Safety
+    ///
+    /// `drop` 必须能够安全地销毁一个符合 `layout` 的值。
+    pub unsafe fn new_with_layout(
+        name: impl Into<Cow<'static, str>>,
+        layout: Layout,
+        drop: Option<unsafe fn(*mut u8)>,
+    ) -> Self {
+        ComponentDescriptor {
+            name: name.into(),
+            layout,
+            drop,
+            clone: None,
+            serialize: None,
+        }
+    }
+
+    /// 设置克隆函数：从第一个指针读取组件，把克隆结果写入第二个指针
+    ///
+    /// # Safety
+    ///
+    /// `clone` 必须能够从一个符合描述的类型和 `layout` 的值克隆出同样类型的值，
+    /// 并写入未初始化的目标内存。
+    pub unsafe fn with_clone_fn(mut self, clone: unsafe fn(*const u8, *mut u8)) -> Self {
+        self.clone = Some(clone);
+        self
+    }
+
+    /// 设置序列化函数，用于控制台输出或存档
+    ///
+    /// # Safety
+    ///
+    /// `serialize` 必须能够读取一个符合描述的类型和 `layout` 的值。
+    pub unsafe fn with_serialize_fn(mut self, serialize: unsafe fn(*const u8) -> String) -> Self {
+        self.serialize = Some(serialize);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn drop_fn(&self) -> Option<unsafe fn(*mut u8)> {
+        self.drop
+    }
+
+    pub fn clone_fn(&self) -> Option<unsafe fn(*const u8, *mut u8)> {
+        self.clone
+    }
+
+    pub fn serialize_fn(&self) -> Option<unsafe fn(*const u8) -> String> {
+        self.serialize
+    }
+}
+
+/// 已注册组件的元信息
+#[derive(Clone, Debug)]
+pub struct ComponentInfo {
+    id: ComponentId,
+    // 动态组件没有对应的 Rust 类型
+    type_id: Option<TypeId>,
+    descriptor: ComponentDescriptor,
+    hooks: ComponentHooks,
+    // 在另一个 EntityManager 中注册同一类型，用于跨 World 复制
+    pub(crate) registrar: Option<fn(&mut EntityManager) -> ComponentId>,
+    // 复制到另一个 World 时重新映射组件中的实体 ID
+    pub(crate) map_entities: Option<unsafe fn(*mut u8, &EntityMap)>,
+    // 由其他组件的钩子维护（例如 RelatedBy），复制时会自动重建，不需要可复制
+    pub(crate) derived: bool,
+}
+
+impl ComponentInfo {
+    pub fn new(id: ComponentId, type_id: Option<TypeId>, descriptor: ComponentDescriptor) -> Self {
+        ComponentInfo {
+            id,
+            type_id,
+            descriptor,
+            hooks: ComponentHooks::default(),
+            registrar: None,
+            map_entities: None,
+            derived: false,
+        }
+    }
+
+    pub fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    pub fn type_id(&self) -> Option<TypeId> {
+        self.type_id
+    }
+
+    /// 组件的类型名称，例如 `engine_window::window::Window`
+    pub fn name(&self) -> &str {
+        self.descriptor.name()
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.descriptor.layout()
+    }
+
+    pub fn descriptor(&self) -> &ComponentDescriptor {
+        &self.descriptor
+    }
+
+    pub fn hooks(&self) -> &ComponentHooks {
+        &self.hooks
+    }
+
+    pub(crate) fn hooks_mut(&mut self) -> &mut ComponentHooks {
+        &mut self.hooks
+    }
+
+    pub(crate) fn descriptor_mut(&mut self) -> &mut ComponentDescriptor {
+        &mut self.descriptor
+    }
 }
@@ -1,18 +1,34 @@
+use std::alloc::{self, Layout};
 use std::collections::HashMap;
-use std::any::{Any, TypeId};
+use std::any::Any;
+use std::ptr::NonNull;
 
-use super::component::Component;
+use super::component::{Component, ComponentDescriptor};
 
 // @TODO: Write comment
 pub trait ComponentManagerTrait {
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
 
-	// @TODO: Write comment
+	// 以下方法不依赖具体的组件类型，供按 ComponentId 访问的 API 使用
 
 	fn has(&self, entity_id: usize) -> bool;
 	fn remove(&mut self, entity_id: usize);
-	fn get_type_id(&self) -> TypeId;
+	fn len(&self) -> usize;
+	fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+	fn entity_ids(&self) -> &[usize];
+	fn reserve(&mut self, additional: usize);
+	fn get_ptr(&self, entity_id: usize) -> Option<*const u8>;
+	fn get_ptr_mut(&mut self, entity_id: usize) -> Option<*mut u8>;
+
+	/// 把 `value` 指向的组件移动到存储中
+	///
+	/// # Safety
+	///
+	/// `value` 必须指向一个有效的、类型与该存储一致的组件，调用之后调用者不能再读取或销毁它。
+	unsafe fn insert_ptr(&mut self, entity_id: usize, value: *mut u8);
 }
 
 impl<T: 'static + Component> ComponentManagerTrait for ComponentManager<T> {
@@ -34,8 +50,29 @@ impl<T: 'static + Component> ComponentManagerTrait for ComponentManager<T> {
 		manager.remove(entity_id);
 	}
 
-	fn get_type_id(&self) -> TypeId {
-		TypeId::of::<T>()
+	fn len(&self) -> usize {
+		self.components.len()
+	}
+
+	fn entity_ids(&self) -> &[usize] {
+		&self.entity_ids
+	}
+
+	fn reserve(&mut self, additional: usize) {
+		ComponentManager::reserve(self, additional);
+	}
+
+	fn get_ptr(&self, entity_id: usize) -> Option<*const u8> {
+		self.borrow_component(entity_id).map(|c| c as *const T as *const u8)
+	}
+
+	fn get_ptr_mut(&mut self, entity_id: usize) -> Option<*mut u8> {
+		self.borrow_component_mut(entity_id).map(|c| c as *mut T as *mut u8)
+	}
+
+	unsafe fn insert_ptr(&mut self, entity_id: usize, value: *mut u8) {
+		let component = unsafe { std::ptr::read(value as *mut T) };
+		self.add(entity_id, component);
 	}
 }
 
@@ -76,6 +113,13 @@ impl<T: Component> ComponentManager<T> {
 		self.entity_id_map.contains_key(&entity_id)
 	}
 
+	/// 为至少 `additional` 个组件预留存储空间
+	pub fn reserve(&mut self, additional: usize) {
+		self.components.reserve(additional);
+		self.entity_ids.reserve(additional);
+		self.entity_id_map.reserve(additional);
+	}
+
 	pub fn add(&mut self, entity_id: usize, component: T) {
 		if self.has(entity_id) {
 			// Nothing to do? Throw error? Update component?
@@ -88,15 +132,20 @@ impl<T: Component> ComponentManager<T> {
 	}
 
 	pub fn remove(&mut self, entity_id: usize) {
+		self.take(entity_id);
+	}
+
+	/// 移除实体的组件并返回其值
+	pub fn take(&mut self, entity_id: usize) -> Option<T> {
 		if !self.has(entity_id) {
-			// Nothing to do? Throw error? Update component?
-			return;
+			return None;
 		}
 		let index = *self.entity_id_map.get(&entity_id).unwrap();
 		self.entity_id_map.insert(*self.entity_ids.last().unwrap(), index);
-		self.components.swap_remove(index);
+		let component = self.components.swap_remove(index);
 		self.entity_ids.swap_remove(index);
 		self.entity_id_map.remove(&entity_id);
+		Some(component)
 	}
 
 	pub fn borrow_component(&self, entity_id: usize) -> Option<&T> {
@@ -127,3 +176,145 @@ impl<T: Component> ComponentManager<T> {
 		&mut self.components
 	}
 }
+
+/// 类型擦除的组件存储，用于通过 [`ComponentDescriptor`] 注册的动态组件。
+///
+/// 组件按照描述中的内存布局连续存放，删除时使用 swap remove，与 [`ComponentManager`] 保持一致。
+pub struct BlobComponentManager {
+	item_layout: Layout,
+	drop: Option<unsafe fn(*mut u8)>,
+	data: NonNull<u8>,
+	capacity: usize,
+	entity_ids: Vec<usize>, // Same order with components
+	entity_id_map: HashMap<usize, usize>, // entity_id -> index in components
+}
+
+impl BlobComponentManager {
+	pub fn new(descriptor: &ComponentDescriptor) -> Self {
+		let item_layout = descriptor.layout();
+		// 零大小类型不需要分配内存
+		let capacity = if item_layout.size() == 0 { usize::MAX } else { 0 };
+		BlobComponentManager {
+			item_layout,
+			drop: descriptor.drop_fn(),
+			data: dangling(item_layout.align()),
+			capacity,
+			entity_ids: Vec::new(),
+			entity_id_map: HashMap::new(),
+		}
+	}
+
+	fn slot(&self, index: usize) -> *mut u8 {
+		unsafe { self.data.as_ptr().add(index * self.item_layout.size()) }
+	}
+
+	fn array_layout(&self, capacity: usize) -> Layout {
+		let size = self.item_layout.size().checked_mul(capacity).expect("Capacity overflow");
+		Layout::from_size_align(size, self.item_layout.align()).expect("Capacity overflow")
+	}
+
+	fn grow(&mut self, required: usize) {
+		if required <= self.capacity {
+			return;
+		}
+		let new_capacity = required.max(self.capacity * 2).max(4);
+		let new_layout = self.array_layout(new_capacity);
+		let ptr = unsafe {
+			if self.capacity == 0 {
+				alloc::alloc(new_layout)
+			} else {
+				alloc::realloc(self.data.as_ptr(), self.array_layout(self.capacity), new_layout.size())
+			}
+		};
+		self.data = NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(new_layout));
+		self.capacity = new_capacity;
+	}
+}
+
+impl ComponentManagerTrait for BlobComponentManager {
+	fn as_any(&self) -> &dyn Any {
+		self as &dyn Any
+	}
+
+	fn as_any_mut(&mut self) -> &mut dyn Any {
+		self as &mut dyn Any
+	}
+
+	fn has(&self, entity_id: usize) -> bool {
+		self.entity_id_map.contains_key(&entity_id)
+	}
+
+	fn remove(&mut self, entity_id: usize) {
+		let Some(index) = self.entity_id_map.remove(&entity_id) else {
+			return;
+		};
+		let last = self.entity_ids.len() - 1;
+		unsafe {
+			if let Some(drop) = self.drop {
+				drop(self.slot(index));
+			}
+			if index != last {
+				std::ptr::copy_nonoverlapping(self.slot(last), self.slot(index), self.item_layout.size());
+				self.entity_id_map.insert(self.entity_ids[last], index);
+			}
+		}
+		self.entity_ids.swap_remove(index);
+	}
+
+	fn len(&self) -> usize {
+		self.entity_ids.len()
+	}
+
+	fn entity_ids(&self) -> &[usize] {
+		&self.entity_ids
+	}
+
+	fn reserve(&mut self, additional: usize) {
+		self.grow(self.entity_ids.len() + additional);
+		self.entity_ids.reserve(additional);
+		self.entity_id_map.reserve(additional);
+	}
+
+	fn get_ptr(&self, entity_id: usize) -> Option<*const u8> {
+		self.entity_id_map.get(&entity_id).map(|index| self.slot(*index) as *const u8)
+	}
+
+	fn get_ptr_mut(&mut self, entity_id: usize) -> Option<*mut u8> {
+		self.entity_id_map.get(&entity_id).map(|index| self.slot(*index))
+	}
+
+	unsafe fn insert_ptr(&mut self, entity_id: usize, value: *mut u8) {
+		if self.has(entity_id) {
+			// 与 ComponentManager::add 一致：已存在时丢弃新值
+			if let Some(drop) = self.drop {
+				unsafe { drop(value) };
+			}
+			return;
+		}
+		let index = self.entity_ids.len();
+		self.grow(index + 1);
+		unsafe {
+			std::ptr::copy_nonoverlapping(value, self.slot(index), self.item_layout.size());
+		}
+		self.entity_ids.push(entity_id);
+		self.entity_id_map.insert(entity_id, index);
+	}
+}
+
+impl Drop for BlobComponentManager {
+	fn drop(&mut self) {
+		if let Some(drop) = self.drop {
+			for index in 0..self.entity_ids.len() {
+				unsafe { drop(self.slot(index)) };
+			}
+		}
+		if self.item_layout.size() != 0 && self.capacity != 0 {
+			unsafe { alloc::dealloc(self.data.as_ptr(), self.array_layout(self.capacity)) };
+		}
+	}
+}
+
+fn dangling(align: usize) -> NonNull<u8> {
+	// 对齐值总是非零的 2 的幂
+	NonNull::new(std::ptr::without_provenance_mut(align)).unwrap()
+}
@@ -0,0 +1,400 @@
+use std::alloc::{self, Layout};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::ptr::NonNull;
+
+use tracing::warn;
+
+use super::component::{Component, ComponentId, ComponentInfo};
+use super::entity_manager::EntityManager;
+use super::relationship::{Relation, RelationKind};
+
+/// 复制实体时旧实体 ID 到新实体 ID 的映射
+#[derive(Clone, Debug, Default)]
+pub struct EntityMap {
+    map: HashMap<usize, usize>,
+}
+
+impl EntityMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, source: usize, target: usize) {
+        self.map.insert(source, target);
+    }
+
+    pub fn get(&self, source: usize) -> Option<usize> {
+        self.map.get(&source).copied()
+    }
+
+    /// 映射实体 ID，不在映射中的 ID 保持不变，只适用于同一个 World 中的 ID
+    pub fn map(&self, source: usize) -> usize {
+        self.get(source).unwrap_or(source)
+    }
+
+    /// 映射实体 ID，不在映射中时返回 `None`
+    pub fn try_map(&self, source: usize) -> Option<usize> {
+        self.get(source)
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+/// 组件中保存了其他实体的 ID，复制到另一个 World 时需要重新映射。
+///
+/// 不在映射中的 ID 在另一个 World 中指向无关的实体或不存在的实体，应当丢弃。
+pub trait MapEntities {
+    fn map_entities(&mut self, entity_map: &EntityMap);
+}
+
+impl<R: RelationKind> Clone for Relation<R> {
+    fn clone(&self) -> Self {
+        Relation::new(self.targets().iter().copied())
+    }
+}
+
+impl<R: RelationKind> MapEntities for Relation<R> {
+    fn map_entities(&mut self, entity_map: &EntityMap) {
+        *self = Relation::new(self.targets().iter().filter_map(|id| entity_map.try_map(*id)));
+    }
+}
+
+unsafe fn clone_ptr<T: Clone>(src: *const u8, dst: *mut u8) {
+    unsafe { std::ptr::write(dst as *mut T, (*(src as *const T)).clone()) };
+}
+
+unsafe fn map_entities_ptr<T: MapEntities>(ptr: *mut u8, entity_map: &EntityMap) {
+    unsafe { (*(ptr as *mut T)).map_entities(entity_map) };
+}
+
+fn register_clonable_in<T: 'static + Component + Clone>(manager: &mut EntityManager) -> ComponentId {
+    manager.register_clonable::<T>();
+    manager.component_id::<T>().unwrap()
+}
+
+impl EntityManager {
+    /// 注册组件并允许它被 [`EntityCloner`] 复制
+    pub fn register_clonable<T: 'static + Component + Clone>(&mut self) -> &mut Self {
+        self.register::<T>();
+        let component_id = self.component_id::<T>().unwrap();
+        let info = self.component_info_mut(component_id);
+        // SAFETY: clone_ptr::<T> 与 T 的描述匹配
+        let descriptor = unsafe { info.descriptor_mut().clone().with_clone_fn(clone_ptr::<T>) };
+        *info.descriptor_mut() = descriptor;
+        info.registrar = Some(register_clonable_in::<T>);
+        self
+    }
+
+    /// 注册组件的实体 ID 映射，跨 World 复制时会调用 [`MapEntities::map_entities`]
+    pub fn register_map_entities<T: 'static + Component + MapEntities>(&mut self) -> &mut Self {
+        self.register::<T>();
+        let component_id = self.component_id::<T>().unwrap();
+        self.component_info_mut(component_id).map_entities = Some(map_entities_ptr::<T>);
+        self
+    }
+
+    /// 注册可复制的关系，复制后的关系会指向新的实体
+    pub fn register_clonable_relation<R: RelationKind>(&mut self) -> &mut Self {
+        self.register_clonable::<Relation<R>>();
+        self.register_map_entities::<Relation<R>>()
+    }
+
+    /// 实体上没有通过 [`EntityManager::register_clonable`] 注册的组件名称，
+    /// 不包括复制后会自动重建的组件（例如 [`RelatedBy`](crate::prelude::RelatedBy)）
+    pub fn non_clonable_components(&self, entity_id: usize) -> Vec<&str> {
+        self.entity_component_ids(entity_id)
+            .iter()
+            .map(|component_id| self.component_info(*component_id).unwrap())
+            .filter(|info| info.descriptor().clone_fn().is_none() && !info.derived)
+            .map(|info| info.name())
+            .collect()
+    }
+
+    /// 使用默认的 [`EntityCloner`] 复制实体，实体不存在时返回 `None`
+    pub fn clone_entity(&mut self, source: usize) -> Option<usize> {
+        EntityCloner::new().clone_entity(self, source)
+    }
+}
+
+/// 复制实体的组件，只会复制通过 [`EntityManager::register_clonable`] 注册过的组件。
+///
+/// ```ignore
+/// let copy = EntityCloner::new()
+///     .deny::<Health>()
+///     .clone_entity(manager, enemy)
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct EntityCloner {
+    allow: Vec<TypeId>,
+    deny: Vec<TypeId>,
+    allow_ids: Vec<ComponentId>,
+    deny_ids: Vec<ComponentId>,
+}
+
+impl EntityCloner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 只复制允许列表中的组件（列表为空时复制所有可复制的组件）
+    pub fn allow<T: 'static + Component>(mut self) -> Self {
+        self.allow.push(TypeId::of::<T>());
+        self
+    }
+
+    /// 不复制该组件
+    pub fn deny<T: 'static + Component>(mut self) -> Self {
+        self.deny.push(TypeId::of::<T>());
+        self
+    }
+
+    /// 按 ComponentId 允许动态组件，ID 属于源实体所在的 World
+    pub fn allow_id(mut self, component_id: ComponentId) -> Self {
+        self.allow_ids.push(component_id);
+        self
+    }
+
+    pub fn deny_id(mut self, component_id: ComponentId) -> Self {
+        self.deny_ids.push(component_id);
+        self
+    }
+
+    fn is_allowed(&self, info: &ComponentInfo) -> bool {
+        if info.descriptor().clone_fn().is_none() {
+            return false;
+        }
+        let in_list = |types: &[TypeId], ids: &[ComponentId]| {
+            ids.contains(&info.id()) || info.type_id().is_some_and(|t| types.contains(&t))
+        };
+        let allowed = (self.allow.is_empty() && self.allow_ids.is_empty())
+            || in_list(&self.allow, &self.allow_ids);
+        allowed && !in_list(&self.deny, &self.deny_ids)
+    }
+
+    /// 在同一个 World 中复制实体，返回新实体的 ID，实体不存在时返回 `None`
+    pub fn clone_entity(&self, manager: &mut EntityManager, source: usize) -> Option<usize> {
+        if !manager.has_entity(source) {
+            return None;
+        }
+        let target = manager.create_entity();
+        let component_ids = manager.entity_component_ids(source).to_vec();
+        for component_id in component_ids {
+            let info = manager.component_info(component_id).unwrap();
+            if !self.is_allowed(info) {
+                continue;
+            }
+            let clone = info.descriptor().clone_fn().unwrap();
+            with_buffer(info.layout(), |buffer| unsafe {
+                clone(manager.get_component_by_id(source, component_id).unwrap(), buffer);
+                manager.insert_component_by_id(target, component_id, buffer);
+            });
+        }
+        Some(target)
+    }
+
+    /// 把一组实体复制到另一个 World。
+    ///
+    /// 复制的组件中指向这组实体的 ID 会被映射为新实体的 ID，关系中指向其他实体的目标会被丢弃。
+    pub fn clone_entities_into(
+        &self,
+        source: &EntityManager,
+        entities: &[usize],
+        target: &mut EntityManager,
+    ) -> EntityMap {
+        // 先创建所有实体，组件钩子（例如关系）插入时就能看到完整的映射
+        let mut entity_map = EntityMap::new();
+        for entity in entities {
+            if source.has_entity(*entity) {
+                entity_map.insert(*entity, target.create_entity());
+            }
+        }
+
+        for entity in entities {
+            let Some(new_entity) = entity_map.get(*entity) else {
+                continue;
+            };
+            for component_id in source.entity_component_ids(*entity) {
+                let info = source.component_info(*component_id).unwrap();
+                if !self.is_allowed(info) {
+                    continue;
+                }
+                let Some(target_id) = target.resolve_component(info) else {
+                    warn!("Cannot copy component {} to another world", info.name());
+                    continue;
+                };
+                let clone = info.descriptor().clone_fn().unwrap();
+                let map_entities = info.map_entities;
+                with_buffer(info.layout(), |buffer| unsafe {
+                    clone(source.get_component_by_id(*entity, *component_id).unwrap(), buffer);
+                    if let Some(map_entities) = map_entities {
+                        map_entities(buffer, &entity_map);
+                    }
+                    target.insert_component_by_id(new_entity, target_id, buffer);
+                });
+            }
+        }
+        entity_map
+    }
+}
+
+impl EntityManager {
+    // 在当前 EntityManager 中找到（或注册）与另一个 World 的组件对应的 ComponentId
+    fn resolve_component(&mut self, info: &ComponentInfo) -> Option<ComponentId> {
+        if info.type_id().is_some() {
+            let register = info.registrar?;
+            let component_id = register(self);
+            self.component_info_mut(component_id).map_entities = info.map_entities;
+            return Some(component_id);
+        }
+        // 动态组件按名称和内存布局匹配
+        let existing = self
+            .components()
+            .iter()
+            .find(|other| {
+                other.type_id().is_none()
+                    && other.name() == info.name()
+                    && other.layout() == info.layout()
+            })
+            .map(|other| other.id());
+        Some(existing.unwrap_or_else(|| {
+            self.register_component_with_descriptor(info.descriptor().clone())
+        }))
+    }
+}
+
+// 为一个组件值分配临时内存，组件在回调中被移动走，之后只释放内存
+fn with_buffer(layout: Layout, f: impl FnOnce(*mut u8)) {
+    if layout.size() == 0 {
+        f(NonNull::new(std::ptr::without_provenance_mut(layout.align())).unwrap().as_ptr());
+        return;
+    }
+    unsafe {
+        let buffer = alloc::alloc(layout);
+        if buffer.is_null() {
+            alloc::handle_alloc_error(layout);
+        }
+        f(buffer);
+        alloc::dealloc(buffer, layout);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Sprite(String);
+    impl Component for Sprite {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Health(u32);
+    impl Component for Health {}
+
+    // 未注册为可复制的组件
+    struct Unique;
+    impl Component for Unique {}
+
+    struct Targets;
+    impl RelationKind for Targets {}
+
+    #[test]
+    fn test_clone_entity_with_filters() {
+        let mut world = World::new();
+        let manager = world.entity_manager_mut();
+        manager.register_clonable::<Sprite>().register_clonable::<Health>();
+        let enemy = manager.spawn((Sprite("enemy.png".into()), Health(3), Unique));
+
+        let copy = manager.clone_entity(enemy).unwrap();
+        assert_eq!(manager.borrow_component::<Sprite>(copy), Some(&Sprite("enemy.png".into())));
+        assert_eq!(manager.borrow_component::<Health>(copy), Some(&Health(3)));
+        assert!(!manager.entity(copy).contains::<Unique>());
+
+        let copy = EntityCloner::new().deny::<Health>().clone_entity(manager, enemy).unwrap();
+        assert!(manager.entity(copy).contains::<Sprite>());
+        assert!(!manager.entity(copy).contains::<Health>());
+
+        let entity_count = manager.entity_ids().len();
+        manager.remove_entity(enemy);
+        assert_eq!(manager.clone_entity(enemy), None);
+        assert_eq!(manager.entity_ids().len(), entity_count - 1);
+    }
+
+    #[test]
+    fn test_copy_entities_between_worlds() {
+        let mut prefabs = World::new();
+        let manager = prefabs.entity_manager_mut();
+        manager.register_clonable::<Sprite>().register_clonable_relation::<Targets>();
+        let turret = manager.spawn(Sprite("turret.png".into()));
+        let target = manager.spawn(Sprite("target.png".into()));
+        manager.relate::<Targets>(turret, target);
+
+        let mut game = World::new();
+        game.create_entity();
+        let entity_map = prefabs.copy_entities_to(&[turret, target], &mut game);
+
+        let new_turret = entity_map.get(turret).unwrap();
+        let new_target = entity_map.get(target).unwrap();
+        assert_ne!(new_turret, turret);
+        assert_eq!(game.get_component::<Sprite>(new_turret), Some(&Sprite("turret.png".into())));
+        assert_eq!(game.entity_manager().relation_targets::<Targets>(new_turret), &[new_target]);
+        assert_eq!(game.entity_manager().related_sources::<Targets>(new_target), &[new_turret]);
+
+        let entity_map = prefabs.move_entities_to(&[target], &mut game);
+        assert!(!prefabs.entity_manager().has_entity(target));
+        assert!(prefabs.entity_manager().relation_targets::<Targets>(turret).is_empty());
+        assert!(game.entity(entity_map.map(target)).contains::<Sprite>());
+    }
+
+    #[test]
+    fn test_copy_relation_to_entity_outside_copied_set() {
+        let mut prefabs = World::new();
+        let manager = prefabs.entity_manager_mut();
+        manager.register_clonable::<Sprite>().register_clonable_relation::<Targets>();
+        let turret = manager.spawn(Sprite("turret.png".into()));
+        let target = manager.spawn(Sprite("target.png".into()));
+        manager.relate::<Targets>(turret, target);
+
+        // 目标 World 中已经有相同 ID 的实体
+        let mut game = World::new();
+        let existing: Vec<usize> = (0..2).map(|_| game.spawn(Sprite("tree.png".into()))).collect();
+        assert!(existing.contains(&target));
+        let entity_map = prefabs.copy_entities_to(&[turret], &mut game);
+
+        let new_turret = entity_map.get(turret).unwrap();
+        assert!(game.entity_manager().relation_targets::<Targets>(new_turret).is_empty());
+        for entity in existing {
+            assert!(game.entity_manager().related_sources::<Targets>(entity).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_move_entity_with_non_clonable_component() {
+        let mut source = World::new();
+        let manager = source.entity_manager_mut();
+        manager.register_clonable::<Sprite>();
+        let unique = manager.spawn((Sprite("unique.png".into()), Unique));
+        let sprite = manager.spawn(Sprite("sprite.png".into()));
+        assert_eq!(
+            source.entity_manager().non_clonable_components(unique),
+            vec![std::any::type_name::<Unique>()]
+        );
+
+        // 移动会丢失 Unique，这个实体留在原来的 World 中
+        let mut target = World::new();
+        let entity_map = source.move_entities_to(&[unique, sprite], &mut target);
+        assert_eq!(entity_map.get(unique), None);
+        assert!(source.entity(unique).contains::<Unique>());
+        assert!(!source.entity_manager().has_entity(sprite));
+        let moved = entity_map.get(sprite).unwrap();
+        assert_eq!(target.get_component::<Sprite>(moved), Some(&Sprite("sprite.png".into())));
+        assert_eq!(target.entity_manager().entity_ids().len(), 1);
+    }
+}
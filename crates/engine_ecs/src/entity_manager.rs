@@ -1,26 +1,73 @@
 use std::any::TypeId;
 use std::collections::HashMap;
 use std::mem::transmute;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
 use std::vec;
 
+use tracing::warn;
+
 use crate::resource::ResourceManager;
 
-use super::component::Component;
+use super::bundle::Bundle;
+use super::component::{Component, ComponentDescriptor, ComponentId, ComponentInfo};
 use super::component_manager::{
-    ComponentManager, ComponentManagerTrait, cast_manager, cast_manager_mut,
+    BlobComponentManager, ComponentManager, ComponentManagerTrait, cast_manager, cast_manager_mut,
 };
 use super::entity::Entity;
+use super::entity_ref::{EntityMut, EntityRef};
+
+// 实体 ID 的预留状态，只需要 `&self` 即可预留，可在线程间共享
+struct Reservations {
+    // 可复用的实体 ID，按先进先出的顺序分配
+    availables: RwLock<Vec<usize>>,
+    // 自上次 flush 以来预留的 ID 数量
+    cursor: AtomicUsize,
+    // 上次 flush 时的实体数量
+    len: AtomicUsize,
+}
+
+impl Reservations {
+    fn reserve(&self) -> usize {
+        let availables = self.availables.read().unwrap();
+        let index = self.cursor.fetch_add(1, Ordering::Relaxed);
+        if index < availables.len() {
+            availables[index]
+        } else {
+            self.len.load(Ordering::Relaxed) + index - availables.len()
+        }
+    }
+}
+
+/// 可以发送到其他线程的实体 ID 预留句柄。
+///
+/// 预留的 ID 在下一次 [`EntityManager::flush`] 时才会成为存活的实体。
+#[derive(Clone)]
+pub struct EntityReserver(Arc<Reservations>);
+
+impl EntityReserver {
+    pub fn reserve_entity(&self) -> usize {
+        self.0.reserve()
+    }
+}
 
 pub struct Entities {
     entities: Vec<Entity>,
-    availables: Vec<usize>,
+    // 每个实体当前拥有的组件，按 ComponentId 排序
+    components: Vec<Vec<ComponentId>>,
+    reservations: Arc<Reservations>,
 }
 
 impl Entities {
     fn new() -> Self {
         Entities {
             entities: vec![],
-            availables: vec![],
+            components: vec![],
+            reservations: Arc::new(Reservations {
+                availables: RwLock::new(vec![]),
+                cursor: AtomicUsize::new(0),
+                len: AtomicUsize::new(0),
+            }),
         }
     }
 
@@ -28,15 +75,47 @@ impl Entities {
         entity_id < self.entities.len() && self.entities[entity_id].is_alive()
     }
 
-    fn create(&mut self) -> usize {
-        if self.availables.len() > 0 {
-            let index = self.availables.remove(0);
+    /// 预留一个实体 ID，不需要可变访问
+    pub fn reserve_entity(&self) -> usize {
+        self.reservations.reserve()
+    }
+
+    fn reserver(&self) -> EntityReserver {
+        EntityReserver(self.reservations.clone())
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.entities.reserve(additional);
+        self.components.reserve(additional);
+    }
+
+    /// 将所有预留的 ID 变为存活的实体
+    fn flush(&mut self) {
+        if self.reservations.cursor.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+        let reservations = self.reservations.clone();
+        self.flush_locked(&mut reservations.availables.write().unwrap());
+    }
+
+    // 调用者持有空闲列表的写锁
+    fn flush_locked(&mut self, availables: &mut Vec<usize>) {
+        let reserved = self.reservations.cursor.swap(0, Ordering::Relaxed);
+        let reused = reserved.min(availables.len());
+        for index in availables.drain(..reused) {
             self.entities[index].reset();
-            return index;
         }
-        let entity = Entity::new();
-        self.entities.push(entity);
-        self.entities.len() - 1
+        for _ in reused..reserved {
+            self.entities.push(Entity::new());
+            self.components.push(vec![]);
+        }
+        self.reservations.len.store(self.entities.len(), Ordering::Relaxed);
+    }
+
+    fn create(&mut self) -> usize {
+        let index = self.reservations.reserve();
+        self.flush();
+        index
     }
 
     fn remove(&mut self, entity_id: usize) {
@@ -44,8 +123,40 @@ impl Entities {
             // @TODO: Error handling
             return;
         }
+        // 预留的 ID 依赖空闲列表的顺序，flush 和回收 ID 必须在同一次加锁中完成，
+        // 否则其他线程可能在两者之间按旧的空闲列表预留 ID
+        let reservations = self.reservations.clone();
+        let mut availables = reservations.availables.write().unwrap();
+        self.flush_locked(&mut availables);
         self.entities[entity_id].invalid();
-        self.availables.push(entity_id);
+        self.components[entity_id].clear();
+        availables.push(entity_id);
+    }
+
+    fn component_ids(&self, entity_id: usize) -> &[ComponentId] {
+        &self.components[entity_id]
+    }
+
+    fn take_component_ids(&mut self, entity_id: usize) -> Vec<ComponentId> {
+        std::mem::take(&mut self.components[entity_id])
+    }
+
+    fn alive_ids(&self) -> Vec<usize> {
+        (0..self.entities.len()).filter(|id| self.has(*id)).collect()
+    }
+
+    fn insert_component_id(&mut self, entity_id: usize, component_id: ComponentId) {
+        let ids = &mut self.components[entity_id];
+        if let Err(index) = ids.binary_search(&component_id) {
+            ids.insert(index, component_id);
+        }
+    }
+
+    fn remove_component_id(&mut self, entity_id: usize, component_id: ComponentId) {
+        let ids = &mut self.components[entity_id];
+        if let Ok(index) = ids.binary_search(&component_id) {
+            ids.remove(index);
+        }
     }
 }
 
@@ -77,7 +188,8 @@ impl EntityIdAccessor {
             true
         } else {
             let updated_frame = *self.updated_frame_map.get(&type_id).unwrap();
-            manager.get_updated_frame::<T>() != updated_frame
+            // 添加组件时记录的是当前帧，缓存之后的同一帧内也可能有修改（例如先查询再生成实体），相等时也要刷新
+            manager.get_updated_frame::<T>() >= updated_frame
         };
 
         if needs_update {
@@ -107,8 +219,8 @@ impl EntityIdAccessor {
             true
         } else {
             let updated_frame = *self.updated_frame_map.get(&type_id).unwrap();
-            manager.get_updated_frame::<T1>() != updated_frame
-                || manager.get_updated_frame::<T2>() != updated_frame
+            manager.get_updated_frame::<T1>() >= updated_frame
+                || manager.get_updated_frame::<T2>() >= updated_frame
         };
 
         if needs_update {
@@ -149,9 +261,9 @@ impl EntityIdAccessor {
             true
         } else {
             let updated_frame = *self.updated_frame_map.get(&type_id).unwrap();
-            manager.get_updated_frame::<T1>() != updated_frame
-                || manager.get_updated_frame::<T2>() != updated_frame
-                || manager.get_updated_frame::<T3>() != updated_frame
+            manager.get_updated_frame::<T1>() >= updated_frame
+                || manager.get_updated_frame::<T2>() >= updated_frame
+                || manager.get_updated_frame::<T3>() >= updated_frame
         };
 
         if needs_update {
@@ -195,10 +307,10 @@ impl EntityIdAccessor {
             true
         } else {
             let updated_frame = *self.updated_frame_map.get(&type_id).unwrap();
-            manager.get_updated_frame::<T1>() != updated_frame
-                || manager.get_updated_frame::<T2>() != updated_frame
-                || manager.get_updated_frame::<T3>() != updated_frame
-                || manager.get_updated_frame::<T4>() != updated_frame
+            manager.get_updated_frame::<T1>() >= updated_frame
+                || manager.get_updated_frame::<T2>() >= updated_frame
+                || manager.get_updated_frame::<T3>() >= updated_frame
+                || manager.get_updated_frame::<T4>() >= updated_frame
         };
 
         if needs_update {
@@ -223,9 +335,14 @@ impl EntityIdAccessor {
 
 pub struct EntityManager {
     entities: Entities,
-    manager_map: HashMap<TypeId, Box<dyn ComponentManagerTrait>>,
-    frame: u64,                              // Rename
-    updated_frame_map: HashMap<TypeId, u64>, // Rename
+    // 按 ComponentId 索引的组件存储
+    managers: Vec<Box<dyn ComponentManagerTrait>>,
+    component_ids: HashMap<TypeId, ComponentId>,
+    component_infos: Vec<ComponentInfo>,
+    frame: u64,               // Rename
+    updated_frames: Vec<u64>, // 按 ComponentId 索引，Rename
+    // 按 ComponentId 索引，除了添加和移除，可变借用组件时也会更新
+    changed_frames: Vec<u64>,
     resource_manager: ResourceManager,
 }
 
@@ -233,9 +350,12 @@ impl EntityManager {
     pub fn new() -> Self {
         EntityManager {
             entities: Entities::new(),
-            manager_map: HashMap::new(),
+            managers: Vec::new(),
+            component_ids: HashMap::new(),
+            component_infos: Vec::new(),
             frame: 0,
-            updated_frame_map: HashMap::new(),
+            updated_frames: Vec::new(),
+            changed_frames: Vec::new(),
             resource_manager: ResourceManager::new(),
         }
     }
@@ -249,33 +369,207 @@ impl EntityManager {
     }
 
     fn get_updated_frame<T: 'static + Component>(&self) -> u64 {
-        *self.updated_frame_map.get(&TypeId::of::<T>()).unwrap()
+        self.updated_frames[self.component_index::<T>()]
     }
 
     pub fn register<T: 'static + Component>(&mut self) -> &mut Self {
         // @TODO: Error handling if already registered?
         if !self.has_component_manager::<T>() {
             let type_id = TypeId::of::<T>();
-            self.manager_map
-                .insert(type_id, Box::new(ComponentManager::<T>::new()));
-            self.updated_frame_map.insert(type_id, self.get_frame());
+            let component_id = self.register_manager(
+                Some(type_id),
+                ComponentDescriptor::new::<T>(),
+                Box::new(ComponentManager::<T>::new()),
+            );
+            self.component_ids.insert(type_id, component_id);
+            T::register_hooks(self.component_infos[component_id.index()].hooks_mut());
         }
         self
     }
 
+    /// 注册一个动态组件类型，每次调用都会分配新的 ComponentId
+    pub fn register_component_with_descriptor(
+        &mut self,
+        descriptor: ComponentDescriptor,
+    ) -> ComponentId {
+        let manager = Box::new(BlobComponentManager::new(&descriptor));
+        self.register_manager(None, descriptor, manager)
+    }
+
+    fn register_manager(
+        &mut self,
+        type_id: Option<TypeId>,
+        descriptor: ComponentDescriptor,
+        manager: Box<dyn ComponentManagerTrait>,
+    ) -> ComponentId {
+        let component_id = ComponentId::new(self.managers.len());
+        self.managers.push(manager);
+        self.updated_frames.push(self.get_frame());
+        self.changed_frames.push(self.get_frame());
+        self.component_infos
+            .push(ComponentInfo::new(component_id, type_id, descriptor));
+        component_id
+    }
+
+    /// 已注册的所有组件
+    pub fn components(&self) -> &[ComponentInfo] {
+        &self.component_infos
+    }
+
+    fn mark_changed(&mut self, component_id: ComponentId, frame: u64) {
+        self.updated_frames[component_id.index()] = frame;
+        self.mark_mutated(component_id.index(), frame);
+    }
+
+    fn mark_mutated(&mut self, index: usize, frame: u64) {
+        self.changed_frames[index] = self.changed_frames[index].max(frame);
+    }
+
+    /// 组件最近一次被添加、移除或可变借用时的帧，组件未注册时返回 `None`。
+    ///
+    /// 只能说明这一类组件可能被修改过，不能说明具体是哪个实体或哪个字段
+    pub fn component_changed_frame(&self, component_id: ComponentId) -> Option<u64> {
+        self.changed_frames.get(component_id.index()).copied()
+    }
+
+    fn trigger_on_add(&mut self, component_id: ComponentId, entity_id: usize) {
+        if let Some(hook) = self.component_infos[component_id.index()].hooks().on_add {
+            hook(self, entity_id);
+        }
+    }
+
+    fn trigger_on_remove(&mut self, component_id: ComponentId, entity_id: usize) {
+        if let Some(hook) = self.component_infos[component_id.index()].hooks().on_remove {
+            hook(self, entity_id);
+        }
+    }
+
+    /// 获取已注册组件的 ID
+    pub fn component_id<T: 'static + Component>(&self) -> Option<ComponentId> {
+        self.component_ids.get(&TypeId::of::<T>()).copied()
+    }
+
+    /// 获取组件的元信息
+    pub fn component_info(&self, component_id: ComponentId) -> Option<&ComponentInfo> {
+        self.component_infos.get(component_id.index())
+    }
+
+    pub(crate) fn component_info_mut(&mut self, component_id: ComponentId) -> &mut ComponentInfo {
+        &mut self.component_infos[component_id.index()]
+    }
+
     pub fn create_entity(&mut self) -> usize {
         self.entities.create()
     }
 
+    /// 创建实体并插入 bundle 中的所有组件
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> usize {
+        self.spawn_batch(std::iter::once(bundle))[0]
+    }
+
+    /// 批量创建实体：预先分配存储，并且整批只触发一次变更记录
+    pub fn spawn_batch<B, I>(&mut self, bundles: I) -> Vec<usize>
+    where
+        B: Bundle,
+        I: IntoIterator<Item = B>,
+    {
+        let bundles = bundles.into_iter();
+        let (additional, _) = bundles.size_hint();
+
+        B::register_components(self);
+        B::reserve_components(self, additional);
+        self.entities.reserve(additional);
+
+        let mut entity_ids = Vec::with_capacity(additional);
+        for bundle in bundles {
+            let entity_id = self.entities.create();
+            bundle.insert_components(self, entity_id);
+            entity_ids.push(entity_id);
+        }
+
+        let frame = self.get_frame();
+        for type_id in B::component_type_ids() {
+            let component_id = self.component_ids[&type_id];
+            self.mark_changed(component_id, frame);
+        }
+        entity_ids
+    }
+
+    /// 预留一个实体 ID，只需要 `&self`，在下一次 [`flush`](Self::flush) 时生效
+    pub fn reserve_entity(&self) -> usize {
+        self.entities.reserve_entity()
+    }
+
+    /// 获取可以发送到其他线程的预留句柄
+    pub fn entity_reserver(&self) -> EntityReserver {
+        self.entities.reserver()
+    }
+
+    /// 将预留的实体 ID 变为存活的实体
+    pub fn flush(&mut self) {
+        self.entities.flush();
+    }
+
+    /// 检查实体是否存在
+    pub fn has_entity(&self, entity_id: usize) -> bool {
+        self.entities.has(entity_id)
+    }
+
+    /// 所有存活实体的 ID
+    pub fn entity_ids(&self) -> Vec<usize> {
+        self.entities.alive_ids()
+    }
+
+    /// 获取实体拥有的组件 ID（按 ID 排序）
+    pub fn entity_component_ids(&self, entity_id: usize) -> &[ComponentId] {
+        if !self.entities.has(entity_id) {
+            return &[];
+        }
+        self.entities.component_ids(entity_id)
+    }
+
+    pub fn entity(&self, entity_id: usize) -> EntityRef<'_> {
+        match self.get_entity(entity_id) {
+            Some(entity) => entity,
+            None => panic!("Entity {} does not exist", entity_id),
+        }
+    }
+
+    pub fn entity_mut(&mut self, entity_id: usize) -> EntityMut<'_> {
+        match self.get_entity_mut(entity_id) {
+            Some(entity) => entity,
+            None => panic!("Entity {} does not exist", entity_id),
+        }
+    }
+
+    pub fn get_entity(&self, entity_id: usize) -> Option<EntityRef<'_>> {
+        match self.entities.has(entity_id) {
+            true => Some(EntityRef::new(self, entity_id)),
+            false => None,
+        }
+    }
+
+    pub fn get_entity_mut(&mut self, entity_id: usize) -> Option<EntityMut<'_>> {
+        self.flush();
+        match self.entities.has(entity_id) {
+            true => Some(EntityMut::new(self, entity_id)),
+            false => None,
+        }
+    }
+
     pub fn remove_entity(&mut self, entity_id: usize) {
+        if !self.entities.has(entity_id) {
+            return;
+        }
         let frame = self.get_frame();
-        for (_, manager) in self.manager_map.iter_mut() {
-            if manager.has(entity_id) {
-                manager.remove(entity_id);
-                // @TODO: Write comment for +1
-                self.updated_frame_map
-                    .insert(manager.get_type_id(), frame + 1);
-            }
+        // 先取出组件列表：钩子可能会再次销毁同一个实体（例如关系形成环）
+        let component_ids = self.entities.take_component_ids(entity_id);
+        // 只需遍历实体自身拥有的组件，而不是所有组件类型
+        for component_id in component_ids {
+            self.trigger_on_remove(component_id, entity_id);
+            self.managers[component_id.index()].remove(entity_id);
+            // 移除发生在当前帧的系统之后，+1 保证缓存在下一帧被刷新
+            self.mark_changed(component_id, frame + 1);
         }
         self.entities.remove(entity_id);
     }
@@ -286,22 +580,131 @@ impl EntityManager {
         component: T,
     ) -> &mut Self {
         if !self.has_component_manager::<T>() {
-            // @TODO: Better error handling
-            println!("Unknown component");
+            warn!("Unknown component {}", std::any::type_name::<T>());
             return self;
         }
-        self.borrow_component_manager_mut::<T>()
-            .add(entity_id, component);
-        self.updated_frame_map
-            .insert(TypeId::of::<T>(), self.get_frame());
+        self.flush();
+        if !self.entities.has(entity_id) {
+            warn!("Cannot add {} to unknown entity {}", std::any::type_name::<T>(), entity_id);
+            return self;
+        }
+        self.insert_component_untracked(entity_id, component);
+        let component_id = self.component_id::<T>().unwrap();
+        self.mark_changed(component_id, self.get_frame());
 
         self
     }
 
+    // 插入组件但不更新 updated_frame_map，由调用者负责变更记录
+    pub(crate) fn insert_component_untracked<T: 'static + Component>(
+        &mut self,
+        entity_id: usize,
+        component: T,
+    ) {
+        let manager = self.borrow_component_manager_mut::<T>();
+        let added = !manager.has(entity_id);
+        manager.add(entity_id, component);
+        let component_id = self.component_id::<T>().unwrap();
+        self.entities.insert_component_id(entity_id, component_id);
+        if added {
+            self.trigger_on_add(component_id, entity_id);
+        }
+    }
+
+    pub(crate) fn reserve_components<T: 'static + Component>(&mut self, additional: usize) {
+        self.borrow_component_manager_mut::<T>().reserve(additional);
+    }
+
+    fn component_index<T: 'static + Component>(&self) -> usize {
+        self.component_ids[&TypeId::of::<T>()].index()
+    }
+
+    /// 从实体上移除组件并返回其值
+    pub fn remove_component_from_entity<T: 'static + Component>(
+        &mut self,
+        entity_id: usize,
+    ) -> Option<T> {
+        if !self.has_component_manager::<T>() || !self.borrow_component_manager::<T>().has(entity_id) {
+            return None;
+        }
+        let component_id = self.component_id::<T>().unwrap();
+        self.trigger_on_remove(component_id, entity_id);
+        // 钩子中可能已经移除了该组件
+        let component = self.borrow_component_manager_mut::<T>().take(entity_id)?;
+        self.mark_changed(component_id, self.get_frame() + 1);
+        self.entities.remove_component_id(entity_id, component_id);
+        Some(component)
+    }
+
+    /// 通过 ComponentId 插入组件，组件值会被移动到存储中
+    ///
+    /// # Safety
+    ///
+    /// `value` 必须指向一个有效的、与 `component_id` 的描述一致的组件值，
+    /// 调用之后调用者不能再读取或销毁它。
+    pub unsafe fn insert_component_by_id(
+        &mut self,
+        entity_id: usize,
+        component_id: ComponentId,
+        value: *mut u8,
+    ) {
+        self.flush();
+        if !self.entities.has(entity_id) || component_id.index() >= self.managers.len() {
+            warn!("Unknown entity {} or component {:?}", entity_id, component_id);
+            return;
+        }
+        let added = !self.managers[component_id.index()].has(entity_id);
+        unsafe { self.managers[component_id.index()].insert_ptr(entity_id, value) };
+        self.mark_changed(component_id, self.get_frame());
+        self.entities.insert_component_id(entity_id, component_id);
+        if added {
+            self.trigger_on_add(component_id, entity_id);
+        }
+    }
+
+    /// 通过 ComponentId 获取组件的指针
+    pub fn get_component_by_id(&self, entity_id: usize, component_id: ComponentId) -> Option<*const u8> {
+        self.managers.get(component_id.index())?.get_ptr(entity_id)
+    }
+
+    /// 通过 ComponentId 获取组件的可变指针
+    pub fn get_component_mut_by_id(
+        &mut self,
+        entity_id: usize,
+        component_id: ComponentId,
+    ) -> Option<*mut u8> {
+        if component_id.index() >= self.managers.len() {
+            return None;
+        }
+        self.mark_mutated(component_id.index(), self.get_frame());
+        self.managers[component_id.index()].get_ptr_mut(entity_id)
+    }
+
+    /// 通过 ComponentId 移除并销毁组件，返回组件是否存在
+    pub fn remove_component_by_id(&mut self, entity_id: usize, component_id: ComponentId) -> bool {
+        match self.managers.get(component_id.index()) {
+            Some(manager) if manager.has(entity_id) => {
+                self.trigger_on_remove(component_id, entity_id);
+                self.managers[component_id.index()].remove(entity_id);
+                self.mark_changed(component_id, self.get_frame() + 1);
+                self.entities.remove_component_id(entity_id, component_id);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 拥有该组件的实体 ID
+    pub fn entity_ids_by_id(&self, component_id: ComponentId) -> &[usize] {
+        match self.managers.get(component_id.index()) {
+            Some(manager) => manager.entity_ids(),
+            None => &[],
+        }
+    }
+
     fn borrow_entity_ids<T: 'static + Component>(&self) -> Option<&Vec<usize>> {
         if !self.has_component_manager::<T>() {
-            // @TODO: Better error handling
-            println!("Unknown component");
+            warn!("Unknown component {}", std::any::type_name::<T>());
             return None;
         }
         Some(self.borrow_component_manager::<T>().borrow_entity_ids())
@@ -320,12 +723,12 @@ impl EntityManager {
         &mut self,
         entity_id: usize,
     ) -> Option<&mut T> {
-        match self.has_component_manager::<T>() {
-            true => self
-                .borrow_component_manager_mut::<T>()
-                .borrow_component_mut(entity_id),
-            false => None,
+        if !self.has_component_manager::<T>() {
+            return None;
         }
+        self.mark_mutated(self.component_index::<T>(), self.get_frame());
+        self.borrow_component_manager_mut::<T>()
+            .borrow_component_mut(entity_id)
     }
 
     pub fn borrow_components<T: 'static + Component>(&self) -> Option<&Vec<T>> {
@@ -336,13 +739,11 @@ impl EntityManager {
     }
 
     pub fn borrow_components_mut<T: 'static + Component>(&mut self) -> Option<&mut Vec<T>> {
-        match self.has_component_manager::<T>() {
-            true => Some(
-                self.borrow_component_manager_mut::<T>()
-                    .borrow_components_mut(),
-            ),
-            false => None,
+        if !self.has_component_manager::<T>() {
+            return None;
         }
+        self.mark_mutated(self.component_index::<T>(), self.get_frame());
+        Some(self.borrow_component_manager_mut::<T>().borrow_components_mut())
     }
 
     pub fn borrow_components_pair_mut<T1: 'static + Component, T2: 'static + Component>(
@@ -352,11 +753,15 @@ impl EntityManager {
             return None;
         }
 
-        let type_id1 = TypeId::of::<T1>();
-        let type_id2 = TypeId::of::<T2>();
+        let index1 = self.component_index::<T1>();
+        let index2 = self.component_index::<T2>();
+
+        let frame = self.get_frame();
+        self.mark_mutated(index1, frame);
+        self.mark_mutated(index2, frame);
 
-        let manager1 = cast_manager_mut_unsafe(self.manager_map.get(&type_id1).unwrap());
-        let manager2 = cast_manager_mut_unsafe(self.manager_map.get(&type_id2).unwrap());
+        let manager1 = cast_manager_mut_unsafe(&self.managers[index1]);
+        let manager2 = cast_manager_mut_unsafe(&self.managers[index2]);
 
         Some((
             manager1.borrow_components_mut(),
@@ -378,13 +783,18 @@ impl EntityManager {
             return None;
         }
 
-        let type_id1 = TypeId::of::<T1>();
-        let type_id2 = TypeId::of::<T2>();
-        let type_id3 = TypeId::of::<T3>();
+        let index1 = self.component_index::<T1>();
+        let index2 = self.component_index::<T2>();
+        let index3 = self.component_index::<T3>();
 
-        let manager1 = cast_manager_mut_unsafe(self.manager_map.get(&type_id1).unwrap());
-        let manager2 = cast_manager_mut_unsafe(self.manager_map.get(&type_id2).unwrap());
-        let manager3 = cast_manager_mut_unsafe(self.manager_map.get(&type_id3).unwrap());
+        let frame = self.get_frame();
+        self.mark_mutated(index1, frame);
+        self.mark_mutated(index2, frame);
+        self.mark_mutated(index3, frame);
+
+        let manager1 = cast_manager_mut_unsafe(&self.managers[index1]);
+        let manager2 = cast_manager_mut_unsafe(&self.managers[index2]);
+        let manager3 = cast_manager_mut_unsafe(&self.managers[index3]);
 
         Some((
             manager1.borrow_components_mut(),
@@ -409,15 +819,21 @@ impl EntityManager {
             return None;
         }
 
-        let type_id1 = TypeId::of::<T1>();
-        let type_id2 = TypeId::of::<T2>();
-        let type_id3 = TypeId::of::<T3>();
-        let type_id4 = TypeId::of::<T4>();
+        let index1 = self.component_index::<T1>();
+        let index2 = self.component_index::<T2>();
+        let index3 = self.component_index::<T3>();
+        let index4 = self.component_index::<T4>();
+
+        let frame = self.get_frame();
+        self.mark_mutated(index1, frame);
+        self.mark_mutated(index2, frame);
+        self.mark_mutated(index3, frame);
+        self.mark_mutated(index4, frame);
 
-        let manager1 = cast_manager_mut_unsafe(self.manager_map.get(&type_id1).unwrap());
-        let manager2 = cast_manager_mut_unsafe(self.manager_map.get(&type_id2).unwrap());
-        let manager3 = cast_manager_mut_unsafe(self.manager_map.get(&type_id3).unwrap());
-        let manager4 = cast_manager_mut_unsafe(self.manager_map.get(&type_id4).unwrap());
+        let manager1 = cast_manager_mut_unsafe(&self.managers[index1]);
+        let manager2 = cast_manager_mut_unsafe(&self.managers[index2]);
+        let manager3 = cast_manager_mut_unsafe(&self.managers[index3]);
+        let manager4 = cast_manager_mut_unsafe(&self.managers[index4]);
 
         Some((
             manager1.borrow_components_mut(),
@@ -435,11 +851,15 @@ impl EntityManager {
             return None;
         }
 
-        let type_id1 = TypeId::of::<T1>();
-        let type_id2 = TypeId::of::<T2>();
+        let index1 = self.component_index::<T1>();
+        let index2 = self.component_index::<T2>();
+
+        let frame = self.get_frame();
+        self.mark_mutated(index1, frame);
+        self.mark_mutated(index2, frame);
 
-        let manager1 = cast_manager_mut_unsafe(self.manager_map.get(&type_id1).unwrap());
-        let manager2 = cast_manager_mut_unsafe(self.manager_map.get(&type_id2).unwrap());
+        let manager1 = cast_manager_mut_unsafe(&self.managers[index1]);
+        let manager2 = cast_manager_mut_unsafe(&self.managers[index2]);
 
         if !manager1.has(entity_id) || !manager2.has(entity_id) {
             return None;
@@ -466,13 +886,18 @@ impl EntityManager {
             return None;
         }
 
-        let type_id1 = TypeId::of::<T1>();
-        let type_id2 = TypeId::of::<T2>();
-        let type_id3 = TypeId::of::<T3>();
+        let index1 = self.component_index::<T1>();
+        let index2 = self.component_index::<T2>();
+        let index3 = self.component_index::<T3>();
 
-        let manager1 = cast_manager_mut_unsafe(self.manager_map.get(&type_id1).unwrap());
-        let manager2 = cast_manager_mut_unsafe(self.manager_map.get(&type_id2).unwrap());
-        let manager3 = cast_manager_mut_unsafe(self.manager_map.get(&type_id3).unwrap());
+        let frame = self.get_frame();
+        self.mark_mutated(index1, frame);
+        self.mark_mutated(index2, frame);
+        self.mark_mutated(index3, frame);
+
+        let manager1 = cast_manager_mut_unsafe(&self.managers[index1]);
+        let manager2 = cast_manager_mut_unsafe(&self.managers[index2]);
+        let manager3 = cast_manager_mut_unsafe(&self.managers[index3]);
 
         if !manager1.has(entity_id) || !manager2.has(entity_id) || !manager3.has(entity_id) {
             return None;
@@ -502,15 +927,21 @@ impl EntityManager {
             return None;
         }
 
-        let type_id1 = TypeId::of::<T1>();
-        let type_id2 = TypeId::of::<T2>();
-        let type_id3 = TypeId::of::<T3>();
-        let type_id4 = TypeId::of::<T4>();
+        let index1 = self.component_index::<T1>();
+        let index2 = self.component_index::<T2>();
+        let index3 = self.component_index::<T3>();
+        let index4 = self.component_index::<T4>();
 
-        let manager1 = cast_manager_mut_unsafe(self.manager_map.get(&type_id1).unwrap());
-        let manager2 = cast_manager_mut_unsafe(self.manager_map.get(&type_id2).unwrap());
-        let manager3 = cast_manager_mut_unsafe(self.manager_map.get(&type_id3).unwrap());
-        let manager4 = cast_manager_mut_unsafe(self.manager_map.get(&type_id4).unwrap());
+        let frame = self.get_frame();
+        self.mark_mutated(index1, frame);
+        self.mark_mutated(index2, frame);
+        self.mark_mutated(index3, frame);
+        self.mark_mutated(index4, frame);
+
+        let manager1 = cast_manager_mut_unsafe(&self.managers[index1]);
+        let manager2 = cast_manager_mut_unsafe(&self.managers[index2]);
+        let manager3 = cast_manager_mut_unsafe(&self.managers[index3]);
+        let manager4 = cast_manager_mut_unsafe(&self.managers[index4]);
 
         if !manager1.has(entity_id)
             || !manager2.has(entity_id)
@@ -530,17 +961,16 @@ impl EntityManager {
 
     fn has_component_manager<T: 'static + Component>(&self) -> bool {
         let type_id = TypeId::of::<T>();
-        self.manager_map.contains_key(&type_id)
+        self.component_ids.contains_key(&type_id)
     }
 
     fn borrow_component_manager<T: 'static + Component>(&self) -> &ComponentManager<T> {
-        let type_id = TypeId::of::<T>();
-        cast_manager(self.manager_map.get(&type_id).unwrap().as_ref())
+        cast_manager(self.managers[self.component_index::<T>()].as_ref())
     }
 
     fn borrow_component_manager_mut<T: 'static + Component>(&mut self) -> &mut ComponentManager<T> {
-        let type_id = TypeId::of::<T>();
-        cast_manager_mut(self.manager_map.get_mut(&type_id).unwrap().as_mut())
+        let index = self.component_index::<T>();
+        cast_manager_mut(self.managers[index].as_mut())
     }
 
     pub fn add_resource<T: 'static>(&mut self, resource: T) {
@@ -558,6 +988,13 @@ impl EntityManager {
     pub fn remove_resource<T: 'static>(&mut self) -> Option<T> {
         self.resource_manager.remove()
     }
+
+    /// 所有资源的类型名，按名称排序
+    pub fn resource_names(&self) -> Vec<&'static str> {
+        let mut names: Vec<_> = self.resource_manager.names().collect();
+        names.sort_unstable();
+        names
+    }
 }
 
 // @TODO: Write comment
@@ -568,3 +1005,93 @@ fn cast_manager_mut_unsafe<T: 'static + Component>(
         cast_manager(manager.as_ref()) as *const ComponentManager<T> as *mut ComponentManager<T>;
     unsafe { transmute(ptr) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve_entity_from_other_thread() {
+        let mut manager = EntityManager::new();
+        let first = manager.create_entity();
+        manager.remove_entity(first);
+
+        let reserver = manager.entity_reserver();
+        let reserved = std::thread::spawn(move || {
+            (reserver.reserve_entity(), reserver.reserve_entity())
+        })
+        .join()
+        .unwrap();
+
+        // 先复用空闲的 ID，再分配新的 ID
+        assert_eq!(reserved, (first, first + 1));
+        assert!(!manager.has_entity(reserved.1));
+
+        manager.flush();
+        assert!(manager.has_entity(reserved.0));
+        assert!(manager.has_entity(reserved.1));
+        assert_eq!(manager.create_entity(), first + 2);
+    }
+
+    #[test]
+    fn test_reserve_entity_while_removing() {
+        let mut manager = EntityManager::new();
+        let entities: Vec<usize> = (0..100_000).map(|_| manager.create_entity()).collect();
+
+        let reserver = manager.entity_reserver();
+        let barrier = Arc::new(std::sync::Barrier::new(2));
+        let handle = std::thread::spawn({
+            let barrier = barrier.clone();
+            move || {
+                barrier.wait();
+                (0..100_000).map(|_| reserver.reserve_entity()).collect::<Vec<_>>()
+            }
+        });
+        barrier.wait();
+        for entity in entities {
+            manager.remove_entity(entity);
+        }
+        let reserved = handle.join().unwrap();
+
+        manager.flush();
+        for entity in reserved {
+            assert!(manager.has_entity(entity));
+        }
+    }
+
+    struct Position;
+    impl Component for Position {}
+
+    #[test]
+    fn test_cached_ids_refresh_in_same_frame() {
+        let mut manager = EntityManager::new();
+        manager.register::<Position>();
+        let mut accessor = EntityIdAccessor::new();
+        let first = manager.spawn(Position);
+        assert_eq!(accessor.borrow_ids::<Position>(&manager).unwrap(), &vec![first]);
+
+        // 填充缓存之后在同一帧内添加组件，缓存的帧号与组件的修改帧号相等
+        let second = manager.spawn(Position);
+        assert_eq!(accessor.borrow_ids::<Position>(&manager).unwrap(), &vec![first, second]);
+
+        manager.increment_frame();
+        manager.remove_entity(first);
+        assert_eq!(accessor.borrow_ids::<Position>(&manager).unwrap(), &vec![second]);
+    }
+
+    #[test]
+    fn test_component_changed_frame() {
+        let mut manager = EntityManager::new();
+        manager.register::<Position>();
+        let component_id = manager.component_id::<Position>().unwrap();
+        let entity = manager.spawn(Position);
+        assert_eq!(manager.component_changed_frame(component_id), Some(0));
+
+        manager.increment_frame();
+        manager.borrow_component::<Position>(entity);
+        assert_eq!(manager.component_changed_frame(component_id), Some(0));
+
+        manager.borrow_component_mut::<Position>(entity);
+        assert_eq!(manager.component_changed_frame(component_id), Some(1));
+    }
+}
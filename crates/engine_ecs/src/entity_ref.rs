@@ -0,0 +1,225 @@
+use std::fmt;
+
+use super::component::{Component, ComponentId, ComponentInfo};
+use super::entity_manager::EntityManager;
+
+/// 实体组件组合的快照，包含每个组件的 ID 和类型名称
+#[derive(Clone, Debug, Default)]
+pub struct Archetype {
+    components: Vec<ComponentInfo>,
+}
+
+impl Archetype {
+    fn new(manager: &EntityManager, entity_id: usize) -> Self {
+        Archetype {
+            components: manager
+                .entity_component_ids(entity_id)
+                .iter()
+                .map(|id| manager.component_info(*id).unwrap().clone())
+                .collect(),
+        }
+    }
+
+    pub fn contains(&self, component_id: ComponentId) -> bool {
+        self.components.iter().any(|info| info.id() == component_id)
+    }
+
+    pub fn component_ids(&self) -> impl Iterator<Item = ComponentId> + '_ {
+        self.components.iter().map(|info| info.id())
+    }
+
+    pub fn component_names(&self) -> impl Iterator<Item = &str> {
+        self.components.iter().map(|info| info.name())
+    }
+
+    pub fn components(&self) -> &[ComponentInfo] {
+        &self.components
+    }
+
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+impl fmt::Display for Archetype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.component_names()).finish()
+    }
+}
+
+/// 对单个实体的只读访问
+#[derive(Copy, Clone)]
+pub struct EntityRef<'w> {
+    manager: &'w EntityManager,
+    entity_id: usize,
+}
+
+impl<'w> EntityRef<'w> {
+    pub(crate) fn new(manager: &'w EntityManager, entity_id: usize) -> Self {
+        EntityRef { manager, entity_id }
+    }
+
+    pub fn id(&self) -> usize {
+        self.entity_id
+    }
+
+    /// 检查实体是否拥有组件 `T`
+    pub fn contains<T: 'static + Component>(&self) -> bool {
+        self.manager
+            .component_id::<T>()
+            .is_some_and(|id| self.contains_id(id))
+    }
+
+    pub fn contains_id(&self, component_id: ComponentId) -> bool {
+        self.component_ids().binary_search(&component_id).is_ok()
+    }
+
+    pub fn get<T: 'static + Component>(&self) -> Option<&'w T> {
+        self.manager.borrow_component::<T>(self.entity_id)
+    }
+
+    /// 实体拥有的组件 ID（按 ID 排序）
+    pub fn component_ids(&self) -> &'w [ComponentId] {
+        self.manager.entity_component_ids(self.entity_id)
+    }
+
+    pub fn archetype(&self) -> Archetype {
+        Archetype::new(self.manager, self.entity_id)
+    }
+}
+
+impl fmt::Debug for EntityRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EntityRef")
+            .field("id", &self.entity_id)
+            .field("components", &self.archetype().component_names().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// 对单个实体的可变访问，可以增删组件或销毁实体
+pub struct EntityMut<'w> {
+    manager: &'w mut EntityManager,
+    entity_id: usize,
+}
+
+impl<'w> EntityMut<'w> {
+    pub(crate) fn new(manager: &'w mut EntityManager, entity_id: usize) -> Self {
+        EntityMut { manager, entity_id }
+    }
+
+    pub fn id(&self) -> usize {
+        self.entity_id
+    }
+
+    /// 转换为只读访问
+    pub fn as_readonly(&self) -> EntityRef<'_> {
+        EntityRef::new(self.manager, self.entity_id)
+    }
+
+    pub fn contains<T: 'static + Component>(&self) -> bool {
+        self.as_readonly().contains::<T>()
+    }
+
+    pub fn contains_id(&self, component_id: ComponentId) -> bool {
+        self.as_readonly().contains_id(component_id)
+    }
+
+    pub fn get<T: 'static + Component>(&self) -> Option<&T> {
+        self.manager.borrow_component::<T>(self.entity_id)
+    }
+
+    pub fn get_mut<T: 'static + Component>(&mut self) -> Option<&mut T> {
+        self.manager.borrow_component_mut::<T>(self.entity_id)
+    }
+
+    /// 添加组件，组件类型未注册时会自动注册；已存在的组件会被替换
+    pub fn insert<T: 'static + Component>(&mut self, component: T) -> &mut Self {
+        self.manager.register::<T>();
+        self.manager.remove_component_from_entity::<T>(self.entity_id);
+        self.manager.add_component_to_entity(self.entity_id, component);
+        self
+    }
+
+    pub fn remove<T: 'static + Component>(&mut self) -> Option<T> {
+        self.manager.remove_component_from_entity::<T>(self.entity_id)
+    }
+
+    /// 销毁实体及其所有组件
+    pub fn despawn(self) {
+        self.manager.remove_entity(self.entity_id);
+    }
+
+    pub fn component_ids(&self) -> &[ComponentId] {
+        self.manager.entity_component_ids(self.entity_id)
+    }
+
+    pub fn archetype(&self) -> Archetype {
+        self.as_readonly().archetype()
+    }
+}
+
+impl fmt::Debug for EntityMut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EntityMut")
+            .field("id", &self.entity_id)
+            .field("components", &self.archetype().component_names().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32, i32);
+    impl Component for Position {}
+
+    #[derive(Debug, PartialEq)]
+    struct Health(u32);
+    impl Component for Health {}
+
+    #[test]
+    fn test_entity_inspection() {
+        let mut world = World::new();
+        world.register_component::<Position>();
+        world.register_component::<Health>();
+        let id = world.create_entity();
+        world.add_component_to_entity(id, Health(10));
+        world.add_component_to_entity(id, Position(1, 2));
+
+        let entity = world.entity(id);
+        assert!(entity.contains::<Position>());
+        assert!(entity.contains::<Health>());
+        assert_eq!(entity.get::<Health>(), Some(&Health(10)));
+
+        let archetype = entity.archetype();
+        let names: Vec<_> = archetype.component_names().collect();
+        assert_eq!(names.len(), 2);
+        assert!(names[0].ends_with("Position"));
+        assert!(names[1].ends_with("Health"));
+    }
+
+    #[test]
+    fn test_entity_mut_insert_remove_despawn() {
+        let mut world = World::new();
+        let id = world.create_entity();
+
+        let mut entity = world.entity_mut(id);
+        entity.insert(Position(0, 0)).insert(Health(3));
+        entity.get_mut::<Health>().unwrap().0 -= 1;
+        assert_eq!(entity.remove::<Position>(), Some(Position(0, 0)));
+        assert!(!entity.contains::<Position>());
+        assert_eq!(entity.component_ids().len(), 1);
+        assert_eq!(entity.get::<Health>(), Some(&Health(2)));
+
+        entity.despawn();
+        assert!(world.get_entity(id).is_none());
+        assert!(world.query::<Health>().is_empty());
+    }
+}
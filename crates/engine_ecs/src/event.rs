@@ -3,6 +3,10 @@ use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
 use std::ops::{Deref, DerefMut};
 
+use tracing::warn;
+
+use super::entity_manager::EntityManager;
+
 pub use engine_ecs_macros::{BufferedEvent};
 pub trait BufferedEvent: Send + Sync + 'static {}
 
@@ -82,8 +86,13 @@ pub(crate) struct EventSequence<E: BufferedEvent> {
 }
 
 
+/// 事件缓冲区。事件在写入后的下一次 [`Events::update`] 之后仍然可读，
+/// 再下一次 `update` 时才被丢弃，所以无论读取的系统在写入之前还是之后运行都能读到。
 pub struct Events<E: BufferedEvent> {
     pub(crate) events: EventSequence<E>,
+    // 上一次 update 之前写入的事件
+    pub(crate) previous: EventSequence<E>,
+    pub(crate) event_count: usize,
 }
 
 
@@ -91,6 +100,8 @@ impl<E: BufferedEvent> Default for Events<E> {
     fn default() -> Self {
         Self {
             events: Default::default(),
+            previous: Default::default(),
+            event_count: 0,
         }
     }
 }
@@ -98,7 +109,8 @@ impl<E: BufferedEvent> Default for Events<E> {
 impl<E: BufferedEvent> Events<E> {
 
     pub fn write(&mut self, event: E) -> EventId<E> {
-        let id = self.events.len();
+        let id = self.event_count;
+        self.event_count += 1;
         let event_id = EventId {
             id,
             _marker: PhantomData,
@@ -107,19 +119,86 @@ impl<E: BufferedEvent> Events<E> {
         event_id
     }
 
+    /// 交换缓冲区，丢弃上一次 update 之前写入的事件，通常每帧调用一次
+    pub fn update(&mut self) {
+        self.previous = std::mem::take(&mut self.events);
+    }
+
     pub fn clear(&mut self) {
         self.events.clear();
+        self.previous.clear();
     }
 
     pub fn len(&self) -> usize {
-        self.events.len()
+        self.events.len() + self.previous.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.events.is_empty()
+        self.events.is_empty() && self.previous.is_empty()
+    }
+
+    /// 按写入顺序遍历所有还未丢弃的事件
+    pub fn iter(&self) -> impl Iterator<Item = &E> {
+        self.previous.iter().chain(self.events.iter()).map(|i| &i.event)
+    }
+
+    /// 和 [`Events::iter`] 相同，同时返回事件 ID，可以用来跳过已经读过的事件
+    pub fn iter_with_id(&self) -> impl Iterator<Item = (EventId<E>, &E)> {
+        self.previous
+            .iter()
+            .chain(self.events.iter())
+            .map(|i| (i.event_id, &i.event))
     }
 
     pub fn drain(&mut self) -> impl Iterator<Item = E> {
-        self.events.drain(..).map(|i| i.event)
+        self.previous
+            .drain(..)
+            .chain(self.events.drain(..))
+            .map(|i| i.event)
+    }
+}
+
+impl EntityManager {
+    /// 写入事件，事件类型必须先注册（添加 `Events<E>` 资源）
+    pub fn write_event<E: BufferedEvent>(&mut self, event: E) -> Option<EventId<E>> {
+        match self.get_resource_mut::<Events<E>>() {
+            Some(events) => Some(events.write(event)),
+            None => {
+                warn!("Event {} is not registered", std::any::type_name::<E>());
+                None
+            }
+        }
+    }
+}
+
+/// 每帧交换事件缓冲区的系统
+pub fn event_update_system<E: BufferedEvent>(manager: &mut EntityManager) {
+    #[cfg(feature = "trace")]
+    let _span = tracing::info_span!("event_update", name = %std::any::type_name::<E>()).entered();
+    if let Some(events) = manager.get_resource_mut::<Events<E>>() {
+        events.update();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Hit(u32);
+    impl BufferedEvent for Hit {}
+
+    #[test]
+    fn test_events_double_buffer() {
+        let mut events = Events::<Hit>::default();
+        events.write(Hit(1));
+        events.update();
+        events.write(Hit(2));
+        assert_eq!(events.iter().collect::<Vec<_>>(), vec![&Hit(1), &Hit(2)]);
+
+        events.update();
+        assert_eq!(events.iter().collect::<Vec<_>>(), vec![&Hit(2)]);
+        events.update();
+        assert!(events.is_empty());
     }
 }
@@ -1,10 +1,16 @@
 
 
+pub mod bundle;
 pub mod component;
 pub mod component_manager;
 pub mod entity;
+pub mod entity_cloner;
+pub mod entity_ref;
 pub mod entity_manager;
+pub mod query;
+pub mod relationship;
 pub mod resource;
+pub mod schedule;
 pub mod system;
 pub mod world;
 pub mod event;
@@ -12,11 +18,17 @@ pub mod event;
 pub mod prelude {
 
     pub use crate::{
+        bundle::*,
         component::*,
         component_manager::*,
         entity::*,
+        entity_cloner::*,
+        entity_ref::*,
         entity_manager::*,
+        query::*,
+        relationship::*,
         resource::*,
+        schedule::*,
         system::*,
         world::*,
         event::*,
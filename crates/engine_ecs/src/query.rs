@@ -0,0 +1,300 @@
+use super::component::{Component, ComponentId};
+use super::entity_manager::EntityManager;
+
+// 查询中的一项组件访问
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Access {
+    Required(ComponentId),
+    Optional(ComponentId),
+}
+
+impl Access {
+    fn id(self) -> ComponentId {
+        match self {
+            Access::Required(id) | Access::Optional(id) => id,
+        }
+    }
+}
+
+/// 在运行时根据 ComponentId 组装查询。
+///
+/// ```ignore
+/// let query = QueryBuilder::new(manager)
+///     .fetch_id(health_id)
+///     .with::<Enemy>()
+///     .without::<Dead>()
+///     .build();
+/// for item in query.iter(manager) {
+///     let health = item.get(0).unwrap();
+/// }
+/// ```
+pub struct QueryBuilder<'m> {
+    manager: &'m EntityManager,
+    fetch: Vec<Access>,
+    with: Vec<ComponentId>,
+    without: Vec<ComponentId>,
+    // 使用了未注册的必需组件，查询结果必然为空
+    unmatched: bool,
+}
+
+impl<'m> QueryBuilder<'m> {
+    pub fn new(manager: &'m EntityManager) -> Self {
+        QueryBuilder {
+            manager,
+            fetch: Vec::new(),
+            with: Vec::new(),
+            without: Vec::new(),
+            unmatched: false,
+        }
+    }
+
+    /// 获取组件的指针，实体必须拥有该组件
+    pub fn fetch_id(mut self, component_id: ComponentId) -> Self {
+        self.fetch.push(Access::Required(component_id));
+        self
+    }
+
+    /// 获取组件的指针，实体没有该组件时为 `None`
+    pub fn optional_id(mut self, component_id: ComponentId) -> Self {
+        self.fetch.push(Access::Optional(component_id));
+        self
+    }
+
+    /// 过滤：实体必须拥有该组件
+    pub fn with_id(mut self, component_id: ComponentId) -> Self {
+        self.with.push(component_id);
+        self
+    }
+
+    /// 过滤：实体不能拥有该组件
+    pub fn without_id(mut self, component_id: ComponentId) -> Self {
+        self.without.push(component_id);
+        self
+    }
+
+    pub fn fetch<T: 'static + Component>(self) -> Self {
+        match self.manager.component_id::<T>() {
+            Some(id) => self.fetch_id(id),
+            None => self.unmatched(),
+        }
+    }
+
+    pub fn with<T: 'static + Component>(self) -> Self {
+        match self.manager.component_id::<T>() {
+            Some(id) => self.with_id(id),
+            None => self.unmatched(),
+        }
+    }
+
+    pub fn without<T: 'static + Component>(self) -> Self {
+        match self.manager.component_id::<T>() {
+            Some(id) => self.without_id(id),
+            None => self,
+        }
+    }
+
+    fn unmatched(mut self) -> Self {
+        self.unmatched = true;
+        self
+    }
+
+    pub fn build(self) -> DynamicQuery {
+        DynamicQuery {
+            fetch: self.fetch,
+            with: self.with,
+            without: self.without,
+            unmatched: self.unmatched,
+        }
+    }
+}
+
+/// 由 [`QueryBuilder`] 构建的查询，可以保存下来重复使用
+#[derive(Clone, Debug)]
+pub struct DynamicQuery {
+    fetch: Vec<Access>,
+    with: Vec<ComponentId>,
+    without: Vec<ComponentId>,
+    unmatched: bool,
+}
+
+/// 动态查询的一条结果，组件指针与 `fetch_id`/`optional_id` 的调用顺序一致
+#[derive(Debug)]
+pub struct DynamicQueryItem {
+    entity_id: usize,
+    components: Vec<Option<*const u8>>,
+}
+
+impl DynamicQueryItem {
+    pub fn entity_id(&self) -> usize {
+        self.entity_id
+    }
+
+    pub fn get(&self, index: usize) -> Option<*const u8> {
+        self.components.get(index).copied().flatten()
+    }
+
+    pub fn components(&self) -> &[Option<*const u8>] {
+        &self.components
+    }
+}
+
+impl DynamicQuery {
+    /// 匹配查询的所有实体
+    pub fn entities(&self, manager: &EntityManager) -> Vec<usize> {
+        if self.unmatched {
+            return Vec::new();
+        }
+
+        let required: Vec<ComponentId> = self
+            .fetch
+            .iter()
+            .filter_map(|access| match access {
+                Access::Required(id) => Some(*id),
+                Access::Optional(_) => None,
+            })
+            .chain(self.with.iter().copied())
+            .collect();
+
+        // 从拥有组件最少的存储开始遍历；没有必需组件时遍历所有实体
+        let candidates: Vec<usize> = match required
+            .iter()
+            .min_by_key(|id| manager.entity_ids_by_id(**id).len())
+        {
+            Some(id) => manager.entity_ids_by_id(*id).to_vec(),
+            None => manager.entity_ids(),
+        };
+
+        candidates
+            .into_iter()
+            .filter(|entity_id| {
+                let ids = manager.entity_component_ids(*entity_id);
+                required.iter().all(|id| ids.binary_search(id).is_ok())
+                    && !self.without.iter().any(|id| ids.binary_search(id).is_ok())
+            })
+            .collect()
+    }
+
+    pub fn iter<'m>(&self, manager: &'m EntityManager) -> impl Iterator<Item = DynamicQueryItem> + 'm {
+        let fetch = self.fetch.clone();
+        self.entities(manager).into_iter().map(move |entity_id| DynamicQueryItem {
+            entity_id,
+            components: fetch
+                .iter()
+                .map(|access| manager.get_component_by_id(entity_id, access.id()))
+                .collect(),
+        })
+    }
+
+    /// 以可变指针遍历查询结果
+    pub fn for_each_mut(
+        &self,
+        manager: &mut EntityManager,
+        mut f: impl FnMut(usize, &[Option<*mut u8>]),
+    ) {
+        let mut components = Vec::with_capacity(self.fetch.len());
+        for entity_id in self.entities(manager) {
+            components.clear();
+            for access in &self.fetch {
+                components.push(manager.get_component_mut_by_id(entity_id, access.id()));
+            }
+            f(entity_id, &components);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::alloc::Layout;
+
+    use crate::prelude::*;
+
+    struct Enemy;
+    impl Component for Enemy {}
+
+    #[test]
+    fn test_dynamic_component_query() {
+        let mut world = World::new();
+        let manager = world.entity_manager_mut();
+        manager.register::<Enemy>();
+
+        unsafe fn serialize_health(ptr: *const u8) -> String {
+            unsafe { format!("Health({})", *(ptr as *const u32)) }
+        }
+
+        // 模拟脚本中定义的 `Health { value: u32 }` 组件
+        let descriptor = unsafe {
+            ComponentDescriptor::new_with_layout("script::Health", Layout::new::<u32>(), None)
+                .with_serialize_fn(serialize_health)
+        };
+        let health = manager.register_component_with_descriptor(descriptor);
+
+        for i in 0..4u32 {
+            let entity = manager.create_entity();
+            let mut value = i * 10;
+            unsafe { manager.insert_component_by_id(entity, health, &mut value as *mut u32 as *mut u8) };
+            if i % 2 == 0 {
+                manager.add_component_to_entity(entity, Enemy);
+            }
+        }
+
+        let query = QueryBuilder::new(manager).fetch_id(health).with::<Enemy>().build();
+        query.for_each_mut(manager, |_, components| unsafe {
+            *(components[0].unwrap() as *mut u32) += 1;
+        });
+
+        let values: Vec<u32> = query
+            .iter(manager)
+            .map(|item| unsafe { *(item.get(0).unwrap() as *const u32) })
+            .collect();
+        assert_eq!(values, vec![1, 21]);
+
+        let entity = manager.entity(3);
+        assert_eq!(entity.archetype().component_names().collect::<Vec<_>>(), vec!["script::Health"]);
+
+        let serialize = manager.component_info(health).unwrap().descriptor().serialize_fn().unwrap();
+        let ptr = manager.get_component_by_id(3, health).unwrap();
+        assert_eq!(unsafe { serialize(ptr) }, "Health(30)");
+    }
+
+    #[test]
+    fn test_dynamic_query_marks_changed() {
+        let mut world = World::new();
+        let manager = world.entity_manager_mut();
+        let health = manager.register_component_with_descriptor(ComponentDescriptor::new::<u32>());
+        let entity = manager.create_entity();
+        let mut value = 10u32;
+        unsafe { manager.insert_component_by_id(entity, health, &mut value as *mut u32 as *mut u8) };
+        assert_eq!(manager.component_changed_frame(health), Some(0));
+
+        manager.increment_frame();
+        let query = QueryBuilder::new(manager).fetch_id(health).build();
+        query.for_each_mut(manager, |_, components| unsafe {
+            *(components[0].unwrap() as *mut u32) += 1;
+        });
+        assert_eq!(manager.component_changed_frame(health), Some(1));
+    }
+
+    #[test]
+    fn test_dynamic_component_drop() {
+        use std::rc::Rc;
+
+        let mut world = World::new();
+        let counter = Rc::new(());
+        let manager = world.entity_manager_mut();
+        let id = manager.register_component_with_descriptor(ComponentDescriptor::new::<Rc<()>>());
+
+        let entities: Vec<usize> = (0..3).map(|_| manager.create_entity()).collect();
+        for entity in &entities {
+            let mut value = std::mem::ManuallyDrop::new(counter.clone());
+            unsafe { manager.insert_component_by_id(*entity, id, &mut *value as *mut Rc<()> as *mut u8) };
+        }
+        assert_eq!(Rc::strong_count(&counter), 4);
+
+        assert!(manager.remove_component_by_id(entities[0], id));
+        manager.remove_entity(entities[1]);
+        assert_eq!(Rc::strong_count(&counter), 2);
+
+        drop(world);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+}
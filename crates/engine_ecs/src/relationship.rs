@@ -0,0 +1,289 @@
+use std::collections::{HashSet, VecDeque};
+use std::marker::PhantomData;
+
+use tracing::warn;
+
+use super::component::{Component, ComponentHooks};
+use super::entity_manager::EntityManager;
+
+/// 目标实体被销毁时，如何处理指向它的源实体
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DespawnPolicy {
+    /// 只移除关系，源实体保留
+    RemoveRelation,
+    /// 同时销毁源实体
+    DespawnRelated,
+}
+
+/// 关系类型的标记，例如“子弹瞄准敌人”、“炮塔守卫区域”、“物品被玩家持有”。
+///
+/// ```ignore
+/// struct HeldBy;
+/// impl RelationKind for HeldBy {
+///     const ON_TARGET_DESPAWN: DespawnPolicy = DespawnPolicy::DespawnRelated;
+/// }
+///
+/// manager.relate::<HeldBy>(sword, player);
+/// ```
+pub trait RelationKind: 'static {
+    const ON_TARGET_DESPAWN: DespawnPolicy = DespawnPolicy::RemoveRelation;
+}
+
+/// 源实体上的关系组件，记录它指向的所有目标实体
+pub struct Relation<R: RelationKind> {
+    targets: Vec<usize>,
+    marker: PhantomData<R>,
+}
+
+impl<R: RelationKind> Relation<R> {
+    pub fn new(targets: impl IntoIterator<Item = usize>) -> Self {
+        Relation {
+            targets: targets.into_iter().collect(),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn targets(&self) -> &[usize] {
+        &self.targets
+    }
+}
+
+impl<R: RelationKind> Component for Relation<R> {
+    fn register_hooks(hooks: &mut ComponentHooks) {
+        hooks.on_add(on_relation_add::<R>).on_remove(on_relation_remove::<R>);
+    }
+}
+
+/// 目标实体上自动维护的组件，记录所有指向它的源实体
+pub struct RelatedBy<R: RelationKind> {
+    sources: Vec<usize>,
+    marker: PhantomData<R>,
+}
+
+impl<R: RelationKind> RelatedBy<R> {
+    pub fn sources(&self) -> &[usize] {
+        &self.sources
+    }
+}
+
+impl<R: RelationKind> Component for RelatedBy<R> {
+    fn register_hooks(hooks: &mut ComponentHooks) {
+        hooks.on_remove(on_related_by_remove::<R>);
+    }
+}
+
+// 源实体添加 Relation<R> 后，把它登记到每个目标实体的 RelatedBy<R> 中
+fn on_relation_add<R: RelationKind>(manager: &mut EntityManager, source: usize) {
+    let targets = manager.borrow_component::<Relation<R>>(source).unwrap().targets.clone();
+    let mut alive = Vec::with_capacity(targets.len());
+    for target in targets {
+        if manager.has_entity(target) && !alive.contains(&target) {
+            link::<R>(manager, source, target);
+            alive.push(target);
+        }
+    }
+    // 丢弃指向不存在实体的关系
+    manager.borrow_component_mut::<Relation<R>>(source).unwrap().targets = alive;
+}
+
+// 源实体移除 Relation<R> 前，从每个目标实体的 RelatedBy<R> 中注销
+fn on_relation_remove<R: RelationKind>(manager: &mut EntityManager, source: usize) {
+    let targets = manager.borrow_component::<Relation<R>>(source).unwrap().targets.clone();
+    for target in targets {
+        unlink::<R>(manager, source, target);
+    }
+}
+
+// 目标实体移除 RelatedBy<R> 前（通常是目标被销毁），按策略处理源实体
+fn on_related_by_remove<R: RelationKind>(manager: &mut EntityManager, target: usize) {
+    let sources = manager.borrow_component::<RelatedBy<R>>(target).unwrap().sources.clone();
+    for source in sources {
+        match R::ON_TARGET_DESPAWN {
+            DespawnPolicy::RemoveRelation => manager.unrelate::<R>(source, target),
+            DespawnPolicy::DespawnRelated => manager.remove_entity(source),
+        }
+    }
+}
+
+fn link<R: RelationKind>(manager: &mut EntityManager, source: usize, target: usize) {
+    match manager.borrow_component_mut::<RelatedBy<R>>(target) {
+        Some(related) => {
+            if !related.sources.contains(&source) {
+                related.sources.push(source);
+            }
+        }
+        None => {
+            manager.register::<RelatedBy<R>>();
+            let component_id = manager.component_id::<RelatedBy<R>>().unwrap();
+            manager.component_info_mut(component_id).derived = true;
+            manager.add_component_to_entity(
+                target,
+                RelatedBy::<R> {
+                    sources: vec![source],
+                    marker: PhantomData,
+                },
+            );
+        }
+    }
+}
+
+fn unlink<R: RelationKind>(manager: &mut EntityManager, source: usize, target: usize) {
+    let Some(related) = manager.borrow_component_mut::<RelatedBy<R>>(target) else {
+        return;
+    };
+    related.sources.retain(|id| *id != source);
+    if related.sources.is_empty() {
+        manager.remove_component_from_entity::<RelatedBy<R>>(target);
+    }
+}
+
+impl EntityManager {
+    /// 建立 `source -> target` 关系，目标实体上的 [`RelatedBy`] 会自动更新
+    pub fn relate<R: RelationKind>(&mut self, source: usize, target: usize) {
+        if !self.has_entity(source) || !self.has_entity(target) {
+            warn!("Cannot relate unknown entity {} -> {}", source, target);
+            return;
+        }
+        match self.borrow_component_mut::<Relation<R>>(source) {
+            Some(relation) => {
+                if !relation.targets.contains(&target) {
+                    relation.targets.push(target);
+                    link::<R>(self, source, target);
+                }
+            }
+            None => {
+                self.register::<Relation<R>>();
+                self.add_component_to_entity(source, Relation::<R>::new([target]));
+            }
+        }
+    }
+
+    /// 移除 `source -> target` 关系；源实体不再指向任何目标时会移除 [`Relation`] 组件
+    pub fn unrelate<R: RelationKind>(&mut self, source: usize, target: usize) {
+        let Some(relation) = self.borrow_component_mut::<Relation<R>>(source) else {
+            return;
+        };
+        let len = relation.targets.len();
+        relation.targets.retain(|id| *id != target);
+        if relation.targets.len() == len {
+            return;
+        }
+        let is_empty = relation.targets.is_empty();
+        unlink::<R>(self, source, target);
+        if is_empty {
+            self.remove_component_from_entity::<Relation<R>>(source);
+        }
+    }
+
+    /// 源实体指向的目标实体
+    pub fn relation_targets<R: RelationKind>(&self, source: usize) -> &[usize] {
+        match self.borrow_component::<Relation<R>>(source) {
+            Some(relation) => relation.targets(),
+            None => &[],
+        }
+    }
+
+    /// 指向目标实体的所有源实体
+    pub fn related_sources<R: RelationKind>(&self, target: usize) -> &[usize] {
+        match self.borrow_component::<RelatedBy<R>>(target) {
+            Some(related) => related.sources(),
+            None => &[],
+        }
+    }
+
+    /// 沿着关系方向广度优先遍历，返回所有可达的目标实体（不包含起点）
+    pub fn traverse_targets<R: RelationKind>(&self, start: usize) -> Vec<usize> {
+        self.traverse(start, |entity| self.relation_targets::<R>(entity))
+    }
+
+    /// 逆着关系方向广度优先遍历，返回所有可达的源实体（不包含起点）
+    pub fn traverse_sources<R: RelationKind>(&self, start: usize) -> Vec<usize> {
+        self.traverse(start, |entity| self.related_sources::<R>(entity))
+    }
+
+    fn traverse<'a>(&'a self, start: usize, next: impl Fn(usize) -> &'a [usize]) -> Vec<usize> {
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        let mut result = Vec::new();
+        while let Some(entity) = queue.pop_front() {
+            for &other in next(entity) {
+                if visited.insert(other) {
+                    result.push(other);
+                    queue.push_back(other);
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    struct Targets;
+    impl RelationKind for Targets {}
+
+    struct HeldBy;
+    impl RelationKind for HeldBy {
+        const ON_TARGET_DESPAWN: DespawnPolicy = DespawnPolicy::DespawnRelated;
+    }
+
+    #[test]
+    fn test_relation_cleanup_on_despawn() {
+        let mut world = World::new();
+        let manager = world.entity_manager_mut();
+        let bullet_a = manager.create_entity();
+        let bullet_b = manager.create_entity();
+        let enemy = manager.create_entity();
+
+        manager.relate::<Targets>(bullet_a, enemy);
+        manager.relate::<Targets>(bullet_b, enemy);
+        assert_eq!(manager.related_sources::<Targets>(enemy), &[bullet_a, bullet_b]);
+
+        manager.remove_entity(bullet_a);
+        assert_eq!(manager.related_sources::<Targets>(enemy), &[bullet_b]);
+
+        manager.remove_entity(enemy);
+        assert!(manager.has_entity(bullet_b));
+        assert!(!manager.entity(bullet_b).contains::<Relation<Targets>>());
+    }
+
+    #[test]
+    fn test_relation_despawn_related() {
+        let mut world = World::new();
+        let manager = world.entity_manager_mut();
+        let player = manager.create_entity();
+        let sword = manager.create_entity();
+        let gem = manager.create_entity();
+
+        manager.relate::<HeldBy>(sword, player);
+        manager.relate::<HeldBy>(gem, sword);
+        assert_eq!(manager.traverse_targets::<HeldBy>(gem), vec![sword, player]);
+        assert_eq!(manager.traverse_sources::<HeldBy>(player), vec![sword, gem]);
+
+        manager.remove_entity(player);
+        assert!(!manager.has_entity(sword));
+        assert!(!manager.has_entity(gem));
+    }
+
+    #[test]
+    fn test_many_to_many_relation() {
+        let mut world = World::new();
+        let manager = world.entity_manager_mut();
+        let turrets: Vec<usize> = (0..2).map(|_| manager.create_entity()).collect();
+        let zones: Vec<usize> = (0..2).map(|_| manager.create_entity()).collect();
+        for turret in &turrets {
+            for zone in &zones {
+                manager.relate::<Targets>(*turret, *zone);
+            }
+        }
+
+        manager.unrelate::<Targets>(turrets[0], zones[1]);
+        assert_eq!(manager.relation_targets::<Targets>(turrets[0]), &[zones[0]]);
+        assert_eq!(manager.related_sources::<Targets>(zones[1]), &[turrets[1]]);
+
+        manager.unrelate::<Targets>(turrets[1], zones[1]);
+        assert!(!manager.entity(zones[1]).contains::<RelatedBy<Targets>>());
+    }
+}
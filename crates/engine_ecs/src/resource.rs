@@ -6,18 +6,22 @@ pub trait Resource: 'static {}
 
 pub struct ResourceManager {
     resources: HashMap<TypeId, Box<dyn Any>>,
+    // 资源的类型名，用于诊断和崩溃报告
+    names: HashMap<TypeId, &'static str>,
 }
 
 impl ResourceManager {
     pub fn new() -> Self {
         ResourceManager {
             resources: HashMap::new(),
+            names: HashMap::new(),
         }
     }
 
     pub fn add<T: 'static>(&mut self, resource: T) {
         let type_id = TypeId::of::<T>();
         self.resources.insert(type_id, Box::new(resource));
+        self.names.insert(type_id, std::any::type_name::<T>());
     }
 
     pub fn get<T: 'static>(&self) -> Option<&T> {
@@ -33,9 +37,23 @@ impl ResourceManager {
     }
 
     pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.names.remove(&TypeId::of::<T>());
         self.resources
             .remove(&TypeId::of::<T>())
             .and_then(|boxed| boxed.downcast::<T>().ok())
             .map(|boxed| *boxed)
     }
+
+    /// 所有资源的类型名，顺序不固定
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.names.values().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.resources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
 }
@@ -0,0 +1,294 @@
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+
+use super::entity_manager::EntityManager;
+use super::system::{run_system, RunningSystemGuard, System, SystemTimings};
+use super::world::World;
+
+/// 调度标签。任何实现了 `Debug + Clone + Eq + Hash` 的类型都可以作为标签，
+/// 例如 `struct Update;` 或 `OnEnter(GameState::Menu)`。
+pub trait ScheduleLabel: Debug + Send + Sync + 'static {
+    fn dyn_clone(&self) -> Box<dyn ScheduleLabel>;
+    fn dyn_eq(&self, other: &dyn ScheduleLabel) -> bool;
+    fn dyn_hash(&self, state: &mut dyn Hasher);
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Debug + Clone + Eq + Hash + Send + Sync + 'static> ScheduleLabel for T {
+    fn dyn_clone(&self) -> Box<dyn ScheduleLabel> {
+        Box::new(self.clone())
+    }
+
+    fn dyn_eq(&self, other: &dyn ScheduleLabel) -> bool {
+        other.as_any().downcast_ref::<T>().is_some_and(|other| self == other)
+    }
+
+    fn dyn_hash(&self, mut state: &mut dyn Hasher) {
+        TypeId::of::<T>().hash(&mut state);
+        self.hash(&mut state);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// 类型擦除后的调度标签，可以作为 HashMap 的键
+#[derive(Debug)]
+pub struct ScheduleKey(Box<dyn ScheduleLabel>);
+
+impl ScheduleKey {
+    pub fn new(label: impl ScheduleLabel) -> Self {
+        // 已经擦除过的标签直接复用，避免嵌套
+        if let Some(key) = label.as_any().downcast_ref::<ScheduleKey>() {
+            return key.clone();
+        }
+        ScheduleKey(Box::new(label))
+    }
+
+    pub fn label(&self) -> &dyn ScheduleLabel {
+        self.0.as_ref()
+    }
+}
+
+impl Clone for ScheduleKey {
+    fn clone(&self) -> Self {
+        ScheduleKey(self.0.dyn_clone())
+    }
+}
+
+impl PartialEq for ScheduleKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.dyn_eq(other.0.as_ref())
+    }
+}
+
+impl Eq for ScheduleKey {}
+
+impl Hash for ScheduleKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.dyn_hash(state);
+    }
+}
+
+/// 运行条件，返回 `false` 时跳过系统
+pub trait Condition: 'static {
+    fn evaluate(&mut self, manager: &EntityManager) -> bool;
+}
+
+impl<F: FnMut(&EntityManager) -> bool + 'static> Condition for F {
+    fn evaluate(&mut self, manager: &EntityManager) -> bool {
+        self(manager)
+    }
+}
+
+enum SystemKind {
+    Normal(Box<dyn System>),
+    // 需要访问整个 World 的系统，例如状态切换
+    Exclusive(Box<dyn FnMut(&mut World)>),
+}
+
+/// 添加到调度中的系统及其运行条件
+pub struct SystemConfig {
+    name: Cow<'static, str>,
+    kind: SystemKind,
+    conditions: Vec<Box<dyn Condition>>,
+}
+
+impl SystemConfig {
+    /// 需要 `&mut World` 的独占系统
+    pub fn exclusive<F: FnMut(&mut World) + 'static>(system: F) -> Self {
+        SystemConfig {
+            name: std::any::type_name::<F>().into(),
+            kind: SystemKind::Exclusive(Box::new(system)),
+            conditions: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 修改系统名称，例如给闭包起一个容易辨认的名字
+    pub fn with_name(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.name = name.into();
+        self
+    }
+}
+
+pub trait IntoSystemConfig {
+    fn into_config(self) -> SystemConfig;
+
+    /// 只有条件满足时才运行系统，可以多次调用叠加条件
+    fn run_if(self, condition: impl Condition) -> SystemConfig
+    where
+        Self: Sized,
+    {
+        let mut config = self.into_config();
+        config.conditions.push(Box::new(condition));
+        config
+    }
+}
+
+impl<S: System + 'static> IntoSystemConfig for S {
+    fn into_config(self) -> SystemConfig {
+        SystemConfig {
+            name: self.name(),
+            kind: SystemKind::Normal(Box::new(self)),
+            conditions: Vec::new(),
+        }
+    }
+}
+
+impl IntoSystemConfig for SystemConfig {
+    fn into_config(self) -> SystemConfig {
+        self
+    }
+}
+
+/// 按添加顺序依次运行的一组系统
+pub struct Schedule {
+    label: ScheduleKey,
+    systems: Vec<SystemConfig>,
+}
+
+impl Schedule {
+    pub fn new(label: impl ScheduleLabel) -> Self {
+        Schedule {
+            label: ScheduleKey::new(label),
+            systems: Vec::new(),
+        }
+    }
+
+    pub fn label(&self) -> &dyn ScheduleLabel {
+        self.label.label()
+    }
+
+    pub fn add_system(&mut self, system: impl IntoSystemConfig) -> &mut Self {
+        self.systems.push(system.into_config());
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    pub(crate) fn append(&mut self, other: Schedule) {
+        self.systems.extend(other.systems);
+    }
+
+    pub fn run(&mut self, world: &mut World) {
+        #[cfg(feature = "trace")]
+        let _span = tracing::info_span!("schedule", name = ?self.label.label()).entered();
+        for config in self.systems.iter_mut() {
+            world.flush();
+            let manager = world.entity_manager();
+            if !config.conditions.iter_mut().all(|condition| condition.evaluate(manager)) {
+                continue;
+            }
+            match &mut config.kind {
+                SystemKind::Normal(system) => {
+                    let (manager, accessor) = world.split_for_system();
+                    run_system(system.as_mut(), manager, accessor);
+                }
+                SystemKind::Exclusive(system) => {
+                    #[cfg(feature = "trace")]
+                    let _span = tracing::info_span!("system", name = %config.name).entered();
+                    let _running = RunningSystemGuard::enter(config.name.clone());
+                    let start = world.get_resource::<SystemTimings>().is_some().then(std::time::Instant::now);
+                    system(world);
+                    if let Some(start) = start {
+                        let elapsed = start.elapsed();
+                        if let Some(timings) = world.get_resource_mut::<SystemTimings>() {
+                            timings.record(config.name.clone(), elapsed);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// World 中所有调度的集合
+#[derive(Default)]
+pub struct Schedules {
+    schedules: HashMap<ScheduleKey, Schedule>,
+}
+
+impl Schedules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contains(&self, label: impl ScheduleLabel) -> bool {
+        self.schedules.contains_key(&ScheduleKey::new(label))
+    }
+
+    pub fn insert(&mut self, schedule: Schedule) -> Option<Schedule> {
+        self.schedules.insert(schedule.label.clone(), schedule)
+    }
+
+    pub fn remove(&mut self, label: &ScheduleKey) -> Option<Schedule> {
+        self.schedules.remove(label)
+    }
+
+    pub fn get(&self, label: impl ScheduleLabel) -> Option<&Schedule> {
+        self.schedules.get(&ScheduleKey::new(label))
+    }
+
+    /// 获取调度，不存在时创建一个空的调度
+    pub fn entry(&mut self, label: impl ScheduleLabel) -> &mut Schedule {
+        let key = ScheduleKey::new(label);
+        self.schedules
+            .entry(key.clone())
+            .or_insert_with(|| Schedule { label: key, systems: Vec::new() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Update;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct OnLevel(u32);
+
+    struct Score(u32);
+
+    #[test]
+    fn test_run_conditions_and_exclusive_systems() {
+        let mut world = World::new();
+        world.add_resource(Score(0));
+        world
+            .add_system_to(Update, |manager: &mut EntityManager| {
+                manager.get_resource_mut::<Score>().unwrap().0 += 1;
+            })
+            .add_system_to(
+                Update,
+                (|manager: &mut EntityManager| {
+                    manager.get_resource_mut::<Score>().unwrap().0 += 100;
+                })
+                .run_if(|manager: &EntityManager| manager.get_resource::<Score>().unwrap().0 > 1),
+            )
+            .add_system_to(Update, SystemConfig::exclusive(|world: &mut World| world.run_schedule(OnLevel(2))))
+            .add_system_to(OnLevel(2), |manager: &mut EntityManager| {
+                manager.get_resource_mut::<Score>().unwrap().0 *= 2;
+            });
+
+        assert!(world.try_run_schedule(Update));
+        assert_eq!(world.get_resource::<Score>().unwrap().0, 2);
+        world.run_schedule(Update);
+        assert_eq!(world.get_resource::<Score>().unwrap().0, 206);
+        assert!(!world.try_run_schedule(OnLevel(1)));
+        assert!(world.schedules().contains(ScheduleKey::new(OnLevel(2))));
+    }
+}
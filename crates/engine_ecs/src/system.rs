@@ -1,5 +1,88 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::time::Duration;
+
 use super::entity_manager::{EntityIdAccessor, EntityManager};
 
 pub trait System {
 	fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor);
+
+	/// 系统名称，用于诊断和日志，默认是类型名
+	fn name(&self) -> Cow<'static, str> {
+		std::any::type_name::<Self>().into()
+	}
+}
+
+/// 只需要访问 EntityManager 的闭包也可以作为系统
+impl<F: FnMut(&mut EntityManager) + 'static> System for F {
+	fn update(&mut self, manager: &mut EntityManager, _accessor: &mut EntityIdAccessor) {
+		self(manager)
+	}
+}
+
+/// 添加这个资源后，调度和 `World::update` 会记录每个系统每次运行的时间
+#[derive(Debug, Default)]
+pub struct SystemTimings {
+	runs: Vec<(Cow<'static, str>, Duration)>,
+}
+
+impl SystemTimings {
+	pub fn record(&mut self, name: Cow<'static, str>, duration: Duration) {
+		self.runs.push((name, duration));
+	}
+
+	/// 按运行顺序返回记录
+	pub fn iter(&self) -> impl Iterator<Item = (&str, Duration)> {
+		self.runs.iter().map(|(name, duration)| (name.as_ref(), *duration))
+	}
+
+	/// 取出所有记录，通常每帧调用一次
+	pub fn drain(&mut self) -> impl Iterator<Item = (Cow<'static, str>, Duration)> + '_ {
+		self.runs.drain(..)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.runs.is_empty()
+	}
+}
+
+thread_local! {
+	static RUNNING_SYSTEM: RefCell<Option<Cow<'static, str>>> = const { RefCell::new(None) };
+}
+
+/// 当前线程正在运行的系统名称，可以在 panic hook 中用来定位出错的系统
+pub fn running_system() -> Option<Cow<'static, str>> {
+	RUNNING_SYSTEM.with(|running| running.borrow().clone())
+}
+
+/// 在作用域内把系统标记为正在运行，结束时恢复之前的值（exclusive 系统中可能嵌套运行调度）
+pub(crate) struct RunningSystemGuard(Option<Cow<'static, str>>);
+
+impl RunningSystemGuard {
+	pub(crate) fn enter(name: Cow<'static, str>) -> Self {
+		RunningSystemGuard(RUNNING_SYSTEM.with(|running| running.borrow_mut().replace(name)))
+	}
+}
+
+impl Drop for RunningSystemGuard {
+	fn drop(&mut self) {
+		let previous = self.0.take();
+		RUNNING_SYSTEM.with(|running| *running.borrow_mut() = previous);
+	}
+}
+
+/// 运行系统，存在 [`SystemTimings`] 资源时记录运行时间
+pub(crate) fn run_system(system: &mut dyn System, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+	#[cfg(feature = "trace")]
+	let _span = tracing::info_span!("system", name = %system.name()).entered();
+	let _running = RunningSystemGuard::enter(system.name());
+	let start = manager.get_resource::<SystemTimings>().is_some().then(std::time::Instant::now);
+	system.update(manager, accessor);
+	if let Some(start) = start {
+		let elapsed = start.elapsed();
+		if let Some(timings) = manager.get_resource_mut::<SystemTimings>() {
+			timings.record(system.name(), elapsed);
+		}
+	}
+	manager.increment_frame();
 }
@@ -1,11 +1,19 @@
-use super::component::Component;
-use super::entity_manager::{EntityIdAccessor, EntityManager};
-use super::system::System;
+use tracing::warn;
+
+use super::bundle::Bundle;
+use super::component::{Component, ComponentDescriptor, ComponentId};
+use super::entity_manager::{EntityIdAccessor, EntityManager, EntityReserver};
+use super::entity_cloner::{EntityCloner, EntityMap};
+use super::entity_ref::{EntityMut, EntityRef};
+use super::relationship::RelationKind;
+use super::schedule::{IntoSystemConfig, ScheduleKey, ScheduleLabel, Schedules};
+use super::system::{run_system, System};
 
 pub struct World {
     entity_manager: EntityManager,
     entity_id_accessor: EntityIdAccessor,
     systems: Vec<Box<dyn System>>,
+    schedules: Schedules,
 }
 
 impl World {
@@ -14,6 +22,7 @@ impl World {
             entity_manager: EntityManager::new(),
             entity_id_accessor: EntityIdAccessor::new(),
             systems: vec![],
+            schedules: Schedules::new(),
         }
     }
 
@@ -37,6 +46,123 @@ impl World {
         self.entity_manager.remove_entity(entity_id);
     }
 
+    /// 创建实体并插入 bundle 中的所有组件
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> usize {
+        self.entity_manager.spawn(bundle)
+    }
+
+    /// 批量创建实体，整批只触发一次变更记录
+    pub fn spawn_batch<B: Bundle, I: IntoIterator<Item = B>>(&mut self, bundles: I) -> Vec<usize> {
+        self.entity_manager.spawn_batch(bundles)
+    }
+
+    /// 通过 `&World` 预留实体 ID，下一次 flush 时生效
+    pub fn reserve_entity(&self) -> usize {
+        self.entity_manager.reserve_entity()
+    }
+
+    /// 获取可以发送到其他线程的实体 ID 预留句柄
+    pub fn entity_reserver(&self) -> EntityReserver {
+        self.entity_manager.entity_reserver()
+    }
+
+    /// 将预留的实体 ID 变为存活的实体
+    pub fn flush(&mut self) {
+        #[cfg(feature = "trace")]
+        let _span = tracing::info_span!("flush").entered();
+        self.entity_manager.flush();
+    }
+
+    /// 获取实体的只读访问，实体不存在时 panic
+    pub fn entity(&self, entity_id: usize) -> EntityRef<'_> {
+        self.entity_manager.entity(entity_id)
+    }
+
+    /// 获取实体的可变访问，实体不存在时 panic
+    pub fn entity_mut(&mut self, entity_id: usize) -> EntityMut<'_> {
+        self.entity_manager.entity_mut(entity_id)
+    }
+
+    pub fn get_entity(&self, entity_id: usize) -> Option<EntityRef<'_>> {
+        self.entity_manager.get_entity(entity_id)
+    }
+
+    pub fn get_entity_mut(&mut self, entity_id: usize) -> Option<EntityMut<'_>> {
+        self.entity_manager.get_entity_mut(entity_id)
+    }
+
+    pub fn component_id<T: 'static + Component>(&self) -> Option<ComponentId> {
+        self.entity_manager.component_id::<T>()
+    }
+
+    /// 注册动态组件，返回新分配的 ComponentId
+    pub fn register_component_with_descriptor(&mut self, descriptor: ComponentDescriptor) -> ComponentId {
+        self.entity_manager.register_component_with_descriptor(descriptor)
+    }
+
+    /// 注册组件并允许它被复制
+    pub fn register_clonable<T: 'static + Component + Clone>(&mut self) -> &mut Self {
+        self.entity_manager.register_clonable::<T>();
+        self
+    }
+
+    /// 复制实体的所有可复制组件，返回新实体的 ID，实体不存在时返回 `None`
+    pub fn clone_entity(&mut self, entity_id: usize) -> Option<usize> {
+        self.entity_manager.clone_entity(entity_id)
+    }
+
+    /// 把实体复制到另一个 World（例如从预制体模板复制到游戏世界）
+    pub fn copy_entities_to(&self, entities: &[usize], other: &mut World) -> EntityMap {
+        EntityCloner::new().clone_entities_into(&self.entity_manager, entities, &mut other.entity_manager)
+    }
+
+    /// 把实体移动到另一个 World，原实体会被销毁。
+    ///
+    /// 移动通过复制实现，有未注册为可复制组件的实体不会被移动（否则这些组件会丢失），
+    /// 它们留在当前 World 中、不在返回的映射里，并输出警告列出这些组件。
+    pub fn move_entities_to(&mut self, entities: &[usize], other: &mut World) -> EntityMap {
+        let movable: Vec<usize> = entities
+            .iter()
+            .copied()
+            .filter(|entity| {
+                let components = self.entity_manager.non_clonable_components(*entity);
+                if !components.is_empty() {
+                    warn!(
+                        "Cannot move entity {} to another world, components are not clonable: {}",
+                        entity,
+                        components.join(", ")
+                    );
+                }
+                components.is_empty()
+            })
+            .collect();
+        let entity_map = self.copy_entities_to(&movable, other);
+        for entity in movable {
+            self.entity_manager.remove_entity(entity);
+        }
+        entity_map
+    }
+
+    /// 建立 `source -> target` 关系
+    pub fn relate<R: RelationKind>(&mut self, source: usize, target: usize) -> &mut Self {
+        self.entity_manager.relate::<R>(source, target);
+        self
+    }
+
+    /// 移除 `source -> target` 关系
+    pub fn unrelate<R: RelationKind>(&mut self, source: usize, target: usize) -> &mut Self {
+        self.entity_manager.unrelate::<R>(source, target);
+        self
+    }
+
+    pub fn entity_manager(&self) -> &EntityManager {
+        &self.entity_manager
+    }
+
+    pub fn entity_manager_mut(&mut self) -> &mut EntityManager {
+        &mut self.entity_manager
+    }
+
     pub fn register_component<T: 'static + Component>(&mut self) -> &mut Self {
         self.entity_manager.register::<T>();
         self
@@ -74,10 +200,54 @@ impl World {
         self.entity_manager.remove_resource()
     }
 
+    /// 所有资源的类型名，按名称排序
+    pub fn resource_names(&self) -> Vec<&'static str> {
+        self.entity_manager.resource_names()
+    }
+
+    /// 把系统添加到指定的调度中，调度不存在时会自动创建
+    pub fn add_system_to(&mut self, label: impl ScheduleLabel, system: impl IntoSystemConfig) -> &mut Self {
+        self.schedules.entry(label).add_system(system);
+        self
+    }
+
+    pub fn schedules(&self) -> &Schedules {
+        &self.schedules
+    }
+
+    pub fn schedules_mut(&mut self) -> &mut Schedules {
+        &mut self.schedules
+    }
+
+    /// 运行调度，调度不存在时返回 `false`。
+    ///
+    /// 运行期间调度会从 World 中暂时取出，独占系统可以在其中运行其他调度。
+    pub fn try_run_schedule(&mut self, label: impl ScheduleLabel) -> bool {
+        let key = ScheduleKey::new(label);
+        let Some(mut schedule) = self.schedules.remove(&key) else {
+            return false;
+        };
+        schedule.run(self);
+        // 运行期间添加到同一调度的系统保留在新创建的调度中
+        if let Some(added) = self.schedules.remove(&key) {
+            schedule.append(added);
+        }
+        self.schedules.insert(schedule);
+        true
+    }
+
+    pub fn run_schedule(&mut self, label: impl ScheduleLabel) {
+        self.try_run_schedule(label);
+    }
+
+    pub(crate) fn split_for_system(&mut self) -> (&mut EntityManager, &mut EntityIdAccessor) {
+        (&mut self.entity_manager, &mut self.entity_id_accessor)
+    }
+
     pub fn update(&mut self) {
         for system in self.systems.iter_mut() {
-            system.update(&mut self.entity_manager, &mut self.entity_id_accessor);
-            self.entity_manager.increment_frame();
+            self.entity_manager.flush();
+            run_system(system.as_mut(), &mut self.entity_manager, &mut self.entity_id_accessor);
         }
     }
 
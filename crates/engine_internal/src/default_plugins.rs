@@ -2,6 +2,10 @@ use engine_app::plugin_group;
 
 plugin_group! {
     pub struct MinimalPlugins {
+        engine_config::ConfigPlugin,
+        engine_tasks::TaskPoolPlugin,
+        engine_time::TimePlugin,
+        engine_window::prelude::WindowPlugin,
         engine_winit::WinitPlugin,
     }
 }
@@ -12,7 +16,19 @@ mod test {
     #[test]
     fn  test_plugin_group_creation() {
         let builder = super::MinimalPlugins.build();
-        assert_eq!(builder.len(), 1);
+        assert_eq!(builder.len(), 5);
+        assert!(builder.contains::<engine_config::ConfigPlugin>());
+        assert!(builder.contains::<engine_tasks::TaskPoolPlugin>());
+        assert!(builder.contains::<engine_time::TimePlugin>());
         assert!(builder.contains::<engine_winit::WinitPlugin>());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_set_winit_plugin_config() {
+        let mut winit_plugin = engine_winit::WinitPlugin::<engine_winit::WakeUp>::default();
+        winit_plugin.run_on_any_thread = true;
+        let builder = super::MinimalPlugins.set(winit_plugin);
+        assert_eq!(builder.len(), 5);
+        assert!(builder.enabled::<engine_winit::WinitPlugin>());
+    }
+}
@@ -4,6 +4,12 @@ use engine_app as app;
 use engine_math as math;
 use engine_platform as platform;
 use engine_ecs as ecs;
+use engine_time as time;
+use engine_log as log;
+use engine_diagnostic as diagnostic;
+use engine_tasks as tasks;
+use engine_config as config;
+use engine_crash as crash;
 
 mod default_plugins;
 
@@ -14,6 +20,12 @@ pub mod prelude {
     pub use super::window::prelude::*;
     pub use super::winit::prelude::*;
     pub use super::ecs::prelude::*;
+    pub use super::time::prelude::*;
+    pub use super::log::prelude::*;
+    pub use super::diagnostic::prelude::*;
+    pub use super::tasks::prelude::*;
+    pub use super::config::prelude::*;
+    pub use super::crash::prelude::*;
 
     pub use super::default_plugins::*;
 }
\ No newline at end of file
@@ -0,0 +1,122 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_chrome::{ChromeLayerBuilder, EventOrSpan, FlushGuard};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::BoxedLayer;
+
+/// Chrome trace 文件的路径，默认是当前目录下的 `trace-<时间戳>.json`
+pub const TRACE_CHROME_ENV: &str = "ENGINE_TRACE_CHROME";
+
+/// 释放时把剩余的 span 写入 Chrome trace 文件，[`LogPlugin`](crate::LogPlugin) 把它作为资源保存，
+/// 所以应用退出、App 释放时文件才完整
+pub struct ChromeTraceGuard(FlushGuard);
+
+impl ChromeTraceGuard {
+    /// 立即写入已经记录的 span
+    pub fn flush(&self) {
+        self.0.flush();
+    }
+}
+
+pub(crate) fn trace_file() -> PathBuf {
+    match std::env::var(TRACE_CHROME_ENV) {
+        Ok(path) if !path.is_empty() => PathBuf::from(path),
+        _ => {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_micros());
+            PathBuf::from(format!("trace-{}.json", timestamp))
+        }
+    }
+}
+
+/// 创建写入 Chrome trace JSON 的输出层，可以用 Perfetto 或 `chrome://tracing` 查看。
+///
+/// 带有 `name` 字段的 span 显示为 `<span 名称>: <name>`，例如 `system: game::move_system`。
+pub fn chrome_layer(path: impl Into<PathBuf>) -> (BoxedLayer, ChromeTraceGuard) {
+    let (layer, guard) = ChromeLayerBuilder::new()
+        .file(path.into())
+        .include_args(true)
+        .name_fn(Box::new(|event_or_span| match event_or_span {
+            EventOrSpan::Event(event) => event.metadata().name().into(),
+            EventOrSpan::Span(span) => match span.extensions().get::<SpanDisplayName>() {
+                Some(name) => format!("{}: {}", span.metadata().name(), name.0),
+                None => span.metadata().name().into(),
+            },
+        }))
+        .build();
+    (Box::new(SpanNameLayer.and_then(layer)), ChromeTraceGuard(guard))
+}
+
+// span 的 `name` 字段
+struct SpanDisplayName(String);
+
+// 在 span 创建时记录 `name` 字段，供 Chrome trace 显示
+struct SpanNameLayer;
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for SpanNameLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = NameVisitor(None);
+        attrs.record(&mut visitor);
+        if let (Some(name), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().insert(SpanDisplayName(name));
+        }
+    }
+}
+
+struct NameVisitor(Option<String>);
+
+impl Visit for NameVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "name" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "name" {
+            self.0 = Some(format!("{:?}", value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use engine_app::prelude::*;
+    use engine_ecs::prelude::*;
+    use tracing_subscriber::prelude::*;
+    use tracing_subscriber::EnvFilter;
+
+    use super::*;
+
+    fn move_system(_manager: &mut EntityManager) {}
+
+    #[test]
+    fn test_chrome_trace_contains_system_spans() {
+        let path = std::env::temp_dir().join(format!("engine_trace_{}.json", std::process::id()));
+        let (layer, guard) = chrome_layer(&path);
+        let subscriber = tracing_subscriber::registry()
+            .with(EnvFilter::new("info"))
+            .with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            let mut app = App::new();
+            app.add_systems(Update, move_system);
+            app.update();
+        });
+        drop(guard);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(contents.trim_start().starts_with('['));
+        assert!(contents.contains("\"name\":\"frame\""));
+        assert!(contents.contains("\"name\":\"schedule: Update\""));
+        assert!(contents.contains("system: engine_log::chrome::tests::move_system"));
+        assert!(contents.contains("\"name\":\"event_update: engine_app::app::AppExit\""));
+    }
+}
@@ -0,0 +1,200 @@
+use std::fs::OpenOptions;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use engine_app::prelude::*;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::layer::Layered;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+pub use tracing::{self, debug, error, info, trace, warn, Level};
+
+pub mod prelude {
+    pub use super::{debug, error, info, trace, warn, Level, LogPlugin, RecentLogs, LOG_ENV};
+}
+
+mod recent;
+
+pub use recent::{RecentLogs, RecentLogsLayer};
+
+#[cfg(feature = "trace")]
+mod chrome;
+
+#[cfg(feature = "trace")]
+pub use chrome::{chrome_layer, ChromeTraceGuard, TRACE_CHROME_ENV};
+
+/// 设置后覆盖代码中的日志过滤规则，语法同 `RUST_LOG`，例如 `ENGINE_LOG=info,engine_winit=trace`
+pub const LOG_ENV: &str = "ENGINE_LOG";
+
+// 输出层位于过滤层之上，只会收到通过过滤的日志
+pub(crate) type BoxedLayer = Box<dyn Layer<Layered<EnvFilter, Registry>> + Send + Sync>;
+
+/// 把引擎和 `log` crate（例如 winit）的日志输出到终端，也可以同时写入文件。
+///
+/// ```ignore
+/// app.add_plugin(LogPlugin {
+///     filter: "engine_winit=warn,engine_ecs=debug".to_string(),
+///     file: Some("game.log".into()),
+///     ..Default::default()
+/// });
+/// ```
+///
+/// 全局日志只能设置一次，重复添加时只会输出警告。
+///
+/// 启用 `trace` 特性后还会把系统、调度等 span 写入 Chrome trace 文件，路径由 `ENGINE_TRACE_CHROME` 环境变量指定。
+#[derive(Debug, Clone)]
+pub struct LogPlugin {
+    /// 没有被 `filter` 匹配的日志的级别
+    pub level: Level,
+    /// 按 crate 或模块设置级别，逗号分隔，例如 `wgpu=error,engine_ecs=debug`
+    pub filter: String,
+    /// 以 JSON 格式输出，每行一条
+    pub json: bool,
+    /// 同时追加写入的日志文件
+    pub file: Option<PathBuf>,
+    /// 在内存中保留的最近日志行数，保存在 [`RecentLogs`] 资源中，崩溃报告会用到
+    pub recent_lines: usize,
+}
+
+impl Default for LogPlugin {
+    fn default() -> Self {
+        LogPlugin {
+            level: Level::INFO,
+            filter: "winit=warn".to_string(),
+            json: false,
+            file: None,
+            recent_lines: 100,
+        }
+    }
+}
+
+impl LogPlugin {
+    /// 过滤规则，[`LOG_ENV`] 环境变量优先于代码中的设置
+    pub fn env_filter(&self) -> EnvFilter {
+        let directives = std::env::var(LOG_ENV).unwrap_or_else(|_| self.directives());
+        EnvFilter::builder().parse_lossy(directives)
+    }
+
+    fn directives(&self) -> String {
+        if self.filter.is_empty() {
+            self.level.to_string()
+        } else {
+            format!("{},{}", self.level, self.filter)
+        }
+    }
+
+    /// 按配置创建输出层（不包含过滤）
+    pub fn layers(&self) -> std::io::Result<Vec<BoxedLayer>> {
+        let mut layers: Vec<BoxedLayer> = Vec::new();
+        let stdout = tracing_subscriber::fmt::layer().with_writer(std::io::stdout);
+        layers.push(if self.json { stdout.json().boxed() } else { stdout.boxed() });
+
+        if let Some(path) = &self.file {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            let file = tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(Mutex::new(file));
+            layers.push(if self.json { file.json().boxed() } else { file.boxed() });
+        }
+        Ok(layers)
+    }
+}
+
+impl Plugin for LogPlugin {
+    fn build(&self, app: &mut App) {
+        let mut layers = match self.layers() {
+            Ok(layers) => layers,
+            Err(err) => {
+                // 日志系统还没有设置，只能直接输出
+                eprintln!("Failed to open log file {:?}: {}", self.file, err);
+                let mut without_file = self.clone();
+                without_file.file = None;
+                without_file.layers().unwrap_or_default()
+            }
+        };
+
+        let recent = RecentLogs::new(self.recent_lines);
+        layers.push(Box::new(recent.layer()));
+        app.world_mut().add_resource(recent);
+
+        #[cfg(feature = "trace")]
+        {
+            let (layer, guard) = chrome_layer(chrome::trace_file());
+            layers.push(layer);
+            app.world_mut().add_resource(guard);
+        }
+
+        // 同时把 `log` crate 的日志转发给 tracing
+        let subscriber = tracing_subscriber::registry().with(self.env_filter()).with(layers);
+        if subscriber.try_init().is_err() {
+            warn!("Could not set the global logger, it has already been set");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_directives() {
+        let plugin = LogPlugin {
+            level: Level::WARN,
+            filter: "engine_ecs=debug".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(plugin.directives(), "WARN,engine_ecs=debug");
+
+        let plugin = LogPlugin {
+            filter: String::new(),
+            ..Default::default()
+        };
+        assert_eq!(plugin.directives(), "INFO");
+    }
+
+    #[test]
+    fn test_json_log_file() {
+        let path = std::env::temp_dir().join(format!("engine_log_{}.log", std::process::id()));
+        let plugin = LogPlugin {
+            json: true,
+            file: Some(path.clone()),
+            ..Default::default()
+        };
+        let subscriber = tracing_subscriber::registry()
+            .with(EnvFilter::new("info"))
+            .with(plugin.layers().unwrap());
+        tracing::subscriber::with_default(subscriber, || {
+            info!(frame = 3, "window created");
+            debug!("filtered out");
+        });
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with('{'));
+        assert!(lines[0].contains("\"message\":\"window created\""));
+        assert!(lines[0].contains("\"frame\":3"));
+    }
+
+    #[test]
+    fn test_recent_logs() {
+        let recent = RecentLogs::new(2);
+        let subscriber = tracing_subscriber::registry()
+            .with(EnvFilter::new("info"))
+            .with(recent.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            info!("first");
+            warn!(entity = 7, "second");
+            debug!("filtered out");
+            error!("third");
+        });
+        assert_eq!(
+            recent.lines().unwrap(),
+            vec![
+                "WARN engine_log::tests: second entity=7".to_string(),
+                "ERROR engine_log::tests: third".to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// 最近的日志行，由 [`LogPlugin`](crate::LogPlugin) 作为资源添加，克隆得到的是同一个缓冲区。
+///
+/// 崩溃报告用它记录 panic 之前发生了什么。
+#[derive(Debug, Clone)]
+pub struct RecentLogs {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    capacity: usize,
+}
+
+impl RecentLogs {
+    pub fn new(capacity: usize) -> Self {
+        RecentLogs {
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn push(&self, line: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut lines = self.lines.lock().unwrap_or_else(|err| err.into_inner());
+        if lines.len() >= self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// 按时间顺序返回保存的日志行。
+    ///
+    /// 缓冲区正在被当前线程写入时（例如在日志输出中 panic）返回 `None`，避免在 panic hook 中死锁。
+    pub fn lines(&self) -> Option<Vec<String>> {
+        let lines = match self.lines.try_lock() {
+            Ok(lines) => lines,
+            Err(std::sync::TryLockError::Poisoned(err)) => err.into_inner(),
+            Err(std::sync::TryLockError::WouldBlock) => return None,
+        };
+        Some(lines.iter().cloned().collect())
+    }
+
+    /// 把日志写入缓冲区的输出层
+    pub fn layer(&self) -> RecentLogsLayer {
+        RecentLogsLayer(self.clone())
+    }
+}
+
+/// 见 [`RecentLogs::layer`]
+pub struct RecentLogsLayer(RecentLogs);
+
+impl<S: Subscriber> Layer<S> for RecentLogsLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = LineVisitor(format!("{} {}:", metadata.level(), metadata.target()));
+        event.record(&mut visitor);
+        self.0.push(visitor.0);
+    }
+}
+
+struct LineVisitor(String);
+
+impl Visit for LineVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, " {:?}", value);
+        } else {
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            let _ = write!(self.0, " {}", value);
+        } else {
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+}
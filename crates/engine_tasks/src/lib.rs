@@ -0,0 +1,230 @@
+mod task;
+mod task_pool;
+mod usages;
+
+use engine_app::prelude::*;
+use tracing::debug;
+
+pub use task::{block_on, Task};
+pub use task_pool::{available_parallelism, Scope, TaskPool, TaskPoolBuilder};
+pub use usages::{AsyncComputeTaskPool, ComputeTaskPool, IoTaskPool};
+
+pub mod prelude {
+    pub use super::{
+        block_on, AsyncComputeTaskPool, ComputeTaskPool, IoTaskPool, Task, TaskPool, TaskPoolOptions,
+        TaskPoolPlugin, TaskPoolThreads,
+    };
+}
+
+/// 一个线程池分到的线程数量
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaskPoolThreads {
+    pub min_threads: usize,
+    pub max_threads: usize,
+    /// 占总线程数的比例
+    pub percent: f32,
+}
+
+impl TaskPoolThreads {
+    /// 固定数量的线程
+    pub fn fixed(threads: usize) -> Self {
+        TaskPoolThreads {
+            min_threads: threads,
+            max_threads: threads,
+            percent: 1.0,
+        }
+    }
+
+    fn threads(&self, remaining: usize, total: usize) -> usize {
+        let desired = (total as f32 * self.percent).round() as usize;
+        desired.min(remaining).clamp(self.min_threads, self.max_threads).max(1)
+    }
+}
+
+/// 三个线程池的线程分配，依次分配 IO、异步计算，剩下的线程都给计算线程池
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskPoolOptions {
+    /// 总线程数，默认为 CPU 逻辑核心数
+    pub total_threads: Option<usize>,
+    pub io: TaskPoolThreads,
+    pub async_compute: TaskPoolThreads,
+    pub compute: TaskPoolThreads,
+}
+
+impl Default for TaskPoolOptions {
+    fn default() -> Self {
+        TaskPoolOptions {
+            total_threads: None,
+            io: TaskPoolThreads {
+                min_threads: 1,
+                max_threads: 4,
+                percent: 0.25,
+            },
+            async_compute: TaskPoolThreads {
+                min_threads: 1,
+                max_threads: 4,
+                percent: 0.25,
+            },
+            compute: TaskPoolThreads {
+                min_threads: 1,
+                max_threads: usize::MAX,
+                percent: 1.0,
+            },
+        }
+    }
+}
+
+impl TaskPoolOptions {
+    /// 按比例在三个线程池之间分配 `total_threads` 个线程
+    pub fn with_num_threads(total_threads: usize) -> Self {
+        TaskPoolOptions {
+            total_threads: Some(total_threads),
+            ..Default::default()
+        }
+    }
+
+    /// 依次返回 IO、异步计算和计算线程池的线程数
+    pub fn thread_counts(&self) -> (usize, usize, usize) {
+        let total = self.total_threads.unwrap_or_else(available_parallelism).max(1);
+        let mut remaining = total;
+        let io = self.io.threads(remaining, total);
+        remaining = remaining.saturating_sub(io);
+        let async_compute = self.async_compute.threads(remaining, total);
+        remaining = remaining.saturating_sub(async_compute);
+        let compute = self.compute.threads(remaining, total);
+        (io, async_compute, compute)
+    }
+}
+
+/// 添加 [`ComputeTaskPool`]、[`AsyncComputeTaskPool`] 和 [`IoTaskPool`] 资源，
+/// 已经添加的线程池不会被替换
+#[derive(Debug, Default, Clone)]
+pub struct TaskPoolPlugin {
+    pub task_pool_options: TaskPoolOptions,
+}
+
+impl Plugin for TaskPoolPlugin {
+    fn build(&self, app: &mut App) {
+        let (io, async_compute, compute) = self.task_pool_options.thread_counts();
+        debug!(io, async_compute, compute, "Creating task pools");
+        let world = app.world_mut();
+        if world.get_resource::<IoTaskPool>().is_none() {
+            world.add_resource(IoTaskPool::new(build_pool("IO Task Pool", io)));
+        }
+        if world.get_resource::<AsyncComputeTaskPool>().is_none() {
+            world.add_resource(AsyncComputeTaskPool::new(build_pool("Async Compute Task Pool", async_compute)));
+        }
+        if world.get_resource::<ComputeTaskPool>().is_none() {
+            world.add_resource(ComputeTaskPool::new(build_pool("Compute Task Pool", compute)));
+        }
+    }
+}
+
+fn build_pool(name: &str, threads: usize) -> TaskPool {
+    TaskPoolBuilder::new().thread_name(name).num_threads(threads).build()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use engine_ecs::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_thread_counts() {
+        let options = TaskPoolOptions::with_num_threads(16);
+        assert_eq!(options.thread_counts(), (4, 4, 8));
+        let options = TaskPoolOptions::with_num_threads(2);
+        assert_eq!(options.thread_counts(), (1, 1, 1));
+        let options = TaskPoolOptions {
+            compute: TaskPoolThreads::fixed(3),
+            ..TaskPoolOptions::with_num_threads(8)
+        };
+        assert_eq!(options.thread_counts(), (2, 2, 3));
+    }
+
+    #[test]
+    fn test_spawn_and_scope() {
+        let pool = TaskPoolBuilder::new().num_threads(2).build();
+        assert_eq!(block_on(pool.spawn(async { 40 + 2 })), 42);
+
+        let values: Vec<u32> = (1..=100).collect();
+        let sums = pool.scope(|scope| {
+            for chunk in values.chunks(10) {
+                scope.spawn(async move { chunk.iter().sum::<u32>() });
+            }
+        });
+        assert_eq!(sums.len(), 10);
+        assert_eq!(sums[0], 55);
+        assert_eq!(sums.iter().sum::<u32>(), 5050);
+    }
+
+    struct SetOnDrop(Arc<AtomicBool>);
+
+    impl Drop for SetOnDrop {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_drop_cancels_task() {
+        let pool = TaskPoolBuilder::new().num_threads(1).build();
+        let dropped = Arc::new(AtomicBool::new(false));
+        let guard = SetOnDrop(dropped.clone());
+        let task = pool.spawn(async move {
+            let _guard = guard;
+            std::future::pending::<()>().await;
+        });
+        drop(task);
+
+        let start = Instant::now();
+        while !dropped.load(Ordering::SeqCst) {
+            assert!(start.elapsed() < Duration::from_secs(5), "task was not cancelled");
+            std::thread::yield_now();
+        }
+    }
+
+    struct Decoded(u32);
+    impl Component for Decoded {}
+
+    fn poll_decode_tasks(manager: &mut EntityManager) {
+        let Some(component_id) = manager.component_id::<Task<u32>>() else {
+            return;
+        };
+        for id in manager.entity_ids_by_id(component_id).to_vec() {
+            let task = manager.borrow_component_mut::<Task<u32>>(id).unwrap();
+            if let Some(value) = task.check() {
+                manager.remove_component_from_entity::<Task<u32>>(id);
+                manager.add_component_to_entity(id, Decoded(value));
+            }
+        }
+    }
+
+    #[test]
+    fn test_poll_task_component() {
+        let mut app = App::new();
+        app.add_plugin(TaskPoolPlugin {
+            task_pool_options: TaskPoolOptions::with_num_threads(3),
+        });
+        app.add_systems(Update, poll_decode_tasks);
+        app.build_plugins().unwrap();
+        let world = app.world_mut();
+        world.register_component::<Task<u32>>().register_component::<Decoded>();
+        let task = world.get_resource::<AsyncComputeTaskPool>().unwrap().spawn(async { 7u32 });
+        let entity = world.spawn(task);
+
+        let start = Instant::now();
+        while app.world().entity_manager().borrow_component::<Decoded>(entity).is_none() {
+            assert!(start.elapsed() < Duration::from_secs(5), "task result was not polled");
+            app.run_once();
+        }
+        let manager = app.world().entity_manager();
+        assert_eq!(manager.borrow_component::<Decoded>(entity).unwrap().0, 7);
+        assert!(manager.borrow_component::<Task<u32>>(entity).is_none());
+    }
+}
@@ -0,0 +1,214 @@
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use engine_ecs::prelude::*;
+
+use super::task_pool::Queue;
+
+type BoxedFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+// 线程池调度的单元，持有类型擦除后的 future
+pub(crate) struct Runnable {
+    future: Mutex<Option<BoxedFuture>>,
+    // 已经在队列中，避免重复入队
+    scheduled: AtomicBool,
+    cancelled: AtomicBool,
+    // future 已经完成或被取消并释放
+    released: AtomicBool,
+    queue: Weak<Queue>,
+}
+
+impl Runnable {
+    pub(crate) fn run(self: Arc<Self>) {
+        self.scheduled.store(false, Ordering::SeqCst);
+        let mut slot = self.future.lock().unwrap();
+        let Some(future) = slot.as_mut() else {
+            return;
+        };
+        let finished = self.cancelled.load(Ordering::SeqCst) || {
+            let waker = Waker::from(self.clone());
+            future.as_mut().poll(&mut Context::from_waker(&waker)).is_ready()
+        };
+        if finished {
+            *slot = None;
+            drop(slot);
+            self.released.store(true, Ordering::SeqCst);
+            if let Some(queue) = self.queue.upgrade() {
+                queue.notify_released();
+            }
+        }
+    }
+
+    pub(crate) fn is_released(&self) -> bool {
+        self.released.load(Ordering::SeqCst)
+    }
+
+    fn schedule(self: Arc<Self>) {
+        if !self.scheduled.swap(true, Ordering::SeqCst)
+            && let Some(queue) = self.queue.upgrade()
+        {
+            queue.push(self);
+        }
+    }
+
+    pub(crate) fn cancel(self: &Arc<Self>) {
+        if !self.is_released() {
+            self.cancelled.store(true, Ordering::SeqCst);
+            // 重新调度，让工作线程尽快释放 future
+            self.clone().schedule();
+        }
+    }
+}
+
+impl Wake for Runnable {
+    fn wake(self: Arc<Self>) {
+        self.schedule();
+    }
+}
+
+struct TaskState<T> {
+    output: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+/// 在线程池中运行的任务，可以作为组件添加到实体上，由系统每帧调用 [`Task::check`] 查看结果。
+///
+/// 释放 `Task` 会取消任务，不需要结果时使用 [`Task::detach`]。
+#[must_use = "dropping a task cancels it, use `detach` to let it run in the background"]
+pub struct Task<T> {
+    runnable: Arc<Runnable>,
+    state: Arc<Mutex<TaskState<T>>>,
+    detached: bool,
+}
+
+impl<T: Send + 'static> Task<T> {
+    /// 创建任务并放入队列
+    ///
+    /// # Safety
+    ///
+    /// `future` 借用的数据必须在任务释放 future 之前（[`Runnable::is_released`]）保持有效
+    pub(crate) unsafe fn spawn_unchecked<'a, F>(queue: &Arc<Queue>, future: F) -> Self
+    where
+        F: Future<Output = T> + Send + 'a,
+    {
+        let state = Arc::new(Mutex::new(TaskState { output: None, waker: None }));
+        let output_state = state.clone();
+        let future: Pin<Box<dyn Future<Output = ()> + Send + 'a>> = Box::pin(async move {
+            let output = CatchUnwind(Box::pin(future)).await;
+            let mut state = output_state.lock().unwrap();
+            state.output = Some(output);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+        // SAFETY: 由调用者保证借用的数据活得足够久
+        let future: BoxedFuture = unsafe { std::mem::transmute(future) };
+
+        let runnable = Arc::new(Runnable {
+            future: Mutex::new(Some(future)),
+            scheduled: AtomicBool::new(false),
+            cancelled: AtomicBool::new(false),
+            released: AtomicBool::new(false),
+            queue: Arc::downgrade(queue),
+        });
+        runnable.clone().schedule();
+        Task {
+            runnable,
+            state,
+            detached: false,
+        }
+    }
+}
+
+impl<T> Task<T> {
+    /// 任务是否已经完成，完成后 [`Task::check`] 会返回结果
+    pub fn is_finished(&self) -> bool {
+        self.state.lock().unwrap().output.is_some()
+    }
+
+    /// 不阻塞地查看任务结果，完成后第一次调用返回 `Some`，之后都返回 `None`。
+    ///
+    /// 任务中发生的 panic 会在这里重新抛出。
+    pub fn check(&mut self) -> Option<T> {
+        let output = self.state.lock().unwrap().output.take()?;
+        Some(output.unwrap_or_else(|payload| panic::resume_unwind(payload)))
+    }
+
+    /// 让任务在后台继续运行，不再关心结果
+    pub fn detach(mut self) {
+        self.detached = true;
+    }
+
+    /// 取消任务，正在运行的 future 会在下一次让出时被释放
+    pub fn cancel(self) {}
+
+    pub(crate) fn runnable(&self) -> &Arc<Runnable> {
+        &self.runnable
+    }
+}
+
+impl<T> Drop for Task<T> {
+    fn drop(&mut self) {
+        if !self.detached {
+            self.runnable.cancel();
+        }
+    }
+}
+
+impl<T> Future for Task<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.lock().unwrap();
+        match state.output.take() {
+            Some(output) => Poll::Ready(output.unwrap_or_else(|payload| panic::resume_unwind(payload))),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T: 'static> Component for Task<T> {}
+
+// 把 future 中的 panic 作为结果返回，避免工作线程退出
+struct CatchUnwind<F>(Pin<Box<F>>);
+
+impl<F: Future> Future for CatchUnwind<F> {
+    type Output = thread::Result<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match panic::catch_unwind(AssertUnwindSafe(|| self.0.as_mut().poll(cx))) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(payload) => Poll::Ready(Err(payload)),
+        }
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// 阻塞当前线程直到 future 完成，通常只在测试或加载时使用，系统中请使用 [`Task::check`]
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use super::task::{Runnable, Task};
+
+// 所有工作线程共享的任务队列
+pub(crate) struct Queue {
+    jobs: Mutex<VecDeque<Arc<Runnable>>>,
+    available: Condvar,
+    // 有任务释放了 future，等待作用域结束的线程需要检查
+    released: Condvar,
+    shutdown: AtomicBool,
+}
+
+impl Queue {
+    pub(crate) fn push(&self, runnable: Arc<Runnable>) {
+        self.jobs.lock().unwrap().push_back(runnable);
+        self.available.notify_one();
+    }
+
+    pub(crate) fn notify_released(&self) {
+        let _jobs = self.jobs.lock().unwrap();
+        self.released.notify_all();
+    }
+
+    fn pop_blocking(&self) -> Option<Arc<Runnable>> {
+        let mut jobs = self.jobs.lock().unwrap();
+        loop {
+            if self.shutdown.load(Ordering::SeqCst) {
+                return None;
+            }
+            if let Some(runnable) = jobs.pop_front() {
+                return Some(runnable);
+            }
+            jobs = self.available.wait(jobs).unwrap();
+        }
+    }
+
+    // 等待任务释放 future，期间帮忙运行队列中的其他任务，这样在工作线程中嵌套使用作用域也不会死锁
+    fn wait_released(&self, runnable: &Runnable) {
+        loop {
+            let mut jobs = self.jobs.lock().unwrap();
+            if runnable.is_released() {
+                return;
+            }
+            match jobs.pop_front() {
+                Some(job) => {
+                    drop(jobs);
+                    job.run();
+                }
+                None => {
+                    // 超时只是兜底，正常情况下由 notify_released 唤醒
+                    let _ = self.released.wait_timeout(jobs, Duration::from_millis(10)).unwrap();
+                }
+            }
+        }
+    }
+}
+
+fn worker(queue: Arc<Queue>) {
+    while let Some(runnable) = queue.pop_blocking() {
+        runnable.run();
+    }
+}
+
+/// 配置并创建 [`TaskPool`]
+#[derive(Debug, Default, Clone)]
+pub struct TaskPoolBuilder {
+    num_threads: Option<usize>,
+    thread_name: Option<String>,
+    stack_size: Option<usize>,
+}
+
+impl TaskPoolBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 工作线程数量，默认为 CPU 逻辑核心数，至少为 1
+    pub fn num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = Some(num_threads);
+        self
+    }
+
+    /// 工作线程名称的前缀，线程名为 `<前缀> (<序号>)`
+    pub fn thread_name(mut self, thread_name: impl Into<String>) -> Self {
+        self.thread_name = Some(thread_name.into());
+        self
+    }
+
+    pub fn stack_size(mut self, stack_size: usize) -> Self {
+        self.stack_size = Some(stack_size);
+        self
+    }
+
+    pub fn build(self) -> TaskPool {
+        let num_threads = self
+            .num_threads
+            .unwrap_or_else(available_parallelism)
+            .max(1);
+        let queue = Arc::new(Queue {
+            jobs: Mutex::new(VecDeque::new()),
+            available: Condvar::new(),
+            released: Condvar::new(),
+            shutdown: AtomicBool::new(false),
+        });
+        let thread_name = self.thread_name.as_deref().unwrap_or("TaskPool");
+        let threads = (0..num_threads)
+            .map(|index| {
+                let mut builder = thread::Builder::new().name(format!("{} ({})", thread_name, index));
+                if let Some(stack_size) = self.stack_size {
+                    builder = builder.stack_size(stack_size);
+                }
+                let queue = queue.clone();
+                builder
+                    .spawn(move || worker(queue))
+                    .expect("Failed to spawn task pool thread")
+            })
+            .collect();
+        TaskPool {
+            inner: Arc::new(Inner { queue, threads }),
+        }
+    }
+}
+
+/// CPU 逻辑核心数，获取失败时为 1
+pub fn available_parallelism() -> usize {
+    thread::available_parallelism().map_or(1, |count| count.get())
+}
+
+struct Inner {
+    queue: Arc<Queue>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.queue.shutdown.store(true, Ordering::SeqCst);
+        {
+            let _jobs = self.queue.jobs.lock().unwrap();
+            self.queue.available.notify_all();
+        }
+        let current = thread::current().id();
+        for thread in self.threads.drain(..) {
+            // 最后一个引用可能在工作线程中释放，不能等待自己
+            if thread.thread().id() != current {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+/// 固定数量工作线程组成的线程池，克隆得到的是同一个线程池。
+///
+/// 最后一个克隆释放时停止所有线程，队列中未完成的任务会被丢弃。
+#[derive(Clone)]
+pub struct TaskPool {
+    inner: Arc<Inner>,
+}
+
+impl Default for TaskPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for TaskPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskPool")
+            .field("thread_num", &self.thread_num())
+            .finish()
+    }
+}
+
+impl TaskPool {
+    pub fn new() -> Self {
+        TaskPoolBuilder::new().build()
+    }
+
+    pub fn thread_num(&self) -> usize {
+        self.inner.threads.len()
+    }
+
+    /// 在线程池中运行 future，返回的 [`Task`] 释放时取消任务
+    pub fn spawn<T, F>(&self, future: F) -> Task<T>
+    where
+        T: Send + 'static,
+        F: Future<Output = T> + Send + 'static,
+    {
+        // SAFETY: future 不借用任何数据
+        unsafe { Task::spawn_unchecked(&self.inner.queue, future) }
+    }
+
+    /// 在作用域中并行运行可以借用局部数据的 future，等待全部完成后按创建顺序返回结果。
+    ///
+    /// ```ignore
+    /// let positions = vec![1.0, 2.0, 3.0, 4.0];
+    /// let sums = pool.scope(|scope| {
+    ///     for chunk in positions.chunks(2) {
+    ///         scope.spawn(async move { chunk.iter().sum::<f32>() });
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// 等待期间当前线程也会运行队列中的任务。任意任务 panic 时，在所有任务结束后重新抛出。
+    pub fn scope<'env, F, T>(&self, f: F) -> Vec<T>
+    where
+        F: for<'scope> FnOnce(&'scope Scope<'scope, 'env, T>),
+        T: Send + 'static,
+    {
+        let scope = Scope {
+            pool: self,
+            tasks: Mutex::new(Vec::new()),
+            scope: PhantomData,
+            env: PhantomData,
+        };
+        let result = panic::catch_unwind(AssertUnwindSafe(|| f(&scope)));
+        let mut tasks = Vec::new();
+        // 任务中也可以继续通过作用域创建任务，直到没有新任务为止
+        loop {
+            let spawned = std::mem::take(&mut *scope.tasks.lock().unwrap());
+            if spawned.is_empty() {
+                break;
+            }
+            if result.is_err() {
+                // 取消剩下的任务，但仍然要等待它们释放借用的数据
+                spawned.iter().for_each(|task| task.runnable().cancel());
+            }
+            for task in &spawned {
+                self.inner.queue.wait_released(task.runnable());
+            }
+            tasks.extend(spawned);
+        }
+        if let Err(payload) = result {
+            panic::resume_unwind(payload);
+        }
+        tasks
+            .into_iter()
+            .map(|mut task| task.check().expect("Scoped task was cancelled"))
+            .collect()
+    }
+}
+
+/// [`TaskPool::scope`] 中创建任务的作用域，`'scope` 内借用的数据可以在任务中使用
+pub struct Scope<'scope, 'env: 'scope, T> {
+    pool: &'scope TaskPool,
+    tasks: Mutex<Vec<Task<T>>>,
+    scope: PhantomData<&'scope mut &'scope ()>,
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+impl<'scope, T: Send + 'static> Scope<'scope, '_, T> {
+    pub fn spawn<F>(&self, future: F)
+    where
+        F: Future<Output = T> + Send + 'scope,
+    {
+        // SAFETY: scope 返回之前会等待所有任务释放 future
+        let task = unsafe { Task::spawn_unchecked(&self.pool.inner.queue, future) };
+        self.tasks.lock().unwrap().push(task);
+    }
+}
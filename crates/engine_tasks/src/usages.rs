@@ -0,0 +1,40 @@
+use std::ops::Deref;
+
+use super::task_pool::TaskPool;
+
+macro_rules! task_pool_resource {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $name(TaskPool);
+
+        impl $name {
+            pub fn new(pool: TaskPool) -> Self {
+                $name(pool)
+            }
+        }
+
+        impl Deref for $name {
+            type Target = TaskPool;
+
+            fn deref(&self) -> &TaskPool {
+                &self.0
+            }
+        }
+    };
+}
+
+task_pool_resource! {
+    /// 当前帧内必须完成的计算，例如并行处理大量实体，通常配合 [`TaskPool::scope`] 使用
+    ComputeTaskPool
+}
+
+task_pool_resource! {
+    /// 可以跨越多帧的计算，例如解码图片、生成地形，结果通过 [`Task::check`](crate::Task::check) 获取
+    AsyncComputeTaskPool
+}
+
+task_pool_resource! {
+    /// 读写文件和网络等大部分时间在等待的任务
+    IoTaskPool
+}
@@ -0,0 +1,60 @@
+use engine_ecs::prelude::*;
+
+/// 记录某种事件的资源，由 [`capture_events_system`] 在每帧的 `Last` 调度中填充。
+///
+/// 事件缓冲区两帧后就会丢弃事件，测试中用它检查之前任意一帧写入过的事件。
+pub struct CapturedEvents<E: BufferedEvent + Clone> {
+    events: Vec<E>,
+    // 下一个还没有记录的事件 ID
+    next_id: usize,
+}
+
+impl<E: BufferedEvent + Clone> Default for CapturedEvents<E> {
+    fn default() -> Self {
+        CapturedEvents {
+            events: Vec::new(),
+            next_id: 0,
+        }
+    }
+}
+
+impl<E: BufferedEvent + Clone> CapturedEvents<E> {
+    /// 按写入顺序返回记录的事件
+    pub fn events(&self) -> &[E] {
+        &self.events
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// 清空记录，之后只记录新写入的事件
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+/// 把新写入的事件复制到 [`CapturedEvents`]
+pub fn capture_events_system<E: BufferedEvent + Clone>(manager: &mut EntityManager) {
+    let Some(next_id) = manager.get_resource::<CapturedEvents<E>>().map(|captured| captured.next_id) else {
+        return;
+    };
+    let Some(events) = manager.get_resource::<Events<E>>() else {
+        return;
+    };
+    let new_events: Vec<(usize, E)> = events
+        .iter_with_id()
+        .filter(|(id, _)| id.id >= next_id)
+        .map(|(id, event)| (id.id, event.clone()))
+        .collect();
+    if let Some(captured) = manager.get_resource_mut::<CapturedEvents<E>>() {
+        for (id, event) in new_events {
+            captured.next_id = id + 1;
+            captured.events.push(event);
+        }
+    }
+}
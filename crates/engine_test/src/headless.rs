@@ -0,0 +1,52 @@
+use engine_app::prelude::*;
+use engine_ecs::prelude::*;
+use engine_window::prelude::*;
+
+/// 不创建真实窗口的窗口后端，用于测试和没有显示器的 CI。
+///
+/// 每帧结束时把新的 [`Window`] 实体记录为已打开，被删除的窗口记录为已关闭。
+#[derive(Default)]
+pub struct HeadlessWindowPlugin;
+
+impl Plugin for HeadlessWindowPlugin {
+    fn build(&self, app: &mut App) {
+        app.world_mut()
+            .register_component::<Window>()
+            .add_resource(HeadlessWindows::default());
+        app.add_systems(Last, headless_window_system);
+    }
+}
+
+/// 无头窗口后端中已经“打开”的窗口
+#[derive(Debug, Default)]
+pub struct HeadlessWindows {
+    open: Vec<usize>,
+}
+
+impl HeadlessWindows {
+    /// 已打开窗口的实体，按打开顺序排列
+    pub fn open_windows(&self) -> &[usize] {
+        &self.open
+    }
+
+    pub fn is_open(&self, entity_id: usize) -> bool {
+        self.open.contains(&entity_id)
+    }
+}
+
+/// 同步 [`HeadlessWindows`] 和拥有 [`Window`] 组件的实体
+pub fn headless_window_system(manager: &mut EntityManager) {
+    let Some(component_id) = manager.component_id::<Window>() else {
+        return;
+    };
+    let windows = manager.entity_ids_by_id(component_id).to_vec();
+    let Some(headless) = manager.get_resource_mut::<HeadlessWindows>() else {
+        return;
+    };
+    headless.open.retain(|entity_id| windows.contains(entity_id));
+    for entity_id in windows {
+        if !headless.open.contains(&entity_id) {
+            headless.open.push(entity_id);
+        }
+    }
+}
@@ -0,0 +1,9 @@
+mod capture;
+mod headless;
+mod test_app;
+
+pub mod prelude {
+    pub use super::capture::*;
+    pub use super::headless::*;
+    pub use super::test_app::*;
+}
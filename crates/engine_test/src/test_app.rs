@@ -0,0 +1,230 @@
+use std::time::Duration;
+
+use engine_app::prelude::*;
+use engine_ecs::prelude::*;
+use engine_time::prelude::*;
+
+use crate::capture::{capture_events_system, CapturedEvents};
+use crate::headless::HeadlessWindowPlugin;
+
+/// 默认的帧时间，即 60 FPS
+pub const DEFAULT_FRAME_TIME: Duration = Duration::from_nanos(16_666_667);
+
+/// 用于集成测试的应用。
+///
+/// 使用假时钟（每帧固定前进 [`DEFAULT_FRAME_TIME`]）和无头窗口后端，
+/// 由测试调用 [`TestApp::update`] 逐帧运行，不会打开窗口也不会阻塞：
+///
+/// ```ignore
+/// let mut app = TestApp::new();
+/// app.add_plugin(GamePlugin).update();
+/// assert_eq!(app.count::<Enemy>(), 3);
+/// ```
+pub struct TestApp {
+    app: App,
+}
+
+impl Default for TestApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestApp {
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.world_mut()
+            .add_resource(TimeUpdateStrategy::ManualDuration(DEFAULT_FRAME_TIME));
+        app.add_plugin(TimePlugin).add_plugin(HeadlessWindowPlugin);
+        TestApp { app }
+    }
+
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    pub fn add_plugin<P: Plugin + 'static>(&mut self, plugin: P) -> &mut Self {
+        self.app.add_plugin(plugin);
+        self
+    }
+
+    pub fn add_plugins<M>(&mut self, plugins: impl Plugins<M>) -> &mut Self {
+        self.app.add_plugins(plugins);
+        self
+    }
+
+    pub fn add_systems(&mut self, label: impl ScheduleLabel, system: impl IntoSystemConfig) -> &mut Self {
+        self.app.add_systems(label, system);
+        self
+    }
+
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> usize {
+        self.app.world_mut().spawn(bundle)
+    }
+
+    /// 运行一帧，插件构建失败或系统请求以错误退出时 panic
+    #[track_caller]
+    pub fn update(&mut self) -> &mut Self {
+        if let AppExit::Error(code) = self.app.update() {
+            panic!("App exited with error code {}", code);
+        }
+        self
+    }
+
+    /// 连续运行 `frames` 帧
+    #[track_caller]
+    pub fn update_frames(&mut self, frames: u32) -> &mut Self {
+        for _ in 0..frames {
+            self.update();
+        }
+        self
+    }
+
+    /// 修改之后每帧前进的时间
+    pub fn set_frame_time(&mut self, frame_time: Duration) -> &mut Self {
+        self.app
+            .world_mut()
+            .add_resource(TimeUpdateStrategy::ManualDuration(frame_time));
+        self
+    }
+
+    /// 写入事件（例如输入事件），下一次 [`TestApp::update`] 中的系统可以读到。
+    /// 事件类型没有注册时自动注册
+    pub fn send_event<E: BufferedEvent>(&mut self, event: E) -> &mut Self {
+        if self.app.world().get_resource::<Events<E>>().is_none() {
+            self.app.add_event::<E>();
+        }
+        self.app.world_mut().get_resource_mut::<Events<E>>().unwrap().write(event);
+        self
+    }
+
+    /// 开始记录事件 `E`，之后通过 [`TestApp::captured_events`] 读取
+    pub fn capture_events<E: BufferedEvent + Clone>(&mut self) -> &mut Self {
+        if self.app.world().get_resource::<CapturedEvents<E>>().is_some() {
+            return self;
+        }
+        if self.app.world().get_resource::<Events<E>>().is_none() {
+            self.app.add_event::<E>();
+        }
+        self.app.world_mut().add_resource(CapturedEvents::<E>::default());
+        self.app.add_systems(Last, capture_events_system::<E>);
+        self
+    }
+
+    /// 从开始记录以来写入的事件 `E`，没有调用过 [`TestApp::capture_events`] 时 panic
+    #[track_caller]
+    pub fn captured_events<E: BufferedEvent + Clone>(&self) -> &[E] {
+        match self.app.world().get_resource::<CapturedEvents<E>>() {
+            Some(captured) => captured.events(),
+            None => panic!("Events {} are not captured", std::any::type_name::<E>()),
+        }
+    }
+
+    /// 拥有组件 `T` 的实体数量
+    pub fn count<T: 'static + Component>(&self) -> usize {
+        let world = self.app.world();
+        match world.component_id::<T>() {
+            Some(component_id) => world.entity_manager().entity_ids_by_id(component_id).len(),
+            None => 0,
+        }
+    }
+
+    /// 断言实体拥有组件 `T` 并且满足 `predicate`
+    #[track_caller]
+    pub fn assert_component<T: 'static + Component + std::fmt::Debug>(
+        &self,
+        entity_id: usize,
+        predicate: impl FnOnce(&T) -> bool,
+    ) {
+        let Some(component) = self.app.world().get_component::<T>(entity_id) else {
+            panic!("Entity {} has no component {}", entity_id, std::any::type_name::<T>());
+        };
+        assert!(
+            predicate(component),
+            "Component {:?} of entity {} does not match the predicate",
+            component,
+            entity_id
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use engine_ecs::prelude::*;
+    use engine_time::prelude::*;
+    use engine_window::prelude::*;
+
+    use crate::prelude::*;
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct KeyPressed(char);
+    impl BufferedEvent for KeyPressed {}
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Jumped(usize);
+    impl BufferedEvent for Jumped {}
+
+    #[derive(Debug)]
+    struct Height(f32);
+    impl Component for Height {}
+
+    fn jump_system(manager: &mut EntityManager) {
+        let jumps = manager
+            .get_resource::<Events<KeyPressed>>()
+            .map_or(0, |events| events.iter().filter(|key| key.0 == ' ').count());
+        if jumps == 0 {
+            return;
+        }
+        let Some(component_id) = manager.component_id::<Height>() else {
+            return;
+        };
+        for entity_id in manager.entity_ids_by_id(component_id).to_vec() {
+            manager.borrow_component_mut::<Height>(entity_id).unwrap().0 += 1.0;
+            manager.write_event(Jumped(entity_id));
+        }
+    }
+
+    #[test]
+    fn test_step_frames_with_injected_events() {
+        let mut app = TestApp::new();
+        app.add_systems(Update, jump_system).capture_events::<Jumped>();
+        app.world_mut().register_component::<Height>();
+        let player = app.spawn(Height(0.0));
+
+        app.send_event(KeyPressed(' ')).update();
+        app.assert_component::<Height>(player, |height| height.0 == 1.0);
+        app.update_frames(3);
+
+        assert_eq!(app.count::<Height>(), 1);
+        assert_eq!(app.captured_events::<Jumped>(), [Jumped(player)]);
+    }
+
+    #[test]
+    fn test_fake_clock_and_headless_windows() {
+        let mut app = TestApp::new();
+        app.set_frame_time(Duration::from_millis(100));
+        let window = app.spawn(Window::default());
+        app.update_frames(3);
+
+        // 第一帧只记录起点
+        let time = app.world().get_resource::<Time<Real>>().unwrap();
+        assert_eq!(time.elapsed(), Duration::from_millis(200));
+        assert!(app.world().get_resource::<HeadlessWindows>().unwrap().is_open(window));
+        assert_eq!(app.count::<Window>(), 1);
+    }
+}
@@ -0,0 +1,89 @@
+use engine_app::prelude::*;
+use engine_ecs::prelude::*;
+
+use super::time::{Fixed, Time, Virtual};
+
+/// 把本帧的游戏时间累积到 `Time<Fixed>`，每满一个步长运行一次
+/// [`FixedPreUpdate`]、[`FixedUpdate`]、[`FixedPostUpdate`]。
+///
+/// 运行期间 `Time` 资源是固定步长的时间，结束后恢复为游戏时间。
+pub fn run_fixed_main_schedule(world: &mut World) {
+    let Some(delta) = world.get_resource::<Time<Virtual>>().map(|time| time.delta()) else {
+        return;
+    };
+    let Some(fixed) = world.get_resource_mut::<Time<Fixed>>() else {
+        return;
+    };
+    fixed.begin_frame(delta);
+
+    while world.get_resource_mut::<Time<Fixed>>().unwrap().expend_step() {
+        let generic = world.get_resource::<Time<Fixed>>().unwrap().as_generic();
+        world.add_resource(generic);
+        world.run_schedule(FixedPreUpdate);
+        world.run_schedule(FixedUpdate);
+        world.run_schedule(FixedPostUpdate);
+    }
+
+    let generic = world.get_resource::<Time<Virtual>>().unwrap().as_generic();
+    world.add_resource(generic);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    use engine_app::prelude::*;
+    use engine_ecs::prelude::*;
+
+    use crate::prelude::*;
+
+    fn fixed_app(frame: Duration, fixed: Time<Fixed>) -> (App, Rc<RefCell<Vec<Duration>>>) {
+        let steps = Rc::new(RefCell::new(Vec::new()));
+        let mut app = App::new();
+        app.world_mut()
+            .add_resource(TimeUpdateStrategy::ManualDuration(frame))
+            .add_resource(fixed);
+        app.add_plugin(TimePlugin);
+        let recorded = steps.clone();
+        app.add_systems(FixedUpdate, move |manager: &mut EntityManager| {
+            recorded.borrow_mut().push(manager.get_resource::<Time>().unwrap().delta());
+        });
+        (app, steps)
+    }
+
+    #[test]
+    fn test_fixed_update_accumulator() {
+        let (mut app, steps) = fixed_app(Duration::from_millis(50), Time::<Fixed>::from_hz(50.0));
+        // 第一帧没有增量
+        app.run();
+        assert!(steps.borrow().is_empty());
+
+        app.run_once();
+        assert_eq!(*steps.borrow(), vec![Duration::from_millis(20); 2]);
+        let fixed = app.world().get_resource::<Time<Fixed>>().unwrap();
+        assert_eq!(fixed.overstep(), Duration::from_millis(10));
+        assert_eq!(fixed.overstep_fraction(), 0.5);
+
+        app.run_once();
+        assert_eq!(steps.borrow().len(), 5);
+        // FixedUpdate 之外的 Time 仍然是游戏时间
+        assert_eq!(app.world().get_resource::<Time>().unwrap().delta(), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_fixed_update_max_steps_per_frame() {
+        let mut fixed = Time::<Fixed>::from_hz(100.0);
+        fixed.set_max_steps_per_frame(3);
+        let (mut app, steps) = fixed_app(Duration::from_millis(105), fixed);
+        app.run();
+        app.run_once();
+
+        let fixed = app.world().get_resource::<Time<Fixed>>().unwrap();
+        assert_eq!(steps.borrow().len(), 3);
+        assert_eq!(fixed.steps_this_frame(), 3);
+        // 多出的完整步长被丢弃，只保留不足一步的部分
+        assert_eq!(fixed.overstep(), Duration::from_millis(5));
+    }
+}
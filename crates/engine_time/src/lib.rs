@@ -0,0 +1,114 @@
+mod fixed;
+mod stopwatch;
+mod time;
+mod timer;
+
+use std::time::{Duration, Instant};
+
+use engine_app::prelude::*;
+use engine_ecs::prelude::*;
+
+pub mod prelude {
+    pub use super::fixed::*;
+    pub use super::stopwatch::*;
+    pub use super::time::*;
+    pub use super::timer::*;
+    pub use super::{TimePlugin, TimeUpdateStrategy};
+}
+
+use prelude::*;
+
+/// 决定每帧如何推进 `Time<Real>`，测试中可以用它替代系统时钟
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimeUpdateStrategy {
+    /// 使用 `Instant::now()`
+    #[default]
+    Automatic,
+    /// 使用指定的时刻
+    ManualInstant(Instant),
+    /// 每帧前进固定的时间
+    ManualDuration(Duration),
+}
+
+/// 添加 `Time`、`Time<Real>`、`Time<Virtual>`、`Time<Fixed>` 资源，
+/// 并在每帧的 [`First`] 调度开始时更新它们；[`FixedUpdate`] 在 [`RunFixedMainLoop`] 中按固定步长运行
+#[derive(Default)]
+pub struct TimePlugin;
+
+impl Plugin for TimePlugin {
+    fn build(&self, app: &mut App) {
+        let world = app.world_mut();
+        world
+            .add_resource(Time::<()>::default())
+            .add_resource(Time::<Real>::default())
+            .add_resource(Time::<Virtual>::default());
+        // 步长和策略可以在添加插件之前配置
+        if world.get_resource::<Time<Fixed>>().is_none() {
+            world.add_resource(Time::<Fixed>::default());
+        }
+        if world.get_resource::<TimeUpdateStrategy>().is_none() {
+            world.add_resource(TimeUpdateStrategy::default());
+        }
+        app.add_systems(First, time_system).add_systems(
+            RunFixedMainLoop,
+            SystemConfig::exclusive(run_fixed_main_schedule),
+        );
+    }
+}
+
+/// 更新真实时间，再按暂停、速度和增量上限推进游戏时间
+pub fn time_system(manager: &mut EntityManager) {
+    let strategy = manager
+        .get_resource::<TimeUpdateStrategy>()
+        .copied()
+        .unwrap_or_default();
+    let Some(real) = manager.get_resource_mut::<Time<Real>>() else {
+        return;
+    };
+    match strategy {
+        TimeUpdateStrategy::Automatic => real.update(),
+        TimeUpdateStrategy::ManualInstant(instant) => real.update_with_instant(instant),
+        TimeUpdateStrategy::ManualDuration(duration) => real.update_with_duration(duration),
+    }
+    let raw_delta = real.delta();
+
+    let Some(virt) = manager.get_resource_mut::<Time<Virtual>>() else {
+        return;
+    };
+    virt.advance_with_raw_delta(raw_delta);
+    let generic = virt.as_generic();
+    if let Some(time) = manager.get_resource_mut::<Time>() {
+        *time = generic;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use engine_app::prelude::*;
+
+    use super::prelude::*;
+
+    #[test]
+    fn test_time_plugin_with_manual_clock() {
+        let mut app = App::new();
+        app.world_mut()
+            .add_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+        app.add_plugin(TimePlugin);
+        app.run();
+
+        // 第一次更新只记录起点
+        let time = app.world().get_resource::<Time>().unwrap();
+        assert_eq!(time.delta(), Duration::ZERO);
+
+        app.run_once();
+        app.world_mut().get_resource_mut::<Time<Virtual>>().unwrap().set_relative_speed(0.5);
+        app.run_once();
+
+        let world = app.world();
+        assert_eq!(world.get_resource::<Time<Real>>().unwrap().elapsed(), Duration::from_millis(200));
+        assert_eq!(world.get_resource::<Time<Virtual>>().unwrap().elapsed(), Duration::from_millis(150));
+        assert_eq!(world.get_resource::<Time>().unwrap().delta(), Duration::from_millis(50));
+    }
+}
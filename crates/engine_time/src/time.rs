@@ -0,0 +1,444 @@
+use std::time::{Duration, Instant};
+
+/// 时钟。`T` 区分不同的时钟：
+///
+/// - `Time<Real>`：真实时间，不受暂停和速度影响
+/// - `Time<Virtual>`：游戏时间，可以暂停、加速、减速，单帧增量有上限
+/// - `Time<Fixed>`：固定步长的时间
+/// - `Time`（即 `Time<()>`）：系统中默认使用的时间，每帧从 `Time<Virtual>` 复制
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Time<T: Default = ()> {
+    context: T,
+    delta: Duration,
+    delta_secs: f32,
+    delta_secs_f64: f64,
+    elapsed: Duration,
+    elapsed_secs: f32,
+    elapsed_secs_f64: f64,
+}
+
+impl<T: Default> Time<T> {
+    pub fn new_with(context: T) -> Self {
+        Time {
+            context,
+            ..Default::default()
+        }
+    }
+
+    /// 前进 `delta`
+    pub fn advance_by(&mut self, delta: Duration) {
+        self.delta = delta;
+        self.delta_secs = delta.as_secs_f32();
+        self.delta_secs_f64 = delta.as_secs_f64();
+        self.elapsed += delta;
+        self.elapsed_secs = self.elapsed.as_secs_f32();
+        self.elapsed_secs_f64 = self.elapsed.as_secs_f64();
+    }
+
+    /// 前进到 `elapsed`，不能倒退
+    pub fn advance_to(&mut self, elapsed: Duration) {
+        assert!(elapsed >= self.elapsed, "tried to move time backwards");
+        self.advance_by(elapsed - self.elapsed);
+    }
+
+    /// 上一帧到这一帧经过的时间
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_secs(&self) -> f32 {
+        self.delta_secs
+    }
+
+    pub fn delta_secs_f64(&self) -> f64 {
+        self.delta_secs_f64
+    }
+
+    /// 从启动开始经过的总时间
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn elapsed_secs(&self) -> f32 {
+        self.elapsed_secs
+    }
+
+    pub fn elapsed_secs_f64(&self) -> f64 {
+        self.elapsed_secs_f64
+    }
+
+    pub fn context(&self) -> &T {
+        &self.context
+    }
+
+    pub fn context_mut(&mut self) -> &mut T {
+        &mut self.context
+    }
+
+    /// 去掉时钟类型，得到通用的 `Time`
+    pub fn as_generic(&self) -> Time<()> {
+        Time {
+            context: (),
+            delta: self.delta,
+            delta_secs: self.delta_secs,
+            delta_secs_f64: self.delta_secs_f64,
+            elapsed: self.elapsed,
+            elapsed_secs: self.elapsed_secs,
+            elapsed_secs_f64: self.elapsed_secs_f64,
+        }
+    }
+}
+
+/// 真实时间
+#[derive(Clone, Copy, Debug)]
+pub struct Real {
+    startup: Instant,
+    first_update: Option<Instant>,
+    last_update: Option<Instant>,
+}
+
+impl Default for Real {
+    fn default() -> Self {
+        Real {
+            startup: Instant::now(),
+            first_update: None,
+            last_update: None,
+        }
+    }
+}
+
+impl Time<Real> {
+    pub fn new(startup: Instant) -> Self {
+        Self::new_with(Real {
+            startup,
+            first_update: None,
+            last_update: None,
+        })
+    }
+
+    /// 以 `Instant::now()` 更新
+    pub fn update(&mut self) {
+        self.update_with_instant(Instant::now());
+    }
+
+    /// 以指定的时刻更新，第一次更新的增量为 0
+    pub fn update_with_instant(&mut self, instant: Instant) {
+        let Some(last_update) = self.context().last_update else {
+            let context = self.context_mut();
+            context.first_update = Some(instant);
+            context.last_update = Some(instant);
+            return;
+        };
+        let delta = instant.saturating_duration_since(last_update);
+        self.advance_by(delta);
+        self.context_mut().last_update = Some(instant);
+    }
+
+    /// 以指定的增量更新，用于测试
+    pub fn update_with_duration(&mut self, duration: Duration) {
+        let last_update = self.context().last_update.unwrap_or(self.context().startup);
+        self.update_with_instant(last_update + duration);
+    }
+
+    pub fn startup(&self) -> Instant {
+        self.context().startup
+    }
+
+    pub fn first_update(&self) -> Option<Instant> {
+        self.context().first_update
+    }
+
+    pub fn last_update(&self) -> Option<Instant> {
+        self.context().last_update
+    }
+}
+
+/// 游戏时间
+#[derive(Clone, Copy, Debug)]
+pub struct Virtual {
+    max_delta: Duration,
+    paused: bool,
+    relative_speed: f64,
+    effective_speed: f64,
+}
+
+impl Virtual {
+    /// 单帧增量的默认上限，例如窗口被拖动、断点调试后恢复时避免时间跳跃过大
+    pub const DEFAULT_MAX_DELTA: Duration = Duration::from_millis(250);
+}
+
+impl Default for Virtual {
+    fn default() -> Self {
+        Virtual {
+            max_delta: Self::DEFAULT_MAX_DELTA,
+            paused: false,
+            relative_speed: 1.0,
+            effective_speed: 1.0,
+        }
+    }
+}
+
+impl Time<Virtual> {
+    pub fn from_max_delta(max_delta: Duration) -> Self {
+        let mut time = Self::default();
+        time.set_max_delta(max_delta);
+        time
+    }
+
+    pub fn max_delta(&self) -> Duration {
+        self.context().max_delta
+    }
+
+    pub fn set_max_delta(&mut self, max_delta: Duration) {
+        assert_ne!(max_delta, Duration::ZERO, "tried to set max delta to zero");
+        self.context_mut().max_delta = max_delta;
+    }
+
+    /// 相对于真实时间的速度，例如 2.0 为两倍速
+    pub fn relative_speed(&self) -> f32 {
+        self.context().relative_speed as f32
+    }
+
+    pub fn relative_speed_f64(&self) -> f64 {
+        self.context().relative_speed
+    }
+
+    /// 上一次更新实际使用的速度，暂停时为 0
+    pub fn effective_speed(&self) -> f32 {
+        self.context().effective_speed as f32
+    }
+
+    pub fn set_relative_speed(&mut self, ratio: f32) {
+        self.set_relative_speed_f64(ratio as f64);
+    }
+
+    pub fn set_relative_speed_f64(&mut self, ratio: f64) {
+        assert!(ratio.is_finite(), "tried to go infinitely fast");
+        assert!(ratio >= 0.0, "tried to go back in time");
+        self.context_mut().relative_speed = ratio;
+    }
+
+    pub fn pause(&mut self) {
+        self.context_mut().paused = true;
+    }
+
+    pub fn unpause(&mut self) {
+        self.context_mut().paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.context().paused
+    }
+
+    /// 上一次更新时是否处于暂停状态
+    pub fn was_paused(&self) -> bool {
+        self.context().effective_speed == 0.0
+    }
+
+    /// 以真实时间的增量推进游戏时间
+    pub fn advance_with_raw_delta(&mut self, raw_delta: Duration) {
+        let max_delta = self.context().max_delta;
+        let clamped_delta = raw_delta.min(max_delta);
+        let effective_speed = if self.context().paused {
+            0.0
+        } else {
+            self.context().relative_speed
+        };
+        let delta = if effective_speed != 1.0 {
+            clamped_delta.mul_f64(effective_speed)
+        } else {
+            // 避免浮点运算带来的误差
+            clamped_delta
+        };
+        self.context_mut().effective_speed = effective_speed;
+        self.advance_by(delta);
+    }
+}
+
+/// 固定步长的时间，由 `FixedUpdate` 推进
+#[derive(Clone, Copy, Debug)]
+pub struct Fixed {
+    timestep: Duration,
+    overstep: Duration,
+    max_steps_per_frame: u32,
+    steps_this_frame: u32,
+}
+
+impl Fixed {
+    /// 默认每秒 64 次
+    pub const DEFAULT_TIMESTEP: Duration = Duration::from_micros(15625);
+    /// 默认每帧最多运行的步数，足够消耗 `Virtual::DEFAULT_MAX_DELTA`
+    pub const DEFAULT_MAX_STEPS_PER_FRAME: u32 = 16;
+}
+
+impl Default for Fixed {
+    fn default() -> Self {
+        Fixed {
+            timestep: Self::DEFAULT_TIMESTEP,
+            overstep: Duration::ZERO,
+            max_steps_per_frame: Self::DEFAULT_MAX_STEPS_PER_FRAME,
+            steps_this_frame: 0,
+        }
+    }
+}
+
+impl Time<Fixed> {
+    pub fn from_duration(timestep: Duration) -> Self {
+        let mut time = Self::default();
+        time.set_timestep(timestep);
+        time
+    }
+
+    pub fn from_seconds(seconds: f64) -> Self {
+        let mut time = Self::default();
+        time.set_timestep_seconds(seconds);
+        time
+    }
+
+    /// 每秒运行 `hz` 次
+    pub fn from_hz(hz: f64) -> Self {
+        let mut time = Self::default();
+        time.set_timestep_hz(hz);
+        time
+    }
+
+    pub fn timestep(&self) -> Duration {
+        self.context().timestep
+    }
+
+    pub fn set_timestep(&mut self, timestep: Duration) {
+        assert_ne!(timestep, Duration::ZERO, "attempted to set fixed timestep to zero");
+        self.context_mut().timestep = timestep;
+    }
+
+    pub fn set_timestep_seconds(&mut self, seconds: f64) {
+        assert!(seconds.is_sign_positive(), "seconds less than or equal to zero");
+        assert!(seconds.is_finite(), "seconds is infinite");
+        self.set_timestep(Duration::from_secs_f64(seconds));
+    }
+
+    pub fn set_timestep_hz(&mut self, hz: f64) {
+        assert!(hz.is_sign_positive(), "Hz less than or equal to zero");
+        assert!(hz.is_finite(), "Hz is infinite");
+        self.set_timestep_seconds(1.0 / hz);
+    }
+
+    /// 累积但还没有被固定步长消耗的时间
+    pub fn overstep(&self) -> Duration {
+        self.context().overstep
+    }
+
+    /// 累积的时间占一个步长的比例，用于渲染插值
+    pub fn overstep_fraction(&self) -> f32 {
+        self.context().overstep.as_secs_f32() / self.context().timestep.as_secs_f32()
+    }
+
+    pub fn overstep_fraction_f64(&self) -> f64 {
+        self.context().overstep.as_secs_f64() / self.context().timestep.as_secs_f64()
+    }
+
+    /// 把游戏时间的增量累积起来
+    pub fn accumulate(&mut self, delta: Duration) {
+        self.context_mut().overstep += delta;
+    }
+
+    /// 消耗一个步长并推进时间，累积的时间不足一个步长时返回 `false`
+    pub fn expend(&mut self) -> bool {
+        let timestep = self.timestep();
+        let Some(overstep) = self.context().overstep.checked_sub(timestep) else {
+            return false;
+        };
+        self.context_mut().overstep = overstep;
+        self.advance_by(timestep);
+        true
+    }
+
+    /// 丢弃累积的时间
+    pub fn discard_overstep(&mut self, discard: Duration) {
+        let overstep = self.context().overstep.saturating_sub(discard);
+        self.context_mut().overstep = overstep;
+    }
+
+    /// 每帧最多运行的步数。单帧的耗时超过一个步长时，固定步长会越积越多（spiral of death），
+    /// 达到上限后多出的完整步长会被丢弃，游戏时间变慢而不是卡死
+    pub fn max_steps_per_frame(&self) -> u32 {
+        self.context().max_steps_per_frame
+    }
+
+    pub fn set_max_steps_per_frame(&mut self, max_steps: u32) {
+        assert_ne!(max_steps, 0, "attempted to set max steps per frame to zero");
+        self.context_mut().max_steps_per_frame = max_steps;
+    }
+
+    /// 本帧已经运行的步数
+    pub fn steps_this_frame(&self) -> u32 {
+        self.context().steps_this_frame
+    }
+
+    // 开始新的一帧，累积游戏时间的增量
+    pub(crate) fn begin_frame(&mut self, delta: Duration) {
+        self.accumulate(delta);
+        self.context_mut().steps_this_frame = 0;
+    }
+
+    // 消耗一个步长，达到每帧上限时返回 `false` 并丢弃多余的完整步长
+    pub(crate) fn expend_step(&mut self) -> bool {
+        if self.context().steps_this_frame >= self.context().max_steps_per_frame {
+            let timestep = self.timestep().as_nanos();
+            let overstep = self.overstep().as_nanos();
+            self.discard_overstep(Duration::from_nanos((overstep - overstep % timestep) as u64));
+            return false;
+        }
+        if !self.expend() {
+            return false;
+        }
+        self.context_mut().steps_this_frame += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    #[test]
+    fn test_virtual_time_pause_speed_and_clamp() {
+        let mut time = Time::<Virtual>::default();
+        time.advance_with_raw_delta(Duration::from_millis(100));
+        assert_eq!(time.delta(), Duration::from_millis(100));
+
+        time.set_relative_speed(2.0);
+        time.advance_with_raw_delta(Duration::from_millis(100));
+        assert_eq!(time.delta(), Duration::from_millis(200));
+
+        // 增量先被限制为 max_delta，再乘以速度
+        time.advance_with_raw_delta(Duration::from_secs(10));
+        assert_eq!(time.delta(), Duration::from_millis(500));
+
+        time.pause();
+        time.advance_with_raw_delta(Duration::from_millis(100));
+        assert_eq!(time.delta(), Duration::ZERO);
+        assert!(time.was_paused());
+        assert_eq!(time.elapsed(), Duration::from_millis(800));
+    }
+
+    #[test]
+    fn test_real_and_fixed_time() {
+        let startup = Instant::now();
+        let mut real = Time::<Real>::new(startup);
+        real.update_with_instant(startup + Duration::from_millis(5));
+        assert_eq!(real.delta(), Duration::ZERO);
+        real.update_with_instant(startup + Duration::from_millis(25));
+        assert_eq!(real.delta(), Duration::from_millis(20));
+
+        let mut fixed = Time::<Fixed>::from_hz(50.0);
+        fixed.accumulate(Duration::from_millis(50));
+        assert!(fixed.expend());
+        assert!(fixed.expend());
+        assert!(!fixed.expend());
+        assert_eq!(fixed.elapsed(), Duration::from_millis(40));
+        assert_eq!(fixed.overstep_fraction(), 0.5);
+    }
+}
@@ -0,0 +1,220 @@
+use std::time::Duration;
+
+use super::stopwatch::Stopwatch;
+
+/// 计时器结束后的行为
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TimerMode {
+    /// 只触发一次，之后保持结束状态直到 [`Timer::reset`]
+    #[default]
+    Once,
+    /// 每经过一个周期触发一次
+    Repeating,
+}
+
+/// 计时器，例如每隔 1.5 秒生成一个敌人。
+///
+/// ```ignore
+/// struct EnemySpawner {
+///     timer: Timer,
+/// }
+///
+/// spawner.timer.tick(time.delta());
+/// for _ in 0..spawner.timer.times_finished_this_tick() {
+///     spawn_enemy(manager);
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Timer {
+    stopwatch: Stopwatch,
+    duration: Duration,
+    mode: TimerMode,
+    finished: bool,
+    times_finished_this_tick: u32,
+}
+
+impl Timer {
+    pub fn new(duration: Duration, mode: TimerMode) -> Self {
+        Timer {
+            duration,
+            mode,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_seconds(duration: f32, mode: TimerMode) -> Self {
+        Self::new(Duration::from_secs_f32(duration), mode)
+    }
+
+    /// 前进 `delta`。重复计时器的增量大于一个周期时会触发多次，
+    /// 次数见 [`Timer::times_finished_this_tick`]。
+    pub fn tick(&mut self, delta: Duration) -> &Self {
+        if self.paused() {
+            self.times_finished_this_tick = 0;
+            if self.mode == TimerMode::Repeating {
+                self.finished = false;
+            }
+            return self;
+        }
+
+        if self.mode != TimerMode::Repeating && self.finished() {
+            self.times_finished_this_tick = 0;
+            return self;
+        }
+
+        self.stopwatch.tick(delta);
+        self.finished = self.elapsed() >= self.duration;
+
+        if self.finished() {
+            if self.mode == TimerMode::Repeating {
+                self.times_finished_this_tick = match self.duration.as_nanos() {
+                    0 => u32::MAX,
+                    duration => (self.elapsed().as_nanos() / duration).min(u32::MAX as u128) as u32,
+                };
+                let remainder = match self.duration.as_nanos() {
+                    0 => Duration::ZERO,
+                    duration => {
+                        Duration::from_nanos((self.elapsed().as_nanos() % duration) as u64)
+                    }
+                };
+                self.stopwatch.set_elapsed(remainder);
+            } else {
+                self.times_finished_this_tick = 1;
+                self.stopwatch.set_elapsed(self.duration);
+            }
+        } else {
+            self.times_finished_this_tick = 0;
+        }
+        self
+    }
+
+    /// 计时器已经结束。重复计时器只在触发的那一次 tick 后为 `true`
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// 计时器在最近一次 tick 中结束
+    pub fn just_finished(&self) -> bool {
+        self.times_finished_this_tick > 0
+    }
+
+    pub fn times_finished_this_tick(&self) -> u32 {
+        self.times_finished_this_tick
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.stopwatch.elapsed()
+    }
+
+    pub fn elapsed_secs(&self) -> f32 {
+        self.stopwatch.elapsed_secs()
+    }
+
+    pub fn set_elapsed(&mut self, elapsed: Duration) {
+        self.stopwatch.set_elapsed(elapsed);
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+    }
+
+    pub fn mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: TimerMode) {
+        // 已经结束的单次计时器改为重复时，重新开始计时
+        if self.mode != TimerMode::Repeating && mode == TimerMode::Repeating && self.finished {
+            self.stopwatch.reset();
+            self.finished = self.just_finished();
+        }
+        self.mode = mode;
+    }
+
+    pub fn pause(&mut self) {
+        self.stopwatch.pause();
+    }
+
+    pub fn unpause(&mut self) {
+        self.stopwatch.unpause();
+    }
+
+    pub fn paused(&self) -> bool {
+        self.stopwatch.is_paused()
+    }
+
+    pub fn reset(&mut self) {
+        self.stopwatch.reset();
+        self.finished = false;
+        self.times_finished_this_tick = 0;
+    }
+
+    /// 剩余时间
+    pub fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.elapsed())
+    }
+
+    pub fn remaining_secs(&self) -> f32 {
+        self.remaining().as_secs_f32()
+    }
+
+    /// 已经过的时间占周期的比例，范围 0.0 到 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.duration == Duration::ZERO {
+            1.0
+        } else {
+            self.elapsed().as_secs_f32() / self.duration.as_secs_f32()
+        }
+    }
+
+    pub fn fraction_remaining(&self) -> f32 {
+        1.0 - self.fraction()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_once_timer() {
+        let mut timer = Timer::from_seconds(1.0, TimerMode::Once);
+        timer.tick(Duration::from_millis(600));
+        assert!(!timer.finished());
+        assert_eq!(timer.fraction(), 0.6);
+
+        timer.tick(Duration::from_millis(600));
+        assert!(timer.finished());
+        assert!(timer.just_finished());
+        assert_eq!(timer.elapsed(), Duration::from_secs(1));
+
+        timer.tick(Duration::from_millis(600));
+        assert!(timer.finished());
+        assert!(!timer.just_finished());
+
+        timer.reset();
+        assert!(!timer.finished());
+    }
+
+    #[test]
+    fn test_repeating_timer() {
+        let mut timer = Timer::from_seconds(0.5, TimerMode::Repeating);
+        timer.tick(Duration::from_millis(1200));
+        assert!(timer.just_finished());
+        assert_eq!(timer.times_finished_this_tick(), 2);
+        assert_eq!(timer.elapsed(), Duration::from_millis(200));
+
+        timer.tick(Duration::from_millis(100));
+        assert!(!timer.finished());
+
+        timer.pause();
+        timer.tick(Duration::from_secs(1));
+        assert_eq!(timer.elapsed(), Duration::from_millis(300));
+    }
+}
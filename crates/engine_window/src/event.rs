@@ -1,4 +1,9 @@
+use std::path::PathBuf;
+
 use engine_ecs::prelude::*;
+use engine_math::prelude::*;
+
+use crate::window::WindowTheme;
 
 #[derive(BufferedEvent, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AppLifecycle {
@@ -16,10 +21,8 @@ pub enum AppLifecycle {
     WillResume,
 }
 
-pub struct EventWriter<E: BufferedEvent> {
-    events: Events<E>,
-}
-
+/// 窗口的逻辑尺寸发生变化
+#[derive(BufferedEvent, Debug, Clone, PartialEq)]
 pub struct WindowResized {
     /// Window that has changed.
     pub window: Entity,
@@ -29,7 +32,8 @@ pub struct WindowResized {
     pub height: f32,
 }
 
-
+/// 系统提供的缩放比例发生变化，无论是否设置了覆盖值都会发送
+#[derive(BufferedEvent, Debug, Clone, PartialEq)]
 pub struct WindowBackendScaleFactorChanged {
     /// Window that had its scale factor changed by the backend.
     pub window: Entity,
@@ -37,10 +41,80 @@ pub struct WindowBackendScaleFactorChanged {
     pub scale_factor: f64,
 }
 
-
+/// 窗口实际使用的缩放比例发生变化，设置了覆盖值时不会发送
+#[derive(BufferedEvent, Debug, Clone, PartialEq)]
 pub struct WindowScaleFactorChanged {
     /// Window that had its scale factor changed.
     pub window: Entity,
     /// The new scale factor.
     pub scale_factor: f64,
 }
+
+/// 窗口被移动，位置是左上角的物理坐标
+#[derive(BufferedEvent, Debug, Clone, PartialEq, Eq)]
+pub struct WindowMoved {
+    pub window: Entity,
+    pub position: IVec2,
+}
+
+/// 窗口获得或失去焦点
+#[derive(BufferedEvent, Debug, Clone, PartialEq, Eq)]
+pub struct WindowFocused {
+    pub window: Entity,
+    pub focused: bool,
+}
+
+/// 用户请求关闭窗口，例如点击了关闭按钮
+#[derive(BufferedEvent, Debug, Clone, PartialEq, Eq)]
+pub struct WindowCloseRequested {
+    pub window: Entity,
+}
+
+/// 窗口实体被删除后，窗口后端关闭了对应的系统窗口
+#[derive(BufferedEvent, Debug, Clone, PartialEq, Eq)]
+pub struct WindowClosed {
+    pub window: Entity,
+}
+
+/// 系统已经销毁了窗口
+#[derive(BufferedEvent, Debug, Clone, PartialEq, Eq)]
+pub struct WindowDestroyed {
+    pub window: Entity,
+}
+
+/// 窗口被完全遮挡或重新可见，被遮挡时可以停止渲染
+#[derive(BufferedEvent, Debug, Clone, PartialEq, Eq)]
+pub struct WindowOccluded {
+    pub window: Entity,
+    pub occluded: bool,
+}
+
+/// 系统主题发生变化
+#[derive(BufferedEvent, Debug, Clone, PartialEq, Eq)]
+pub struct WindowThemeChanged {
+    pub window: Entity,
+    pub theme: WindowTheme,
+}
+
+/// 光标进入窗口
+#[derive(BufferedEvent, Debug, Clone, PartialEq, Eq)]
+pub struct CursorEntered {
+    pub window: Entity,
+}
+
+/// 光标离开窗口
+#[derive(BufferedEvent, Debug, Clone, PartialEq, Eq)]
+pub struct CursorLeft {
+    pub window: Entity,
+}
+
+/// 文件拖放事件，拖动多个文件时每个文件一个事件
+#[derive(BufferedEvent, Debug, Clone, PartialEq, Eq)]
+pub enum FileDragAndDrop {
+    /// 文件被放到窗口中
+    DroppedFile { window: Entity, path_buf: PathBuf },
+    /// 文件被拖到窗口上方
+    HoveredFile { window: Entity, path_buf: PathBuf },
+    /// 拖动的文件离开了窗口或拖放被取消
+    HoveredFileCanceled { window: Entity },
+}
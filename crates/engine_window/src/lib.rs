@@ -3,11 +3,15 @@ extern crate alloc;
 mod raw_handle;
 mod window;
 mod event;
+mod plugin;
+mod system;
 
 pub mod prelude {
     pub use crate::{
         event::*,
         window::*,
         raw_handle::*,
+        plugin::*,
+        system::*,
     };
 }
@@ -0,0 +1,252 @@
+use engine_app::prelude::*;
+use engine_config::prelude::*;
+
+use crate::event::*;
+use crate::system::{close_when_requested, exit_on_all_closed, exit_on_primary_closed, CloseRequestCursor};
+use crate::window::{PrimaryWindow, Window, WindowResolution};
+
+settings! {
+    /// 主窗口的配置，对应配置文件中的 `[window]`
+    pub struct WindowSettings("window") {
+        pub title: String = "App".to_string(),
+        /// 物理宽度
+        pub width: u32 = 1280,
+        /// 物理高度
+        pub height: u32 = 720,
+        pub resizable: bool = true,
+        pub decorations: bool = true,
+        pub transparent: bool = false,
+        pub visible: bool = true,
+    }
+}
+
+impl WindowSettings {
+    pub fn to_window(&self) -> Window {
+        Window {
+            title: self.title.clone(),
+            resolution: WindowResolution::new(self.width, self.height),
+            resizable: self.resizable,
+            decorations: self.decorations,
+            transparent: self.transparent,
+            visible: self.visible,
+            ..Default::default()
+        }
+    }
+}
+
+/// 窗口关闭时何时退出 App
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCondition {
+    /// 主窗口关闭后退出，其他窗口一起关闭
+    OnPrimaryClosed,
+    /// 所有窗口都关闭后退出
+    #[default]
+    OnAllClosed,
+    /// 不因为窗口关闭而退出，适合没有窗口的 App
+    DontExit,
+}
+
+/// 注册 [`Window`] 组件和 [`WindowSettings`] 配置，并按配置创建主窗口
+#[derive(Debug, Clone)]
+pub struct WindowPlugin {
+    /// 主窗口的默认配置，会被配置文件、环境变量和命令行参数覆盖
+    pub primary_window: WindowSettings,
+    /// 是否创建主窗口
+    pub spawn_primary_window: bool,
+    pub exit_condition: ExitCondition,
+    /// 收到 [`WindowCloseRequested`] 时是否直接删除窗口实体。
+    ///
+    /// 设为 `false` 可以拦截关闭请求，例如先询问是否保存，确认后由系统自己删除窗口实体。
+    pub close_when_requested: bool,
+}
+
+impl Default for WindowPlugin {
+    fn default() -> Self {
+        WindowPlugin {
+            primary_window: WindowSettings::default(),
+            spawn_primary_window: true,
+            exit_condition: ExitCondition::default(),
+            close_when_requested: true,
+        }
+    }
+}
+
+impl Plugin for WindowPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_settings(self.primary_window.clone());
+        app.world_mut()
+            .register_component::<Window>()
+            .register_component::<PrimaryWindow>();
+        app.add_event::<AppLifecycle>()
+            .add_event::<WindowResized>()
+            .add_event::<WindowBackendScaleFactorChanged>()
+            .add_event::<WindowScaleFactorChanged>()
+            .add_event::<WindowMoved>()
+            .add_event::<WindowFocused>()
+            .add_event::<WindowCloseRequested>()
+            .add_event::<WindowClosed>()
+            .add_event::<WindowDestroyed>()
+            .add_event::<WindowOccluded>()
+            .add_event::<WindowThemeChanged>()
+            .add_event::<CursorEntered>()
+            .add_event::<CursorLeft>()
+            .add_event::<FileDragAndDrop>();
+
+        match self.exit_condition {
+            ExitCondition::OnPrimaryClosed => {
+                app.add_systems(PostUpdate, exit_on_primary_closed);
+            }
+            ExitCondition::OnAllClosed => {
+                app.add_systems(PostUpdate, exit_on_all_closed);
+            }
+            ExitCondition::DontExit => {}
+        }
+        if self.close_when_requested {
+            app.world_mut().add_resource(CloseRequestCursor::default());
+            app.add_systems(Update, close_when_requested);
+        }
+    }
+
+    // 配置在所有插件构建之后才确定
+    fn finish(&self, app: &mut App) {
+        if self.spawn_primary_window {
+            let world = app.world_mut();
+            let window = world.get_resource::<WindowSettings>().unwrap().to_window();
+            world.spawn((window, PrimaryWindow));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use engine_ecs::prelude::*;
+
+    use super::*;
+    use crate::window::WindowRef;
+
+    #[test]
+    fn test_primary_window_from_config() {
+        let mut app = App::new();
+        app.add_plugin(WindowPlugin {
+            primary_window: WindowSettings {
+                title: "Game".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        app.add_plugin(ConfigPlugin {
+            files: Vec::new(),
+            env_prefix: None,
+            args: Some(vec!["--set".to_string(), "window.width=800".to_string()]),
+            strict: true,
+        });
+        app.build_plugins().unwrap();
+        app.finish();
+
+        let manager = app.world().entity_manager();
+        let windows = manager.entity_ids_by_id(manager.component_id::<Window>().unwrap());
+        assert_eq!(windows.len(), 1);
+        let window = manager.borrow_component::<Window>(windows[0]).unwrap();
+        assert_eq!(window.title, "Game");
+        assert_eq!(window.physical_width(), 800);
+        assert_eq!(window.physical_height(), 720);
+        assert!(manager.borrow_component::<PrimaryWindow>(windows[0]).is_some());
+    }
+
+    #[test]
+    fn test_window_events_registered() {
+        let mut app = App::new();
+        app.add_plugin(WindowPlugin {
+            spawn_primary_window: false,
+            ..Default::default()
+        });
+        app.build_plugins().unwrap();
+
+        let window = Entity::from_raw(0);
+        let manager = app.world_mut().entity_manager_mut();
+        assert!(manager.write_event(WindowCloseRequested { window }).is_some());
+        assert!(manager.write_event(FileDragAndDrop::HoveredFileCanceled { window }).is_some());
+    }
+
+    fn window_app(plugin: WindowPlugin) -> (App, usize) {
+        let mut app = App::new();
+        app.add_plugin(plugin);
+        app.add_plugin(ConfigPlugin {
+            files: Vec::new(),
+            env_prefix: None,
+            args: Some(Vec::new()),
+            strict: true,
+        });
+        app.build_plugins().unwrap();
+        app.finish();
+        let primary = WindowRef::Primary
+            .get_entity(app.world().entity_manager())
+            .unwrap()
+            .to_raw();
+        (app, primary)
+    }
+
+    fn request_close(app: &mut App, entity_id: usize) {
+        let window = Entity::from_raw(entity_id);
+        app.world_mut().entity_manager_mut().write_event(WindowCloseRequested { window });
+    }
+
+    #[test]
+    fn test_exit_on_all_closed() {
+        let (mut app, primary) = window_app(WindowPlugin::default());
+        let secondary = app.world_mut().spawn(Window::default());
+
+        request_close(&mut app, primary);
+        app.run_once();
+        assert!(!app.world().has_component::<Window>(primary));
+        assert!(WindowRef::Primary.get_entity(app.world().entity_manager()).is_none());
+        assert_eq!(app.should_exit(), None);
+
+        request_close(&mut app, secondary);
+        app.run_once();
+        assert_eq!(app.should_exit(), Some(AppExit::Success));
+    }
+
+    #[test]
+    fn test_close_request_written_during_frame() {
+        let (mut app, primary) = window_app(WindowPlugin::default());
+        let secondary = app.world_mut().spawn(Window::default());
+        let mut pending = Some(Entity::from_raw(secondary));
+        app.add_systems(PreUpdate, move |manager: &mut EntityManager| {
+            if let Some(window) = pending.take() {
+                manager.write_event(WindowCloseRequested { window });
+            }
+        });
+
+        app.run_once();
+        assert!(!app.world().has_component::<Window>(secondary));
+
+        // 请求在下一帧仍留在双缓冲中，不会再关闭复用同一 ID 的新窗口
+        let reused = app.world_mut().spawn(Window::default());
+        assert_eq!(reused, secondary);
+        app.run_once();
+        assert!(app.world().has_component::<Window>(reused));
+        assert!(app.world().has_component::<Window>(primary));
+    }
+
+    #[test]
+    fn test_intercept_close_request() {
+        let (mut app, primary) = window_app(WindowPlugin {
+            exit_condition: ExitCondition::OnPrimaryClosed,
+            close_when_requested: false,
+            ..Default::default()
+        });
+        app.world_mut().spawn(Window::default());
+
+        // 关闭请求被拦截，窗口保持打开
+        request_close(&mut app, primary);
+        app.run_once();
+        assert!(app.world().has_component::<Window>(primary));
+        assert_eq!(app.should_exit(), None);
+
+        // 确认后由用户删除主窗口，其他窗口还在也会退出
+        app.world_mut().remove_entity(primary);
+        app.run_once();
+        assert_eq!(app.should_exit(), Some(AppExit::Success));
+    }
+}
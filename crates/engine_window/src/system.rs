@@ -0,0 +1,54 @@
+use engine_app::prelude::*;
+use engine_ecs::prelude::*;
+
+use crate::event::WindowCloseRequested;
+use crate::window::{Window, WindowRef};
+
+/// 所有窗口都关闭后退出，见 [`ExitCondition::OnAllClosed`](crate::prelude::ExitCondition::OnAllClosed)
+pub fn exit_on_all_closed(manager: &mut EntityManager) {
+    let open = manager
+        .component_id::<Window>()
+        .is_some_and(|component_id| !manager.entity_ids_by_id(component_id).is_empty());
+    if !open {
+        manager.write_event(AppExit::Success);
+    }
+}
+
+/// 主窗口关闭后退出，见 [`ExitCondition::OnPrimaryClosed`](crate::prelude::ExitCondition::OnPrimaryClosed)
+pub fn exit_on_primary_closed(manager: &mut EntityManager) {
+    if WindowRef::Primary.get_entity(manager).is_none() {
+        manager.write_event(AppExit::Success);
+    }
+}
+
+// close_when_requested 下一个要处理的事件 ID，事件会在双缓冲中保留两帧，避免重复处理
+#[derive(Default)]
+pub(crate) struct CloseRequestCursor {
+    next_id: usize,
+}
+
+/// 收到 [`WindowCloseRequested`] 时删除窗口实体，窗口后端随后关闭对应的系统窗口。
+///
+/// 每个请求只处理一次，已经没有 [`Window`] 的实体（例如已关闭后 ID 被复用）不会被删除。
+pub fn close_when_requested(manager: &mut EntityManager) {
+    let Some(next_id) = manager.get_resource::<CloseRequestCursor>().map(|cursor| cursor.next_id) else {
+        return;
+    };
+    let Some(events) = manager.get_resource::<Events<WindowCloseRequested>>() else {
+        return;
+    };
+    let mut last_id = None;
+    let mut windows = Vec::new();
+    for (id, event) in events.iter_with_id().filter(|(id, _)| id.id >= next_id) {
+        windows.push(event.window.to_raw());
+        last_id = Some(id.id);
+    }
+    if let (Some(last_id), Some(cursor)) = (last_id, manager.get_resource_mut::<CloseRequestCursor>()) {
+        cursor.next_id = last_id + 1;
+    }
+    for entity_id in windows {
+        if manager.borrow_component::<Window>(entity_id).is_some() {
+            manager.remove_entity(entity_id);
+        }
+    }
+}
@@ -1,4 +1,6 @@
 
+use core::fmt;
+
 use engine_ecs::prelude::*;
 use engine_math::prelude::*;
 
@@ -18,15 +20,53 @@ pub enum WindowRef {
     Entity(Entity),
 }
 
+impl WindowRef {
+    /// 找到引用的窗口实体，窗口不存在时返回 `None`
+    pub fn get_entity(&self, manager: &EntityManager) -> Option<Entity> {
+        match *self {
+            WindowRef::Primary => {
+                let component_id = manager.component_id::<PrimaryWindow>()?;
+                manager
+                    .entity_ids_by_id(component_id)
+                    .iter()
+                    .copied()
+                    .find(|&entity_id| manager.borrow_component::<Window>(entity_id).is_some())
+                    .map(Entity::from_raw)
+            }
+            WindowRef::Entity(entity) => manager
+                .borrow_component::<Window>(entity.to_raw())
+                .map(|_| entity),
+        }
+    }
+}
+
 /// 窗口组件，存储窗口的主要属性
 #[derive(Debug, Clone)]
 pub struct Window {
     pub title: String,
     pub resolution: WindowResolution,
+    /// 窗口创建时的位置
+    pub position: WindowPosition,
+    /// 窗口化或全屏
+    pub mode: WindowMode,
+    /// 交换链的呈现模式，由渲染器创建 surface 时使用
+    pub present_mode: PresentMode,
+    /// 窗口逻辑尺寸的范围
+    pub resize_constraints: WindowResizeConstraints,
     pub resizable: bool,
     pub decorations: bool,
     pub transparent: bool,
     pub visible: bool,
+    /// 是否置顶或置底
+    pub window_level: WindowLevel,
+    /// 创建时是否获取焦点
+    pub focused: bool,
+    /// 窗口主题，为 `None` 时跟随系统
+    pub window_theme: Option<WindowTheme>,
+    /// 窗口图标，为 `None` 时使用系统默认图标
+    pub icon: Option<WindowIcon>,
+    /// 是否启用输入法
+    pub ime_enabled: bool,
 }
 
 impl Default for Window {
@@ -34,10 +74,19 @@ impl Default for Window {
         Self {
             title: "App".to_owned(),
             resolution: Default::default(),
+            position: Default::default(),
+            mode: Default::default(),
+            present_mode: Default::default(),
+            resize_constraints: Default::default(),
             resizable: true,
             decorations: true,
             transparent: false,
             visible: true,
+            window_level: Default::default(),
+            focused: true,
+            window_theme: None,
+            icon: None,
+            ime_enabled: false,
         }
     }
 }
@@ -73,12 +122,15 @@ impl Window {
     }
 }
 
-/// 窗口分辨率
+/// 窗口分辨率，逻辑尺寸等于物理尺寸除以缩放比例
 #[derive(Debug, Clone, PartialEq)]
 pub struct WindowResolution {
     physical_width: u32,
     physical_height: u32,
+    /// 系统提供的缩放比例
     scale_factor: f32,
+    /// 覆盖系统的缩放比例
+    scale_factor_override: Option<f32>,
 }
 
 impl Default for WindowResolution {
@@ -87,16 +139,26 @@ impl Default for WindowResolution {
             physical_width: 1280,
             physical_height: 720,
             scale_factor: 1.0,
+            scale_factor_override: None,
         }
     }
 }
 
 impl WindowResolution {
+    /// 指定物理尺寸，缩放比例为 1
+    pub fn new(physical_width: u32, physical_height: u32) -> Self {
+        WindowResolution {
+            physical_width,
+            physical_height,
+            ..Default::default()
+        }
+    }
+
     pub fn width(&self) -> f32 {
-        self.physical_width as f32 / self.scale_factor
+        self.physical_width as f32 / self.scale_factor()
     }
     pub fn height(&self) -> f32 {
-        self.physical_height as f32 / self.scale_factor
+        self.physical_height as f32 / self.scale_factor()
     }
     pub fn size(&self) -> Vec2 {
         Vec2::new(self.width(), self.height())
@@ -110,4 +172,295 @@ impl WindowResolution {
     pub fn physical_size(&self) -> UVec2 {
         UVec2::new(self.physical_width, self.physical_height)
     }
+
+    /// 实际使用的缩放比例，有覆盖值时使用覆盖值
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor_override.unwrap_or(self.scale_factor)
+    }
+    /// 系统提供的缩放比例
+    pub fn base_scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+    pub fn scale_factor_override(&self) -> Option<f32> {
+        self.scale_factor_override
+    }
+
+    /// 设置逻辑尺寸
+    pub fn set(&mut self, width: f32, height: f32) {
+        self.set_physical_resolution(
+            (width * self.scale_factor()).round() as u32,
+            (height * self.scale_factor()).round() as u32,
+        );
+    }
+
+    /// 设置物理尺寸
+    pub fn set_physical_resolution(&mut self, width: u32, height: u32) {
+        self.physical_width = width;
+        self.physical_height = height;
+    }
+
+    /// 设置系统提供的缩放比例，物理尺寸不变
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
+    /// 设置系统提供的缩放比例，逻辑尺寸不变
+    pub fn set_scale_factor_and_apply_to_physical_size(&mut self, scale_factor: f32) {
+        let size = self.size();
+        self.scale_factor = scale_factor;
+        self.set(size.x, size.y);
+    }
+
+    /// 设置或取消缩放比例的覆盖值，逻辑尺寸不变
+    pub fn set_scale_factor_override(&mut self, scale_factor_override: Option<f32>) {
+        let size = self.size();
+        self.scale_factor_override = scale_factor_override;
+        self.set(size.x, size.y);
+    }
+}
+
+/// 选择显示器
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MonitorSelection {
+    /// 窗口当前所在的显示器，创建窗口时等同于主显示器
+    #[default]
+    Current,
+    Primary,
+    /// 按系统返回的顺序选择
+    Index(usize),
+}
+
+/// 窗口创建时的位置
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WindowPosition {
+    /// 由系统决定
+    #[default]
+    Automatic,
+    /// 显示器中央
+    Centered(MonitorSelection),
+    /// 左上角的物理坐标
+    At(IVec2),
+}
+
+/// 全屏时的分辨率和刷新率
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VideoMode {
+    pub physical_size: UVec2,
+    pub bit_depth: u16,
+    pub refresh_rate_millihertz: u32,
+}
+
+/// 选择全屏时的显示模式
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VideoModeSelection {
+    /// 显示器当前的分辨率和刷新率
+    #[default]
+    Current,
+    /// 最接近的显示模式
+    Specific(VideoMode),
+}
+
+/// 窗口模式
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WindowMode {
+    #[default]
+    Windowed,
+    /// 无边框窗口覆盖整个显示器，不改变显示模式
+    BorderlessFullscreen(MonitorSelection),
+    /// 独占全屏，可以改变分辨率和刷新率
+    Fullscreen(MonitorSelection, VideoModeSelection),
+}
+
+/// 交换链的呈现模式
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PresentMode {
+    /// 开启垂直同步，优先使用 `FifoRelaxed`，不支持时使用 `Fifo`
+    #[default]
+    AutoVsync,
+    /// 关闭垂直同步，优先使用 `Immediate`，然后是 `Mailbox`，最后是 `Fifo`
+    AutoNoVsync,
+    Fifo,
+    FifoRelaxed,
+    Immediate,
+    Mailbox,
+}
+
+/// 窗口逻辑尺寸的范围，最大值可以是无穷大
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowResizeConstraints {
+    pub min_width: f32,
+    pub min_height: f32,
+    pub max_width: f32,
+    pub max_height: f32,
+}
+
+impl Default for WindowResizeConstraints {
+    fn default() -> Self {
+        WindowResizeConstraints {
+            min_width: 180.0,
+            min_height: 120.0,
+            max_width: f32::INFINITY,
+            max_height: f32::INFINITY,
+        }
+    }
+}
+
+impl WindowResizeConstraints {
+    /// 固定的尺寸
+    pub fn fixed(width: f32, height: f32) -> Self {
+        WindowResizeConstraints {
+            min_width: width,
+            min_height: height,
+            max_width: width,
+            max_height: height,
+        }
+    }
+
+    /// 修正无效的范围：最小值至少为 1，最大值不小于最小值
+    pub fn check_constraints(&self) -> Self {
+        let min_width = self.min_width.max(1.0);
+        let min_height = self.min_height.max(1.0);
+        WindowResizeConstraints {
+            min_width,
+            min_height,
+            max_width: self.max_width.max(min_width),
+            max_height: self.max_height.max(min_height),
+        }
+    }
+}
+
+/// 窗口层级
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WindowLevel {
+    AlwaysOnBottom,
+    #[default]
+    Normal,
+    AlwaysOnTop,
+}
+
+/// 窗口主题
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowTheme {
+    Light,
+    Dark,
+}
+
+/// 窗口图标，像素格式为 RGBA8
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowIcon {
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+/// 创建 [`WindowIcon`] 时的错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowIconError {
+    /// 数据长度不是 4 的倍数
+    ByteCountNotDivisibleBy4 { byte_count: usize },
+    /// 像素数与尺寸不一致
+    DimensionsMismatch { width: u32, height: u32, pixel_count: usize },
+}
+
+impl fmt::Display for WindowIconError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowIconError::ByteCountNotDivisibleBy4 { byte_count } => {
+                write!(f, "Icon data has {} bytes, which is not divisible by 4", byte_count)
+            }
+            WindowIconError::DimensionsMismatch {
+                width,
+                height,
+                pixel_count,
+            } => write!(
+                f,
+                "Icon is {}x{} but has {} pixels",
+                width, height, pixel_count
+            ),
+        }
+    }
+}
+
+impl core::error::Error for WindowIconError {}
+
+impl WindowIcon {
+    pub fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Result<Self, WindowIconError> {
+        if !rgba.len().is_multiple_of(4) {
+            return Err(WindowIconError::ByteCountNotDivisibleBy4 { byte_count: rgba.len() });
+        }
+        let pixel_count = rgba.len() / 4;
+        if pixel_count != width as usize * height as usize {
+            return Err(WindowIconError::DimensionsMismatch {
+                width,
+                height,
+                pixel_count,
+            });
+        }
+        Ok(WindowIcon { rgba, width, height })
+    }
+
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resize_constraints() {
+        let constraints = WindowResizeConstraints {
+            min_width: 0.0,
+            min_height: 600.0,
+            max_width: 800.0,
+            max_height: 300.0,
+        }
+        .check_constraints();
+        assert_eq!(constraints.min_width, 1.0);
+        assert_eq!(constraints.max_width, 800.0);
+        assert_eq!(constraints.max_height, 600.0);
+    }
+
+    #[test]
+    fn test_scale_factor_override() {
+        let mut resolution = WindowResolution::new(800, 600);
+        resolution.set_scale_factor(2.0);
+        assert_eq!(resolution.size(), Vec2::new(400.0, 300.0));
+
+        // 覆盖缩放比例时保持逻辑尺寸，系统缩放比例的变化不再影响逻辑尺寸
+        resolution.set_scale_factor_override(Some(1.0));
+        assert_eq!(resolution.physical_size(), UVec2::new(400, 300));
+        resolution.set_scale_factor(3.0);
+        assert_eq!(resolution.size(), Vec2::new(400.0, 300.0));
+
+        resolution.set_scale_factor_override(None);
+        assert_eq!(resolution.physical_size(), UVec2::new(1200, 900));
+        assert_eq!(resolution.base_scale_factor(), 3.0);
+    }
+
+    #[test]
+    fn test_window_icon() {
+        assert!(WindowIcon::from_rgba(vec![0; 16], 2, 2).is_ok());
+        assert_eq!(
+            WindowIcon::from_rgba(vec![0; 6], 1, 1),
+            Err(WindowIconError::ByteCountNotDivisibleBy4 { byte_count: 6 })
+        );
+        assert_eq!(
+            WindowIcon::from_rgba(vec![0; 12], 2, 2),
+            Err(WindowIconError::DimensionsMismatch {
+                width: 2,
+                height: 2,
+                pixel_count: 3
+            })
+        );
+    }
 }
@@ -0,0 +1,163 @@
+use engine_window::prelude::*;
+use tracing::warn;
+use winit::dpi::{LogicalSize, PhysicalPosition};
+use winit::event_loop::ActiveEventLoop;
+use winit::monitor::{MonitorHandle, VideoModeHandle};
+use winit::window::{Fullscreen, Icon, Theme, Window as WinitWindow, WindowLevel as WinitWindowLevel};
+
+/// 可以查询显示器的对象：创建窗口时是事件循环，之后是窗口本身
+pub trait MonitorSource {
+    fn primary_monitor(&self) -> Option<MonitorHandle>;
+    fn available_monitors(&self) -> impl Iterator<Item = MonitorHandle>;
+    fn current_monitor(&self) -> Option<MonitorHandle>;
+}
+
+impl MonitorSource for ActiveEventLoop {
+    fn primary_monitor(&self) -> Option<MonitorHandle> {
+        ActiveEventLoop::primary_monitor(self)
+    }
+
+    fn available_monitors(&self) -> impl Iterator<Item = MonitorHandle> {
+        ActiveEventLoop::available_monitors(self)
+    }
+
+    // 窗口还没有创建
+    fn current_monitor(&self) -> Option<MonitorHandle> {
+        None
+    }
+}
+
+impl MonitorSource for WinitWindow {
+    fn primary_monitor(&self) -> Option<MonitorHandle> {
+        WinitWindow::primary_monitor(self)
+    }
+
+    fn available_monitors(&self) -> impl Iterator<Item = MonitorHandle> {
+        WinitWindow::available_monitors(self)
+    }
+
+    fn current_monitor(&self) -> Option<MonitorHandle> {
+        WinitWindow::current_monitor(self)
+    }
+}
+
+/// 按 [`MonitorSelection`] 选择显示器，没有当前显示器时 `Current` 等同于主显示器
+pub fn select_monitor(source: &impl MonitorSource, selection: MonitorSelection) -> Option<MonitorHandle> {
+    let primary = || source.primary_monitor().or_else(|| source.available_monitors().next());
+    match selection {
+        MonitorSelection::Current => source.current_monitor().or_else(primary),
+        MonitorSelection::Primary => primary(),
+        MonitorSelection::Index(index) => source.available_monitors().nth(index),
+    }
+}
+
+/// 选择最接近的显示模式：优先匹配分辨率，其次是刷新率和色深
+fn select_video_mode(monitor: &MonitorHandle, selection: VideoModeSelection) -> Option<VideoModeHandle> {
+    let modes = monitor.video_modes();
+    match selection {
+        VideoModeSelection::Current => {
+            let size = monitor.size();
+            let refresh_rate = monitor.refresh_rate_millihertz();
+            modes
+                .filter(|mode| mode.size() == size)
+                .max_by_key(|mode| {
+                    let same_refresh_rate = Some(mode.refresh_rate_millihertz()) == refresh_rate;
+                    (same_refresh_rate, mode.bit_depth())
+                })
+        }
+        VideoModeSelection::Specific(target) => modes.min_by_key(|mode| {
+            let size = mode.size();
+            (
+                size.width.abs_diff(target.physical_size.x) + size.height.abs_diff(target.physical_size.y),
+                mode.refresh_rate_millihertz().abs_diff(target.refresh_rate_millihertz),
+                mode.bit_depth().abs_diff(target.bit_depth),
+            )
+        }),
+    }
+}
+
+/// 把 [`WindowMode`] 转换为 winit 的全屏模式，找不到合适的显示模式时退回无边框全屏
+pub fn convert_window_mode(source: &impl MonitorSource, mode: WindowMode) -> Option<Fullscreen> {
+    match mode {
+        WindowMode::Windowed => None,
+        WindowMode::BorderlessFullscreen(selection) => {
+            Some(Fullscreen::Borderless(select_monitor(source, selection)))
+        }
+        WindowMode::Fullscreen(selection, video_mode) => {
+            let monitor = select_monitor(source, selection);
+            match monitor.as_ref().and_then(|monitor| select_video_mode(monitor, video_mode)) {
+                Some(video_mode) => Some(Fullscreen::Exclusive(video_mode)),
+                None => {
+                    warn!("找不到显示模式 {:?}，使用无边框全屏", video_mode);
+                    Some(Fullscreen::Borderless(monitor))
+                }
+            }
+        }
+    }
+}
+
+/// 计算窗口创建时的物理坐标，`Automatic` 或找不到显示器时返回 `None`
+pub fn convert_window_position(
+    source: &impl MonitorSource,
+    position: WindowPosition,
+    window: &Window,
+) -> Option<PhysicalPosition<i32>> {
+    match position {
+        WindowPosition::Automatic => None,
+        WindowPosition::At(position) => Some(PhysicalPosition::new(position.x, position.y)),
+        WindowPosition::Centered(selection) => {
+            let monitor = select_monitor(source, selection)?;
+            let origin = monitor.position();
+            let monitor_size = monitor.size();
+            // 窗口尺寸以逻辑像素为准，按显示器的缩放比例换算
+            let scale_factor = monitor.scale_factor();
+            let width = (window.width() as f64 * scale_factor) as i32;
+            let height = (window.height() as f64 * scale_factor) as i32;
+            Some(PhysicalPosition::new(
+                origin.x + (monitor_size.width as i32 - width) / 2,
+                origin.y + (monitor_size.height as i32 - height) / 2,
+            ))
+        }
+    }
+}
+
+/// 最小和最大逻辑尺寸，宽高都为无穷大时不限制最大值
+pub fn convert_resize_constraints(
+    constraints: &WindowResizeConstraints,
+) -> (LogicalSize<f32>, Option<LogicalSize<f32>>) {
+    let constraints = constraints.check_constraints();
+    let min = LogicalSize::new(constraints.min_width, constraints.min_height);
+    let max = (constraints.max_width.is_finite() || constraints.max_height.is_finite()).then(|| {
+        LogicalSize::new(constraints.max_width.min(f32::MAX), constraints.max_height.min(f32::MAX))
+    });
+    (min, max)
+}
+
+pub fn convert_window_level(level: WindowLevel) -> WinitWindowLevel {
+    match level {
+        WindowLevel::AlwaysOnBottom => WinitWindowLevel::AlwaysOnBottom,
+        WindowLevel::Normal => WinitWindowLevel::Normal,
+        WindowLevel::AlwaysOnTop => WinitWindowLevel::AlwaysOnTop,
+    }
+}
+
+pub fn convert_window_theme(theme: WindowTheme) -> Theme {
+    match theme {
+        WindowTheme::Light => Theme::Light,
+        WindowTheme::Dark => Theme::Dark,
+    }
+}
+
+pub fn convert_winit_theme(theme: Theme) -> WindowTheme {
+    match theme {
+        Theme::Light => WindowTheme::Light,
+        Theme::Dark => WindowTheme::Dark,
+    }
+}
+
+/// [`WindowIcon`] 在创建时已经校验过，这里只会因为 winit 的额外限制失败
+pub fn convert_window_icon(icon: &WindowIcon) -> Option<Icon> {
+    Icon::from_rgba(icon.rgba().to_vec(), icon.width(), icon.height())
+        .map_err(|err| warn!("无法设置窗口图标: {}", err))
+        .ok()
+}
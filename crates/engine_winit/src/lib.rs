@@ -2,10 +2,14 @@ use std::{cell::RefCell, marker::PhantomData, time::Instant};
 use engine_app::prelude::*;
 use engine_ecs::prelude::*;
 use engine_window::prelude::*;
-use winit::{application::ApplicationHandler, event::{StartCause, WindowEvent}, event_loop::{ActiveEventLoop, EventLoop, EventLoopProxy}, window::{WindowId, Window}};
+use tracing::{debug, error, info, trace, warn};
+use winit::{application::ApplicationHandler, dpi::PhysicalSize, event::{StartCause, WindowEvent}, event_loop::{ActiveEventLoop, EventLoop, EventLoopProxy}, window::{WindowId, Window}};
 
-use crate::{winit_windows::WinitWindows};
+use engine_math::prelude::*;
 
+use crate::{converters::convert_winit_theme, system::{changed_windows, CachedWindow, SyncedWindowsFrame}, winit_windows::WinitWindows};
+
+mod converters;
 mod winit_windows;
 mod system;
 
@@ -73,21 +77,99 @@ impl<T: BufferedEvent> ApplicationHandler<T> for WinitAppRunnerState<T> {
         };
     }
 
-    fn window_event(&mut self, event_loop: &ActiveEventLoop, window_id: WindowId, event: WindowEvent) {
-        println!("window event: {:?}", event);
+    fn window_event(&mut self, _event_loop: &ActiveEventLoop, window_id: WindowId, event: WindowEvent) {
+        trace!("window event: {:?}", event);
 
-        WINIT_WINDOWS.with_borrow(|winit_windows| {
-            let Some(window) = winit_windows.get_window_entity(window_id) else {
-                println!("无法找到与窗口 ID {:?} 关联的实体", window_id);
-                return;
-            };
-            println!("{:?}", window);
-        });
+        let Some(window) = WINIT_WINDOWS.with_borrow(|winit_windows| winit_windows.get_window_entity(window_id)) else {
+            warn!("无法找到与窗口 ID {:?} 关联的实体", window_id);
+            return;
+        };
+        // 已关闭的窗口只处理 Destroyed，实体 ID 可能已被新的窗口复用
+        if WINIT_WINDOWS.with_borrow(|winit_windows| winit_windows.is_closing(window_id))
+            && !matches!(event, WindowEvent::Destroyed)
+        {
+            return;
+        }
 
         match event {
+            WindowEvent::Resized(size) => {
+                let mut logical_size = None;
+                self.update_window(window, |component| {
+                    component.resolution.set_physical_resolution(size.width, size.height);
+                    logical_size = Some(component.size());
+                });
+                if let Some(logical_size) = logical_size {
+                    self.write_event(WindowResized {
+                        window,
+                        width: logical_size.x,
+                        height: logical_size.y,
+                    });
+                }
+            }
+            WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                mut inner_size_writer,
+            } => {
+                let mut requested_size = None;
+                let mut prior_factor = None;
+                self.update_window(window, |component| {
+                    prior_factor.get_or_insert(component.resolution.scale_factor());
+                    component.resolution.set_scale_factor(scale_factor as f32);
+                    // 覆盖缩放比例时保持原来的物理尺寸，否则使用系统建议的尺寸并在 Resized 中更新
+                    if component.resolution.scale_factor_override().is_some() {
+                        requested_size = Some(PhysicalSize::new(component.physical_width(), component.physical_height()));
+                    }
+                });
+                if let Some(size) = requested_size
+                    && let Err(err) = inner_size_writer.request_inner_size(size)
+                {
+                    warn!("无法保持窗口尺寸: {}", err);
+                }
+                self.write_event(WindowBackendScaleFactorChanged { window, scale_factor });
+                if requested_size.is_none() && prior_factor != Some(scale_factor as f32) {
+                    self.write_event(WindowScaleFactorChanged { window, scale_factor });
+                }
+            }
+            WindowEvent::Moved(position) => {
+                let position = IVec2::new(position.x, position.y);
+                self.update_window(window, |component| component.position = WindowPosition::At(position));
+                self.write_event(WindowMoved { window, position });
+            }
+            WindowEvent::Focused(focused) => {
+                self.update_window(window, |component| component.focused = focused);
+                self.write_event(WindowFocused { window, focused });
+            }
+            WindowEvent::ThemeChanged(theme) => {
+                let theme = convert_winit_theme(theme);
+                self.update_window(window, |component| component.window_theme = Some(theme));
+                self.write_event(WindowThemeChanged { window, theme });
+            }
+            WindowEvent::Occluded(occluded) => {
+                self.write_event(WindowOccluded { window, occluded });
+            }
+            WindowEvent::CursorEntered { .. } => {
+                self.write_event(CursorEntered { window });
+            }
+            WindowEvent::CursorLeft { .. } => {
+                self.write_event(CursorLeft { window });
+            }
+            WindowEvent::DroppedFile(path_buf) => {
+                self.write_event(FileDragAndDrop::DroppedFile { window, path_buf });
+            }
+            WindowEvent::HoveredFile(path_buf) => {
+                self.write_event(FileDragAndDrop::HoveredFile { window, path_buf });
+            }
+            WindowEvent::HoveredFileCancelled => {
+                self.write_event(FileDragAndDrop::HoveredFileCanceled { window });
+            }
+            WindowEvent::Destroyed => {
+                WINIT_WINDOWS.with_borrow_mut(|winit_windows| winit_windows.remove_destroyed(window_id));
+                self.write_event(WindowDestroyed { window });
+            }
+            // 由 WindowPlugin 决定是否删除窗口实体以及是否退出
             WindowEvent::CloseRequested => {
-                println!("Test window close requested");
-                event_loop.exit();
+                info!("Window close requested");
+                self.write_event(WindowCloseRequested { window });
             }
             WindowEvent::RedrawRequested => {
                 // 标记需要重绘
@@ -106,16 +188,48 @@ impl<T: BufferedEvent> ApplicationHandler<T> for WinitAppRunnerState<T> {
     }
 
     fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
-        println!("about_to_wait called, wait_elapsed: {}", self.wait_elapsed);
+        trace!("about_to_wait called, wait_elapsed: {}", self.wait_elapsed);
 
         self.create_pending_windows(event_loop);
 
+        if self.app.plugins_state() == PluginsState::Ready {
+            self.app.finish();
+            self.app.cleanup();
+        }
+
+        // 运行一帧，系统写入的 AppExit 会在本帧结束时处理
+        if let AppExit::Error(code) = self.app.run_once() {
+            self.exit(event_loop, AppExit::Error(code));
+            return;
+        }
+
+        self.despawn_windows();
+
         #[cfg(not(target_os = "windows"))]
         self.redraw_requested(event_loop);
+
+        if let Some(app_exit) = self.app.should_exit() {
+            self.exit(event_loop, app_exit);
+        }
     }
 }
 
 impl<T: BufferedEvent> WinitAppRunnerState<T> {
+    // 把 winit 报告的变化同时写入 Window 和 CachedWindow，避免 changed_windows 再写回 winit
+    fn update_window(&mut self, entity: Entity, mut f: impl FnMut(&mut engine_window::prelude::Window)) {
+        let manager = self.app.world_mut().entity_manager_mut();
+        if let Some((window, cache)) =
+            manager.borrow_component_pair_mut::<engine_window::prelude::Window, CachedWindow>(entity.to_raw())
+        {
+            f(window);
+            f(&mut cache.0);
+        }
+    }
+
+    fn write_event<E: BufferedEvent>(&mut self, event: E) {
+        self.app.world_mut().entity_manager_mut().write_event(event);
+    }
+
     fn create_pending_windows(&mut self, event_loop: &ActiveEventLoop) {
 
         let world = self.app.world_mut();
@@ -139,24 +253,63 @@ impl<T: BufferedEvent> WinitAppRunnerState<T> {
         }
 
 
-        // 创建窗口
+        // 创建窗口，并用 winit 窗口实际的尺寸和缩放比例更新组件
         WINIT_WINDOWS.with_borrow_mut(|winit_windows| {
-            for (entity_id, window) in &windows_to_create {
+            for (entity_id, window) in &mut windows_to_create {
                 let entity = Entity::from_raw(*entity_id);
 
-                println!("Creating window '{}' for entity {}", window.title, entity_id);
+                debug!("Creating window '{}' for entity {}", window.title, entity_id);
 
-                winit_windows.create_window(event_loop, entity, window);
+                let winit_window = winit_windows.create_window(event_loop, entity, window);
+                let size = winit_window.inner_size();
+                window.resolution.set_scale_factor(winit_window.scale_factor() as f32);
+                window.resolution.set_physical_resolution(size.width, size.height);
             }
         });
 
         // 第四步：标记已创建的窗口
-        for (entity_id, _) in windows_to_create {
+        for (entity_id, window) in windows_to_create {
+            if let Some(component) = world.entity_manager_mut().borrow_component_mut::<engine_window::prelude::Window>(entity_id) {
+                component.resolution = window.resolution.clone();
+            }
+            world.add_component_to_entity(entity_id, CachedWindow(window));
             world.add_component_to_entity(entity_id, WinitWindowCreated);
         }
 
     }
 
+    // 关闭 Window 组件已经被删除的 winit 窗口，必须在 create_pending_windows 之前运行。
+    //
+    // 实体 ID 会被复用：同一帧内删除窗口实体后再生成的新窗口可能得到相同的 ID，
+    // 它有 Window 但没有 WinitWindowCreated（remove_entity 会删除它），旧的 winit 窗口同样需要关闭
+    fn despawn_windows(&mut self) {
+        let world = self.app.world_mut();
+        let closed = WINIT_WINDOWS.with_borrow_mut(|winit_windows| {
+            let closed: Vec<Entity> = winit_windows
+                .entity_to_winit
+                .keys()
+                .copied()
+                .filter(|entity| {
+                    !world.has_component::<engine_window::prelude::Window>(entity.to_raw())
+                        || !world.has_component::<WinitWindowCreated>(entity.to_raw())
+                })
+                .collect();
+            for entity in &closed {
+                debug!("Closing window for entity {}", entity.to_raw());
+                winit_windows.remove_window(*entity);
+            }
+            closed
+        });
+
+        let manager = world.entity_manager_mut();
+        for window in closed {
+            // 只删除了 Window 组件时实体还在，之后再添加 Window 会重新创建窗口，复用 ID 的新窗口也会在之后创建
+            manager.remove_component_from_entity::<CachedWindow>(window.to_raw());
+            manager.remove_component_from_entity::<WinitWindowCreated>(window.to_raw());
+            manager.write_event(WindowClosed { window });
+        }
+    }
+
     fn redraw_requested(&mut self, event_loop: &ActiveEventLoop) {
         if self.redraw_requested && self.lifecycle != AppLifecycle::Suspended {
             WINIT_WINDOWS.with_borrow(|winit_windows| {
@@ -166,12 +319,14 @@ impl<T: BufferedEvent> WinitAppRunnerState<T> {
             });
             self.redraw_requested = false;
         }
+    }
 
-        if let Some(app_exit) = self.app.should_exit() {
+    // 记录第一次退出请求并停止事件循环
+    fn exit(&mut self, event_loop: &ActiveEventLoop, app_exit: AppExit) {
+        if self.app_exit.is_none() {
             self.app_exit = Some(app_exit);
-
-            event_loop.exit();
         }
+        event_loop.exit();
     }
 }
 
@@ -192,10 +347,10 @@ pub fn winit_runner<T: BufferedEvent>(mut app: App, event_loop: EventLoop<T>) ->
 
     let mut runner_state = runner_state;
     if let Err(err) = event_loop.run_app(&mut runner_state) {
-        eprintln!("事件循环运行失败: {:?}", err);
-        AppExit::Error(1)
+        error!("事件循环运行失败: {:?}", err);
+        AppExit::error()
     } else {
-        AppExit::Success
+        runner_state.app_exit.unwrap_or(AppExit::Success)
     }
 }
 
@@ -216,13 +371,17 @@ impl<T: BufferedEvent> Plugin for WinitPlugin<T> {
     fn build(&self, app: &mut App) {
 
             // 自动注册 WinitWindowCreated 组件
-            app.world_mut().register_component::<WinitWindowCreated>();
+            app.world_mut()
+                .register_component::<WinitWindowCreated>()
+                .register_component::<CachedWindow>()
+                .add_resource(SyncedWindowsFrame::default());
+            app.add_systems(Last, changed_windows);
 
             let mut event_loop_builder = EventLoop::<T>::with_user_event();
 
             #[cfg(all(target_os = "linux", feature = "x11"))]
             {
-                println!("启用 X11 支持: {}", self.run_on_any_thread);
+                debug!("启用 X11 支持: {}", self.run_on_any_thread);
                 #[allow(unused_imports)]
                 use winit::platform::x11::EventLoopBuilderExtX11;
                 event_loop_builder.with_any_thread(self.run_on_any_thread);
@@ -230,7 +389,7 @@ impl<T: BufferedEvent> Plugin for WinitPlugin<T> {
 
             #[cfg(target_os = "macos")]
             {
-                println!("启用 macOS 支持: {}", self.run_on_any_thread);
+                debug!("启用 macOS 支持: {}", self.run_on_any_thread);
                 use winit::platform::macos::ActivationPolicy;
                 #[allow(unused_imports)]
                 use winit::platform::macos::EventLoopBuilderExtMacOS;
@@ -250,6 +409,7 @@ impl<T: BufferedEvent> Plugin for WinitPlugin<T> {
 
 pub mod prelude {
     pub use super::winit_windows::*;
+    pub use super::system::{changed_windows, CachedWindow};
     pub use super::WinitPlugin;
     pub use super::WakeUp;
 
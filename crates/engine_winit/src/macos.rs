@@ -5,6 +5,7 @@ use objc2::runtime::ProtocolObject;
 use objc2::{declare_class, msg_send_id, mutability, ClassType, DeclaredClass};
 use objc2_app_kit::{NSApplication, NSApplicationDelegate};
 use objc2_foundation::{NSArray, NSURL, MainThreadMarker, NSObject, NSObjectProtocol};
+use tracing::{debug, warn};
 use winit::event_loop::EventLoop;
 
 declare_class!(
@@ -25,7 +26,7 @@ declare_class!(
         fn application_openURLs(&self, application: &NSApplication, urls: &NSArray<NSURL>) {
             // Note: To specifically get `application:openURLs:` to work, you _might_
             // have to bundle your application. This is not done in this example.
-            println!("open urls: {application:?}, {urls:?}");
+            debug!("open urls: {application:?}, {urls:?}");
         }
     }
 );
@@ -52,9 +53,9 @@ pub fn setup_app_delegate() {
                 app.setActivationPolicy(NSApplicationActivationPolicy::Regular);
             }
 
-            println!("macOS 应用委托设置完成");
+            debug!("macOS 应用委托设置完成");
         } else {
-            eprintln!("警告: 无法获取主线程标记，macOS 应用委托设置失败");
+            warn!("无法获取主线程标记，macOS 应用委托设置失败");
         }
     });
 }
@@ -64,7 +65,7 @@ pub fn configure_window_for_macos() {
     // 设置 macOS 特定的窗口属性
     use winit::platform::macos::WindowExtMacOS;
 
-    println!("macOS 窗口配置完成");
+    debug!("macOS 窗口配置完成");
 }
 
 /// 检查是否在 macOS 平台运行
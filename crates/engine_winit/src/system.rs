@@ -1,3 +1,153 @@
+use engine_ecs::prelude::*;
+use engine_window::prelude::*;
+use winit::dpi::PhysicalSize;
+use winit::window::Window as WinitWindow;
+
+use crate::converters::*;
+use crate::WINIT_WINDOWS;
+
+/// 上一次与 winit 窗口同步时的 [`Window`]，创建 winit 窗口时添加。
+///
+/// [`changed_windows`] 只把与它不同的字段写入 winit 窗口；winit 事件同时更新 [`Window`] 和它，
+/// 因此系统引起的变化不会再被写回 winit。
+#[derive(Debug, Clone)]
+pub struct CachedWindow(pub Window);
+
+impl Component for CachedWindow {}
+
+/// [`changed_windows`] 上一次同步之后 [`Window`] 组件的变更帧
+#[derive(Debug, Default)]
+pub(crate) struct SyncedWindowsFrame(Option<u64>);
+
+/// 把 [`Window`] 组件的修改写入对应的 winit 窗口。
+///
+/// 没有任何 [`Window`] 被可变借用时直接跳过。变更帧只记录到组件类型，
+/// 不知道是哪个窗口的哪个字段被修改，所以仍然需要逐个与 [`CachedWindow`] 比较。
+pub fn changed_windows(manager: &mut EntityManager) {
+    let Some(component_id) = manager.component_id::<Window>() else {
+        return;
+    };
+    let changed_frame = manager.component_changed_frame(component_id);
+    if manager
+        .get_resource::<SyncedWindowsFrame>()
+        .is_some_and(|synced| synced.0 == changed_frame)
+    {
+        return;
+    }
+    let entity_ids = manager.entity_ids_by_id(component_id).to_vec();
+    WINIT_WINDOWS.with_borrow(|winit_windows| {
+        for entity_id in entity_ids {
+            let Some(winit_window) = winit_windows.get_window(Entity::from_raw(entity_id)) else {
+                continue;
+            };
+            let Some((window, cache)) = manager.borrow_component_pair_mut::<Window, CachedWindow>(entity_id) else {
+                continue;
+            };
+            if sync_window(window, &cache.0, winit_window) {
+                cache.0 = window.clone();
+            }
+        }
+    });
+    // 上面的可变借用也会更新变更帧，记录借用之后的值
+    let changed_frame = manager.component_changed_frame(component_id);
+    if let Some(synced) = manager.get_resource_mut::<SyncedWindowsFrame>() {
+        synced.0 = changed_frame;
+    }
+}
+
+// 返回是否有字段发生变化
+fn sync_window(window: &mut Window, cache: &Window, winit_window: &WinitWindow) -> bool {
+    let mut changed = false;
+
+    if window.title != cache.title {
+        winit_window.set_title(&window.title);
+        changed = true;
+    }
+
+    if window.resolution != cache.resolution {
+        if window.resolution.physical_size() != cache.resolution.physical_size() {
+            let size = PhysicalSize::new(window.physical_width(), window.physical_height());
+            // 立即生效时返回实际的尺寸，否则之后通过 Resized 事件更新
+            if let Some(size) = winit_window.request_inner_size(size) {
+                window.resolution.set_physical_resolution(size.width, size.height);
+            }
+        }
+        changed = true;
+    }
+
+    if window.position != cache.position {
+        if let Some(position) = convert_window_position(winit_window, window.position, window) {
+            winit_window.set_outer_position(position);
+        }
+        changed = true;
+    }
+
+    if window.mode != cache.mode {
+        winit_window.set_fullscreen(convert_window_mode(winit_window, window.mode));
+        changed = true;
+    }
+
+    if window.resize_constraints != cache.resize_constraints {
+        let (min_inner_size, max_inner_size) = convert_resize_constraints(&window.resize_constraints);
+        winit_window.set_min_inner_size(Some(min_inner_size));
+        winit_window.set_max_inner_size(max_inner_size);
+        changed = true;
+    }
+
+    if window.resizable != cache.resizable {
+        winit_window.set_resizable(window.resizable);
+        changed = true;
+    }
+
+    if window.decorations != cache.decorations {
+        winit_window.set_decorations(window.decorations);
+        changed = true;
+    }
+
+    if window.transparent != cache.transparent {
+        winit_window.set_transparent(window.transparent);
+        changed = true;
+    }
+
+    if window.visible != cache.visible {
+        winit_window.set_visible(window.visible);
+        changed = true;
+    }
+
+    if window.window_level != cache.window_level {
+        winit_window.set_window_level(convert_window_level(window.window_level));
+        changed = true;
+    }
+
+    // 系统不能让窗口失去焦点，只处理获取焦点
+    if window.focused != cache.focused {
+        if window.focused {
+            winit_window.focus_window();
+        }
+        changed = true;
+    }
+
+    if window.window_theme != cache.window_theme {
+        winit_window.set_theme(window.window_theme.map(convert_window_theme));
+        changed = true;
+    }
+
+    if window.icon != cache.icon {
+        winit_window.set_window_icon(window.icon.as_ref().and_then(convert_window_icon));
+        changed = true;
+    }
+
+    if window.ime_enabled != cache.ime_enabled {
+        winit_window.set_ime_allowed(window.ime_enabled);
+        changed = true;
+    }
+
+    // present_mode 由渲染器读取
+    changed |= window.present_mode != cache.present_mode;
+
+    changed
+}
+
 // pub fn create_windows<F: QueryFilter + 'static>(
 //     event_loop: &ActiveEventLoop,
 //     (
@@ -6,6 +6,8 @@ use winit::window::{Window as WinitWindow, WindowId};
 use winit::dpi::LogicalSize;
 use winit::event_loop::ActiveEventLoop;
 
+use crate::converters::*;
+
 /// 管理 Winit 窗口与实体之间的映射关系
 #[derive(Debug, Default)]
 pub struct WinitWindows {
@@ -39,11 +41,34 @@ impl WinitWindows {
 
         winit_window_attributes = winit_window_attributes
             .with_title(&window.title)
-            .with_inner_size(LogicalSize::new(window.width(), window.height()))
             .with_visible(false) // 先设为不可见，创建完成后再显示
             .with_resizable(window.resizable)
             .with_decorations(window.decorations)
-            .with_transparent(window.transparent);
+            .with_transparent(window.transparent)
+            .with_fullscreen(convert_window_mode(event_loop, window.mode))
+            .with_window_level(convert_window_level(window.window_level))
+            .with_active(window.focused)
+            .with_theme(window.window_theme.map(convert_window_theme))
+            .with_window_icon(window.icon.as_ref().and_then(convert_window_icon));
+
+        // 覆盖缩放比例时按覆盖值换算物理尺寸，否则由系统的缩放比例决定
+        let logical_size = LogicalSize::new(window.width(), window.height());
+        winit_window_attributes = match window.resolution.scale_factor_override() {
+            Some(scale_factor) => {
+                winit_window_attributes.with_inner_size(logical_size.to_physical::<u32>(scale_factor as f64))
+            }
+            None => winit_window_attributes.with_inner_size(logical_size),
+        };
+
+        if let Some(position) = convert_window_position(event_loop, window.position, window) {
+            winit_window_attributes = winit_window_attributes.with_position(position);
+        }
+
+        let (min_inner_size, max_inner_size) = convert_resize_constraints(&window.resize_constraints);
+        winit_window_attributes = winit_window_attributes.with_min_inner_size(min_inner_size);
+        if let Some(max_inner_size) = max_inner_size {
+            winit_window_attributes = winit_window_attributes.with_max_inner_size(max_inner_size);
+        }
 
         #[cfg(target_os = "macos")]
         {
@@ -67,6 +92,7 @@ impl WinitWindows {
 
         // 设置可见性
         winit_window.set_visible(window.visible);
+        winit_window.set_ime_allowed(window.ime_enabled);
 
         let window_id = winit_window.id();
 
@@ -99,12 +125,21 @@ impl WinitWindows {
         self.winit_to_entity.get(&winit_id).copied()
     }
 
-    /// 移除窗口及其所有映射关系
+    /// 移除窗口，保留窗口 ID 到实体的映射直到收到 `Destroyed` 事件，见 [`WinitWindows::remove_destroyed`]
     pub fn remove_window(&mut self, entity: Entity) -> Option<WindowWrapper<WinitWindow>> {
         let winit_id = self.entity_to_winit.remove(&entity)?;
-        self.winit_to_entity.remove(&winit_id);
         self.windows.remove(&winit_id)
     }
 
+    /// 窗口是否已经通过 [`WinitWindows::remove_window`] 关闭，但还没有收到 `Destroyed` 事件
+    pub fn is_closing(&self, winit_id: WindowId) -> bool {
+        self.winit_to_entity.contains_key(&winit_id) && !self.windows.contains_key(&winit_id)
+    }
+
+    /// 收到 `Destroyed` 事件后移除窗口 ID 到实体的映射
+    pub fn remove_destroyed(&mut self, winit_id: WindowId) -> Option<Entity> {
+        self.winit_to_entity.remove(&winit_id)
+    }
+
 
 }
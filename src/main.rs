@@ -19,24 +19,18 @@ struct Test {
     b: String,
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+fn main() -> AppExit {
     let mut app = App::new();
 
-    app.add_plugins(MinimalPlugins);
-
-    // 注册组件
-    app.world_mut().register_component::<Window>(); 
-    // 创建实体
-    let window_id = app.world_mut().create_entity();
-    // 创建组件
-    let window = Window {
-        title: "This is window 0!".to_string(),
+    app.add_plugin(LogPlugin::default());
+    // 窗口大小等配置可以在 engine.toml 中修改，或者使用 `--set window.width=800`
+    app.add_plugins::<()>(MinimalPlugins.set(WindowPlugin {
+        primary_window: WindowSettings {
+            title: "This is window 0!".to_string(),
+            ..Default::default()
+        },
         ..Default::default()
-    };
-    // 添加组件到实体
-    app.world_mut().add_component_to_entity(window_id, window);
-
-    app.run();
+    }));
 
-    Ok(())
+    app.run()
 }
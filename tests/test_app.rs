@@ -1,6 +1,5 @@
-use std::marker::PhantomData;
-
 use engine_internal::prelude::*;
+use engine_test::prelude::*;
 
 pub struct HelloWorldSystem;
 
@@ -20,13 +19,10 @@ impl Plugin for HelloWorldPlugin {
 }
 
 
-fn create_test_app() -> App {
-    let mut app = App::new();
+fn create_test_app() -> TestApp {
+    let mut app = TestApp::new();
 
     app.add_plugin(HelloWorldPlugin);
-    let mut winit_plugin = WinitPlugin::<WakeUp>::default();
-    winit_plugin.run_on_any_thread = true;
-    app.add_plugin(winit_plugin);
 
     // 注册组件
     app.world_mut().register_component::<Window>(); 
@@ -47,10 +43,12 @@ fn create_test_app() -> App {
 fn test_window_title() {
     let mut app = create_test_app();
 
-    app.run();
+    // 无头窗口后端，逐帧运行不会阻塞
+    app.update_frames(2);
 
     let window: Vec<&Window> = app.world_mut().query::<Window>();
-    let window: &Window = *window.first().unwrap();
+    let window: &Window = window.first().unwrap();
 
     assert_eq!(window.title, "This is window 0!");
-}
\ No newline at end of file
+    assert_eq!(app.world().get_resource::<HeadlessWindows>().unwrap().open_windows().len(), 1);
+}